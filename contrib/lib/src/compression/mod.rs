@@ -31,7 +31,7 @@ pub use self::responder::Compress;
 
 use std::io::Read;
 
-use rocket::http::MediaType;
+use rocket::http::{MediaType, AcceptEncoding};
 use rocket::http::hyper::header::{ContentEncoding, Encoding};
 use rocket::{Request, Response};
 
@@ -44,13 +44,27 @@ use flate2::read::GzEncoder;
 struct CompressionUtils;
 
 impl CompressionUtils {
-    fn accepts_encoding(request: &Request<'_>, encoding: &str) -> bool {
-        request
-            .headers()
-            .get("Accept-Encoding")
-            .flat_map(|accept| accept.split(','))
-            .map(|accept| accept.trim())
-            .any(|accept| accept == encoding)
+    /// Returns the enabled encoding most preferred by the request's
+    /// `Accept-Encoding` header, respecting quality values. Brotli is preferred
+    /// over gzip when both are equally acceptable. Returns `None` if the request
+    /// has no `Accept-Encoding` header or no enabled encoding is preferred over
+    /// `identity`.
+    fn preferred_encoding(request: &Request<'_>) -> Option<&'static str> {
+        let accept = request.guard::<&AcceptEncoding>().succeeded()?;
+
+        let mut available = vec![];
+        if cfg!(feature = "brotli_compression") {
+            available.push("br");
+        }
+
+        if cfg!(feature = "gzip_compression") {
+            available.push("gzip");
+        }
+
+        available.push("identity");
+        accept.preferred_of(&available)
+            .filter(|&&encoding| encoding != "identity")
+            .map(|&encoding| encoding)
     }
 
     fn already_encoded(response: &Response<'_>) -> bool {
@@ -93,12 +107,11 @@ impl CompressionUtils {
             return;
         }
 
-        // Compression is done when the request accepts brotli or gzip encoding
+        // Compression is done when the request prefers brotli or gzip encoding
         // and the corresponding feature is enabled
-        if cfg!(feature = "brotli_compression") && CompressionUtils::accepts_encoding(request, "br")
-        {
+        match CompressionUtils::preferred_encoding(request) {
             #[cfg(feature = "brotli_compression")]
-            {
+            Some("br") => {
                 if let Some(plain) = response.take_body() {
                     let content_type_top = content_type.as_ref().map(|ct| ct.top());
                     let mut params = brotli::enc::BrotliEncoderInitParams();
@@ -119,17 +132,15 @@ impl CompressionUtils {
                     );
                 }
             }
-        } else if cfg!(feature = "gzip_compression")
-            && CompressionUtils::accepts_encoding(request, "gzip")
-        {
             #[cfg(feature = "gzip_compression")]
-            {
+            Some("gzip") => {
                 if let Some(plain) = response.take_body() {
                     let compressor = GzEncoder::new(plain.into_inner(), flate2::Compression::default());
 
                     CompressionUtils::set_body_and_encoding(response, compressor, Encoding::Gzip);
                 }
             }
+            _ => { /* no compression preferred or enabled */ }
         }
     }
 }
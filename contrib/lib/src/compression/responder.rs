@@ -6,8 +6,10 @@ use super::CompressionUtils;
 /// Compresses responses with Brotli or Gzip compression.
 ///
 /// The `Compress` type implements brotli and gzip compression for responses in
-/// accordance with the `Accept-Encoding` header. If accepted, brotli
-/// compression is preferred over gzip.
+/// accordance with the `Accept-Encoding` header, including its quality values:
+/// an encoding with `q=0` is never used, and a higher quality encoding is used
+/// over a lower quality one. If both are equally accepted, brotli compression
+/// is preferred over gzip.
 ///
 /// In the brotli compression mode (using the
 /// [rust-brotli](https://github.com/dropbox/rust-brotli) crate), quality is set
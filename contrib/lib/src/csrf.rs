@@ -0,0 +1,427 @@
+//! Cross-site request forgery (CSRF) protection.
+//!
+//! The [`Csrf`] fairing rejects requests with unsafe methods, i.e., any method
+//! other than `GET`, `HEAD`, `OPTIONS`, and `TRACE`, unless they carry the
+//! client's CSRF token. The token is random, kept by the client in a private
+//! cookie, and must be echoed back by the request in one of two ways:
+//!
+//!   * in the `X-CSRF-Token` header, typically set by JavaScript, or
+//!   * in a `csrf_token` field of an `application/x-www-form-urlencoded` or
+//!     `multipart/form-data` form.
+//!
+//! Requests that fail validation are answered with a `403 Forbidden` without
+//! running any route handlers. Validation occurs after Rocket applies the
+//! `_method` form field, so forms that override their method are protected as
+//! well.
+//!
+//! The [`CsrfToken`] request guard retrieves the client's token, issuing one if
+//! the client doesn't have one yet, so that it can be embedded in forms. The
+//! [`CsrfForm`] data guard reads a form and validates the token it carries.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `csrf` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["csrf"]
+//! ```
+//!
+//! # Usage
+//!
+//! Attach the fairing, embed the token from a [`CsrfToken`] guard in every
+//! form, and read submitted forms with a [`CsrfForm`] instead of a
+//! [`Form`](rocket::request::Form):
+//!
+//! ```rust
+//! # #![feature(proc_macro_hygiene)]
+//! # #[macro_use] extern crate rocket;
+//! # extern crate rocket_contrib;
+//! use rocket::request::FromForm;
+//! use rocket::response::content::Html;
+//! use rocket_contrib::csrf::{Csrf, CsrfForm, CsrfToken};
+//!
+//! #[derive(FromForm)]
+//! struct Message {
+//!     message: String,
+//! }
+//!
+//! #[get("/")]
+//! fn form(token: CsrfToken) -> Html<String> {
+//!     Html(format!(r#"<form method="post" action="/submit">
+//!         <input type="hidden" name="csrf_token" value="{}">
+//!         <input type="text" name="message">
+//!     </form>"#, token))
+//! }
+//!
+//! #[post("/submit", data = "<form>")]
+//! fn submit(form: CsrfForm<Message>) -> String {
+//!     format!("only reached with a valid token: {}", form.message)
+//! }
+//!
+//! fn main() {
+//!     rocket::ignite()
+//!         .attach(Csrf::fairing())
+//!         .mount("/", routes![form, submit])
+//!         # ;
+//! }
+//! ```
+//!
+//! The fairing only sees the first 512 bytes of a body, the [peek
+//! buffer](rocket::Data::peek()), so it validates a form's token itself only
+//! when the token field lies within them, as it does when the token is the
+//! first field. Any other form, including every `multipart/form-data` form
+//! without the header, passes the fairing and is validated by [`CsrfForm`] once
+//! the whole body has been read. As a result, every route that accepts form
+//! submissions with an unsafe method should read them with a `CsrfForm`.
+//!
+//! ## Exemptions
+//!
+//! Routes that are meant to be called by other sites, such as webhooks, can be
+//! exempted from validation by path prefix via [`Csrf::exempt()`].
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::from_utf8;
+
+use rand::{RngCore, rngs::OsRng};
+
+use rocket::{Rocket, Request, Data, Route, Outcome};
+use rocket::data::{self, FromData, Transform, Transformed};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::handler;
+use rocket::http::{Cookie, Method, Status};
+use rocket::http::uri::Origin;
+use rocket::request::{self, FromRequest, Form, FormBuffer, FormDataError};
+use rocket::request::{FormItems, FromForm};
+
+/// The name of the private cookie that holds the CSRF token.
+pub const COOKIE_NAME: &str = "csrf_token";
+
+/// The name of the form field checked for the CSRF token.
+pub const FIELD_NAME: &str = "csrf_token";
+
+/// The name of the header checked for the CSRF token.
+pub const HEADER_NAME: &str = "X-CSRF-Token";
+
+/// Path of the internal route that requests failing validation are sent to.
+const FORBIDDEN_PATH: &str = "/__rocket/csrf/forbidden";
+
+/// Fairing that validates the CSRF token of requests with unsafe methods.
+///
+/// See the [module documentation](self) for details.
+///
+/// # Example
+///
+/// Protect every route except those under `/webhooks`:
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::csrf::Csrf;
+///
+/// fn main() {
+///     rocket::ignite()
+///         .attach(Csrf::fairing().exempt("/webhooks"))
+///         // ...
+///     # ;
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Csrf {
+    exempt: Vec<String>,
+}
+
+impl Csrf {
+    /// Returns a fairing that validates CSRF tokens on all routes.
+    pub fn fairing() -> Csrf {
+        Csrf::default()
+    }
+
+    /// Exempts all requests whose path is `path` or begins with `path`
+    /// followed by a `/` from validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::csrf::Csrf;
+    ///
+    /// // Exempts `/webhooks`, `/webhooks/github`, but not `/webhooksfoo`.
+    /// # #[allow(unused_variables)]
+    /// let csrf = Csrf::fairing().exempt("/webhooks");
+    /// ```
+    pub fn exempt<P: Into<String>>(mut self, path: P) -> Self {
+        let mut path = path.into();
+        while path.len() > 1 && path.ends_with('/') {
+            path.pop();
+        }
+
+        self.exempt.push(path);
+        self
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        self.exempt.iter().any(|prefix| {
+            prefix == "/" || path == prefix
+                || (path.starts_with(prefix.as_str()) && path[prefix.len()..].starts_with('/'))
+        })
+    }
+
+    /// Returns the token in the form field of the peek buffer of `data`, if
+    /// the field is entirely within it.
+    fn peeked_token(data: &Data) -> Option<String> {
+        let form = match from_utf8(data.peek()) {
+            Ok(form) => form,
+            // The peek buffer may end in the middle of a character.
+            Err(e) => from_utf8(&data.peek()[..e.valid_up_to()]).ok()?,
+        };
+
+        // Unless the body is complete, the last item may be cut short.
+        let mut items: Vec<_> = FormItems::from(form).collect();
+        if !data.peek_complete() {
+            items.pop();
+        }
+
+        items.into_iter()
+            .find(|item| item.key.as_str() == FIELD_NAME)
+            .and_then(|item| item.value.url_decode().ok())
+    }
+}
+
+fn is_safe(method: Method) -> bool {
+    match method {
+        Method::Get | Method::Head | Method::Options | Method::Trace => true,
+        _ => false
+    }
+}
+
+/// Compares `a` and `b` in time independent of where they first differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Returns `true` if `submitted` matches the token in the client's cookie.
+fn is_valid(request: &Request<'_>, submitted: Option<&str>) -> bool {
+    match (request.cookies().get_private(COOKIE_NAME), submitted) {
+        (Some(expected), Some(submitted)) => {
+            constant_time_eq(expected.value().as_bytes(), submitted.as_bytes())
+        }
+        _ => false
+    }
+}
+
+fn forbidden<'r>(_: &'r Request<'_>, _: Data) -> handler::Outcome<'r> {
+    Outcome::Failure(Status::Forbidden)
+}
+
+impl Fairing for Csrf {
+    fn info(&self) -> Info {
+        Info {
+            name: "CSRF Protection",
+            kind: Kind::Attach | Kind::Request,
+        }
+    }
+
+    fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> {
+        let routes: Vec<_> = [Method::Post, Method::Put, Method::Delete, Method::Patch,
+                              Method::Connect]
+            .iter()
+            .map(|&method| Route::ranked(isize::min_value(), method, FORBIDDEN_PATH, forbidden))
+            .collect();
+
+        Ok(rocket.mount("/", routes))
+    }
+
+    fn on_request(&self, request: &mut Request<'_>, data: &Data) {
+        if is_safe(request.method()) || self.is_exempt(request.uri().path()) {
+            return;
+        }
+
+        let (is_form, is_form_data) = request.content_type()
+            .map_or((false, false), |ct| (ct.is_form(), ct.is_form_data()));
+
+        let submitted = match request.headers().get_one(HEADER_NAME) {
+            Some(token) => Some(token.to_string()),
+            None if is_form => Csrf::peeked_token(data),
+            None => None,
+        };
+
+        // The token of a form that doesn't fit in the peek buffer is checked by
+        // `CsrfForm` once the body has been read.
+        if submitted.is_none() && (is_form_data || (is_form && !data.peek_complete())) {
+            return;
+        }
+
+        if !is_valid(request, submitted.as_deref()) {
+            warn_!("CSRF token validation failed for {} {}.", request.method(), request.uri());
+            request.set_uri(Origin::parse(FORBIDDEN_PATH).expect("valid path"));
+        }
+    }
+}
+
+/// Request guard that retrieves the client's CSRF token.
+///
+/// If the client doesn't have a token yet, a new one is generated and sent to
+/// the client in a private cookie. The guard never fails or forwards. The token
+/// is formatted by `Display` as-is, so it can be embedded in a form field named
+/// [`FIELD_NAME`] or sent in a header named [`HEADER_NAME`].
+///
+/// See the [module documentation](self) for a usage example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+/// The token for the current request, cached so all guards agree.
+struct CachedToken(String);
+
+impl CsrfToken {
+    /// Returns the token as a string slice.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes `self` and returns the token.
+    #[inline(always)]
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for CsrfToken {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let cached = request.local_cache(|| {
+            let mut cookies = request.cookies();
+            match cookies.get_private(COOKIE_NAME) {
+                Some(cookie) => CachedToken(cookie.value().to_string()),
+                None => {
+                    let token = generate_token();
+                    cookies.add_private(Cookie::new(COOKIE_NAME, token.clone()));
+                    CachedToken(token)
+                }
+            }
+        });
+
+        Outcome::Success(CsrfToken(cached.0.clone()))
+    }
+}
+
+/// Data guard that reads a form and validates the CSRF token it carries.
+///
+/// A `CsrfForm<T>` reads the request body exactly like a
+/// [`Form<T>`](rocket::request::Form), from either an
+/// `application/x-www-form-urlencoded` or a `multipart/form-data` body, and
+/// parses it into a `T` with the [`FIELD_NAME`] field removed. The token is
+/// taken from the [`HEADER_NAME`] header if present and from that field
+/// otherwise. If it doesn't match the client's token, the guard fails with a
+/// `403 Forbidden` and a [`CsrfFormError::Token`].
+///
+/// See the [module documentation](self) for a usage example.
+#[derive(Debug)]
+pub struct CsrfForm<T>(pub T);
+
+/// The error returned by the [`CsrfForm`] data guard.
+#[derive(Debug)]
+pub enum CsrfFormError<'f, E> {
+    /// The submitted token is missing or doesn't match the client's token.
+    Token,
+    /// The form could not be read or parsed.
+    Form(FormDataError<'f, E>),
+}
+
+impl<T> CsrfForm<T> {
+    /// Consumes `self` and returns the parsed form.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for CsrfForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// A form of type `T` along with the token that was removed from it.
+struct WithToken<T> {
+    token: Option<String>,
+    form: T,
+}
+
+impl<'f, T: FromForm<'f>> FromForm<'f> for WithToken<T> {
+    type Error = T::Error;
+
+    fn from_form(items: &mut FormItems<'f>, strict: bool) -> Result<Self, T::Error> {
+        let mut token = None;
+        let rest: Vec<_> = items
+            .filter(|item| {
+                if item.key.as_str() != FIELD_NAME {
+                    return true;
+                }
+
+                token = item.value.url_decode().ok();
+                false
+            })
+            .collect();
+
+        let form = T::from_form(&mut FormItems::from(rest), strict)?;
+        Ok(WithToken { token, form })
+    }
+}
+
+impl<'f, T: FromForm<'f>> FromData<'f> for CsrfForm<T> {
+    type Error = CsrfFormError<'f, T::Error>;
+    type Owned = FormBuffer;
+    type Borrowed = FormBuffer;
+
+    fn transform(
+        request: &Request<'_>,
+        data: Data
+    ) -> Transform<data::Outcome<Self::Owned, Self::Error>> {
+        let lift = |(status, e)| (status, CsrfFormError::Form(e));
+        match <Form<T> as FromData<'f>>::transform(request, data) {
+            Transform::Owned(outcome) => Transform::Owned(outcome.map_failure(lift)),
+            Transform::Borrowed(outcome) => Transform::Borrowed(outcome.map_failure(lift)),
+        }
+    }
+
+    fn from_data(
+        request: &Request<'_>,
+        outcome: Transformed<'f, Self>
+    ) -> data::Outcome<Self, Self::Error> {
+        let buffer = try_outcome!(outcome.borrowed());
+        let transformed = Transform::Borrowed(Outcome::Success(buffer));
+        let form = <Form<WithToken<T>> as FromData<'f>>::from_data(request, transformed)
+            .map_failure(|(status, e)| (status, CsrfFormError::Form(e)));
+
+        let WithToken { token, form } = try_outcome!(form).into_inner();
+        let submitted = request.headers().get_one(HEADER_NAME).or(token.as_deref());
+        if !is_valid(request, submitted) {
+            warn_!("CSRF token validation failed for {} {}.", request.method(), request.uri());
+            return Outcome::Failure((Status::Forbidden, CsrfFormError::Token));
+        }
+
+        Outcome::Success(CsrfForm(form))
+    }
+}
+
+/// Generates a new token: 256 random bits, hex encoded.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,149 @@
+//! Automatic entity tags for dynamic responses.
+//!
+//! The [`ETag`] fairing computes an `ETag` for successful `GET` and `HEAD`
+//! responses by hashing their bodies and answers requests whose
+//! `If-None-Match` header matches that tag with a `304 Not Modified` and no
+//! body. The response is still generated, so the fairing saves bandwidth, not
+//! work; handlers that can compute a tag cheaply should use
+//! [`Tagged`](rocket::response::Tagged) instead.
+//!
+//! A response is left untouched if any of the following hold:
+//!
+//!   * its status isn't `200 OK`,
+//!   * it already carries an `ETag` or `Last-Modified` validator,
+//!   * its body is streamed (`Body::Chunked`) or larger than the fairing's
+//!     maximum size, 1MiB by default,
+//!   * or it sets a cookie, since the cookie would be lost in a `304`.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `etag` feature is enabled. Enable it
+//! in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["etag"]
+//! ```
+//!
+//! # Usage
+//!
+//! Attach the fairing, optionally adjusting the maximum size of hashed bodies:
+//!
+//! ```rust
+//! # extern crate rocket;
+//! # extern crate rocket_contrib;
+//! use rocket_contrib::etag::ETag;
+//!
+//! fn main() {
+//!     rocket::ignite()
+//!         .attach(ETag::fairing().max_size(256 * 1024))
+//!         // ...
+//!     # ;
+//! }
+//! ```
+
+use std::io::Cursor;
+use std::hash::Hasher;
+use std::collections::hash_map::DefaultHasher;
+
+use rocket::{Request, Response};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Method, Status, EntityTag, IfNoneMatch};
+use rocket::response::Body;
+
+/// The default maximum size, 1MiB, of a body hashed by the fairing.
+const DEFAULT_MAX_SIZE: u64 = 1 << 20;
+
+/// Fairing that tags dynamic responses and answers conditional requests.
+///
+/// See the [module level docs](self) for details.
+#[derive(Debug, Clone)]
+pub struct ETag {
+    max_size: u64,
+}
+
+impl ETag {
+    /// Returns a fairing that tags bodies of up to 1MiB.
+    pub fn fairing() -> ETag {
+        ETag { max_size: DEFAULT_MAX_SIZE }
+    }
+
+    /// Sets the maximum size, in bytes, of a body that is hashed. Larger bodies
+    /// are sent without an `ETag`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::etag::ETag;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let etag = ETag::fairing().max_size(64 * 1024);
+    /// ```
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Returns `true` if the fairing should tag `response` to `request`.
+    fn applies(&self, request: &Request<'_>, response: &mut Response<'_>) -> bool {
+        if request.method() != Method::Get && request.method() != Method::Head {
+            return false;
+        }
+
+        let headers = response.headers();
+        if response.status() != Status::Ok
+            || headers.contains("ETag")
+            || headers.contains("Last-Modified")
+            || headers.contains("Set-Cookie")
+        {
+            return false;
+        }
+
+        match response.body() {
+            Some(Body::Sized(_, size)) => size <= self.max_size,
+            _ => false
+        }
+    }
+}
+
+impl Fairing for ETag {
+    fn info(&self) -> Info {
+        Info {
+            name: "Automatic ETag",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response(&self, request: &Request<'_>, response: &mut Response<'_>) {
+        if !self.applies(request, response) {
+            return;
+        }
+
+        let body = match response.body_bytes() {
+            Some(body) => body,
+            None => {
+                error_!("Failed to read a response body to compute its ETag.");
+                response.set_status(Status::InternalServerError);
+                return;
+            }
+        };
+
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&body);
+        let tag = EntityTag::strong(format!("{:016x}", hasher.finish()));
+
+        let if_none_match = request.headers().get("If-None-Match").collect::<Vec<_>>();
+        let not_modified = !if_none_match.is_empty() && if_none_match.join(", ")
+            .parse::<IfNoneMatch>()
+            .map_or(false, |header| header.matches(Some(&tag)));
+
+        response.set_header(tag);
+        match not_modified {
+            true => response.set_status(Status::NotModified),
+            false => response.set_sized_body(Cursor::new(body)),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! Automatic JSON (de)serialization support.
 //!
-//! See the [`Json`](json::Json) type for further details.
+//! See the [`Json`](json::Json) type for further details. Large sequences of
+//! values can be streamed without serializing them all up front with
+//! [`JsonLines`](json::JsonLines) and
+//! [`JsonArrayStream`](json::JsonArrayStream).
 //!
 //! # Enabling
 //!
@@ -18,11 +21,12 @@ use std::ops::{Deref, DerefMut};
 use std::io::{self, Read};
 use std::iter::FromIterator;
 
+use rocket::Catcher;
 use rocket::request::Request;
 use rocket::outcome::Outcome::*;
 use rocket::data::{Outcome, Transform, Transform::*, Transformed, Data, FromData};
-use rocket::response::{self, Responder, content};
-use rocket::http::Status;
+use rocket::response::{self, Response, Responder, content};
+use rocket::http::{Status, ContentType};
 
 use serde::{Serialize, Serializer};
 use serde::de::{Deserialize, Deserializer};
@@ -290,6 +294,499 @@ impl<'a> Responder<'a> for JsonValue {
     }
 }
 
+/// What a streaming JSON responder does when an item fails to serialize.
+///
+/// By the time an item is serialized, the response's status and headers have
+/// been sent, so the failure can't change the status of the response. The
+/// error is logged in either case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// Omit the item and continue with the next one.
+    Skip,
+    /// End the response after the items preceding the failed one, all of which
+    /// are sent. A [`JsonArrayStream`] is left without its closing `]`, so
+    /// clients fail to parse the incomplete array; a [`JsonLines`] stream
+    /// simply ends after the last complete item. This is the default.
+    Abort,
+}
+
+impl Default for OnError {
+    fn default() -> OnError {
+        OnError::Abort
+    }
+}
+
+/// Streams the items of an iterator as [newline-delimited JSON].
+///
+/// Each item is serialized into JSON only when the response body is read and
+/// followed by a newline, so at most one item and one chunk of the body are
+/// held in memory at once regardless of the number of items. The response has
+/// a Content-Type of `application/x-ndjson` and a chunked body.
+///
+/// Items that fail to serialize are handled according to [`OnError`], set with
+/// [`JsonLines::on_error()`].
+///
+/// [newline-delimited JSON]: http://ndjson.org/
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// # #[macro_use] extern crate serde_derive;
+/// use rocket_contrib::json::JsonLines;
+///
+/// #[derive(Serialize)]
+/// struct Reading { sensor: u32, value: f64 }
+///
+/// # fn all_readings() -> impl Iterator<Item = Reading> { std::iter::empty() }
+/// #[get("/readings")]
+/// fn readings() -> JsonLines<impl Iterator<Item = Reading>> {
+///     JsonLines::new(all_readings())
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug)]
+pub struct JsonLines<I> {
+    items: I,
+    on_error: OnError,
+}
+
+/// Streams the items of an iterator as a JSON array.
+///
+/// Like [`JsonLines`], items are serialized lazily as the response body is
+/// read, so memory use is bounded regardless of the number of items; unlike
+/// it, the result is a single JSON array with a Content-Type of
+/// `application/json`, suitable for clients that expect one document.
+///
+/// Items that fail to serialize are handled according to [`OnError`], set with
+/// [`JsonArrayStream::on_error()`].
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::json::{JsonArrayStream, OnError};
+///
+/// #[get("/squares")]
+/// fn squares() -> JsonArrayStream<impl Iterator<Item = u64>> {
+///     JsonArrayStream::new((0..1_000_000u64).map(|n| n * n)).on_error(OnError::Skip)
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug)]
+pub struct JsonArrayStream<I> {
+    items: I,
+    on_error: OnError,
+}
+
+macro_rules! impl_json_stream {
+    ($T:ident, $format:expr, $content_type:expr) => (
+        impl<I> $T<I> {
+            /// Streams the items produced by `items`.
+            #[inline(always)]
+            pub fn new(items: I) -> $T<I> {
+                $T { items, on_error: OnError::default() }
+            }
+
+            /// Sets what happens when an item fails to serialize.
+            #[inline(always)]
+            pub fn on_error(mut self, on_error: OnError) -> $T<I> {
+                self.on_error = on_error;
+                self
+            }
+        }
+
+        impl<I> From<I> for $T<I> {
+            #[inline(always)]
+            fn from(items: I) -> $T<I> {
+                $T::new(items)
+            }
+        }
+
+        /// Streams the serialized items in a chunked body.
+        impl<'r, I> Responder<'r> for $T<I>
+            where I: IntoIterator, I::IntoIter: 'r, I::Item: Serialize
+        {
+            fn respond_to(self, _: &Request<'_>) -> response::Result<'r> {
+                let body = JsonStream::new(self.items.into_iter(), $format, self.on_error);
+                Response::build()
+                    .header($content_type)
+                    .streamed_body(body)
+                    .ok()
+            }
+        }
+    )
+}
+
+impl_json_stream!(JsonLines, Format::Lines, ContentType::new("application", "x-ndjson"));
+impl_json_stream!(JsonArrayStream, Format::Array, ContentType::JSON);
+
+/// The layout of a streamed sequence of JSON items.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Lines,
+    Array,
+}
+
+/// A reader that serializes the items of an iterator on demand.
+struct JsonStream<I> {
+    items: I,
+    format: Format,
+    on_error: OnError,
+    /// The serialized bytes not yet read.
+    pending: Vec<u8>,
+    position: usize,
+    /// The number of items written so far.
+    written: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<I: Iterator> JsonStream<I> where I::Item: Serialize {
+    fn new(items: I, format: Format, on_error: OnError) -> JsonStream<I> {
+        JsonStream {
+            items, format, on_error,
+            pending: vec![],
+            position: 0,
+            written: 0,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Serializes the next item, or the end of the sequence, into `pending`.
+    /// Returns `false` once there's nothing left to serialize.
+    ///
+    /// An aborted stream simply ends rather than failing a read: the body is
+    /// written out in chunks, and a failed read would discard the complete
+    /// items already read into the current chunk.
+    fn fill(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        if !self.started {
+            self.started = true;
+            if self.format == Format::Array {
+                self.pending.push(b'[');
+            }
+        }
+
+        loop {
+            let item = match self.items.next() {
+                Some(item) => item,
+                None => {
+                    self.finished = true;
+                    if self.format == Format::Array {
+                        self.pending.push(b']');
+                    }
+
+                    return !self.pending.is_empty();
+                }
+            };
+
+            let mark = self.pending.len();
+            if self.format == Format::Array && self.written > 0 {
+                self.pending.push(b',');
+            }
+
+            match serde_json::to_writer(&mut self.pending, &item) {
+                Ok(()) => {
+                    if self.format == Format::Lines {
+                        self.pending.push(b'\n');
+                    }
+
+                    self.written += 1;
+                    return true;
+                }
+                Err(e) => {
+                    error_!("JSON failed to serialize a streamed item: {:?}", e);
+                    self.pending.truncate(mark);
+                    if self.on_error == OnError::Abort {
+                        self.finished = true;
+                        return !self.pending.is_empty();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I: Iterator> Read for JsonStream<I> where I::Item: Serialize {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            if self.position == self.pending.len() {
+                self.pending.clear();
+                self.position = 0;
+                if !self.fill() {
+                    break;
+                }
+            }
+
+            let n = std::cmp::min(buf.len() - read, self.pending.len() - self.position);
+            let pending = &self.pending[self.position..(self.position + n)];
+            buf[read..(read + n)].copy_from_slice(pending);
+            self.position += n;
+            read += n;
+        }
+
+        Ok(read)
+    }
+}
+
+/// The members defined by RFC 7807, which extension members may not replace.
+const PROBLEM_MEMBERS: &[&str] = &["type", "title", "status", "detail", "instance"];
+
+/// An [RFC 7807] problem details document describing an error.
+///
+/// A `Problem` carries the standard `type`, `title`, `status`, `detail`, and
+/// `instance` members, along with any number of extension members. Only the
+/// status is required: the type defaults to `about:blank`, and the title to
+/// the status's reason phrase.
+///
+/// [RFC 7807]: https://tools.ietf.org/html/rfc7807
+///
+/// # `Responder`
+///
+/// The `Responder` implementation serializes the document into the body of a
+/// fixed-sized response with the problem's status and a `Content-Type` of
+/// `application/problem+json`. If serialization fails, an `Err` of
+/// `Status::InternalServerError` is returned.
+///
+/// # Usage
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # #[macro_use] extern crate rocket_contrib;
+/// use rocket::http::Status;
+/// use rocket_contrib::json::{Json, Problem};
+///
+/// #[get("/account/<id>/withdraw/<amount>")]
+/// fn withdraw(id: usize, amount: u64) -> Result<Json<u64>, Problem> {
+///     let balance = 30;
+///     if amount > balance {
+///         return Err(Problem::new(Status::Forbidden)
+///             .type_uri("https://example.com/probs/out-of-credit")
+///             .title("You do not have enough credit.")
+///             .detail(format!("Your balance is {}, but that costs {}.", balance, amount))
+///             .instance(format!("/account/{}/withdraw/{}", id, amount))
+///             .extension("balance", balance)
+///             .extension("accounts", json!(["/account/12345", "/account/67890"])));
+///     }
+///
+///     Ok(Json(balance - amount))
+/// }
+/// # fn main() {  }
+/// ```
+///
+/// To render Rocket's default error responses as problem documents as well,
+/// register the catchers returned by [`problem_catchers()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    type_uri: Option<String>,
+    title: Option<String>,
+    status: Status,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Problem {
+    /// Creates a problem with the status `status` and no other members set.
+    #[inline]
+    pub fn new(status: Status) -> Problem {
+        Problem {
+            type_uri: None,
+            title: None,
+            status,
+            detail: None,
+            instance: None,
+            extensions: serde_json::Map::new(),
+        }
+    }
+
+    /// Sets the URI identifying the problem type.
+    #[inline]
+    pub fn type_uri<S: Into<String>>(mut self, uri: S) -> Problem {
+        self.type_uri = Some(uri.into());
+        self
+    }
+
+    /// Sets the short, human-readable summary of the problem type.
+    #[inline]
+    pub fn title<S: Into<String>>(mut self, title: S) -> Problem {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the human-readable explanation specific to this occurrence.
+    #[inline]
+    pub fn detail<S: Into<String>>(mut self, detail: S) -> Problem {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the URI identifying this occurrence of the problem.
+    #[inline]
+    pub fn instance<S: Into<String>>(mut self, instance: S) -> Problem {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds the extension member `name` with the value `value`, replacing any
+    /// previous member of the same name. Extensions named like one of the
+    /// standard members are ignored when the problem is serialized.
+    #[inline]
+    pub fn extension<N, V>(mut self, name: N, value: V) -> Problem
+        where N: Into<String>, V: Into<serde_json::Value>
+    {
+        self.extensions.insert(name.into(), value.into());
+        self
+    }
+
+    /// Returns the status of the problem.
+    #[inline(always)]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the problem type's URI, `about:blank` if none was set.
+    #[inline]
+    pub fn get_type_uri(&self) -> &str {
+        self.type_uri.as_ref().map_or("about:blank", |uri| uri.as_str())
+    }
+
+    /// Returns the problem's title, the status's reason phrase if none was
+    /// set.
+    #[inline]
+    pub fn get_title(&self) -> &str {
+        self.title.as_ref().map_or(self.status.reason, |title| title.as_str())
+    }
+
+    /// Returns the problem's detail, if any.
+    #[inline]
+    pub fn get_detail(&self) -> Option<&str> {
+        self.detail.as_ref().map(|detail| detail.as_str())
+    }
+
+    /// Returns the problem's instance URI, if any.
+    #[inline]
+    pub fn get_instance(&self) -> Option<&str> {
+        self.instance.as_ref().map(|instance| instance.as_str())
+    }
+
+    /// Returns the value of the extension member `name`, if any.
+    #[inline]
+    pub fn get_extension(&self, name: &str) -> Option<&serde_json::Value> {
+        self.extensions.get(name)
+    }
+}
+
+impl From<Status> for Problem {
+    #[inline(always)]
+    fn from(status: Status) -> Problem {
+        Problem::new(status)
+    }
+}
+
+impl Serialize for Problem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", self.get_type_uri())?;
+        map.serialize_entry("title", self.get_title())?;
+        map.serialize_entry("status", &self.status.code)?;
+        if let Some(detail) = self.get_detail() {
+            map.serialize_entry("detail", detail)?;
+        }
+
+        if let Some(instance) = self.get_instance() {
+            map.serialize_entry("instance", instance)?;
+        }
+
+        for (name, value) in &self.extensions {
+            if !PROBLEM_MEMBERS.contains(&name.as_str()) {
+                map.serialize_entry(name, value)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// Serializes the problem into JSON. Returns a response with the problem's
+/// status, Content-Type `application/problem+json`, and a fixed-size body with
+/// the serialized problem. If serialization fails, an `Err` of
+/// `Status::InternalServerError` is returned.
+impl<'a> Responder<'a> for Problem {
+    fn respond_to(self, _: &Request<'_>) -> response::Result<'a> {
+        let body = serde_json::to_vec(&self).map_err(|e| {
+            error_!("JSON failed to serialize: {:?}", e);
+            Status::InternalServerError
+        })?;
+
+        Response::build()
+            .status(self.status)
+            .header(ContentType::new("application", "problem+json"))
+            .sized_body(io::Cursor::new(body))
+            .ok()
+    }
+}
+
+macro_rules! problem_catchers {
+    ($($code:expr, $fn_name:ident),+) => ({
+        $(
+            fn $fn_name<'r>(req: &'r Request<'_>) -> response::Result<'r> {
+                Problem::new(Status::from_code($code).unwrap()).respond_to(req)
+            }
+        )+
+
+        vec![$(Catcher::new($code, $fn_name)),+]
+    })
+}
+
+/// Returns catchers that render each of Rocket's default error responses as a
+/// [`Problem`] document instead of an HTML page.
+///
+/// Each catcher responds with a `Problem` with the error's status, the type
+/// `about:blank`, and the status's reason phrase as its title. Catchers
+/// registered after these replace them for their status codes.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::json::problem_catchers;
+///
+/// fn main() {
+/// # if false {
+///     rocket::ignite()
+///         .register(problem_catchers())
+///         .launch();
+/// # }
+/// }
+/// ```
+pub fn problem_catchers() -> Vec<Catcher> {
+    problem_catchers! {
+        400, handle_400, 401, handle_401, 402, handle_402, 403, handle_403,
+        404, handle_404, 405, handle_405, 406, handle_406, 407, handle_407,
+        408, handle_408, 409, handle_409, 410, handle_410, 411, handle_411,
+        412, handle_412, 413, handle_413, 414, handle_414, 415, handle_415,
+        416, handle_416, 417, handle_417, 418, handle_418, 421, handle_421,
+        422, handle_422, 426, handle_426, 428, handle_428, 429, handle_429,
+        431, handle_431, 451, handle_451, 500, handle_500, 501, handle_501,
+        503, handle_503, 504, handle_504, 510, handle_510
+    }
+}
+
 /// A macro to create ad-hoc JSON serializable values using JSON syntax.
 ///
 /// # Usage
@@ -19,12 +19,16 @@
 //! * [json*](type@json) - JSON (de)serialization
 //! * [serve*](serve) - Static File Serving
 //! * [msgpack](msgpack) - MessagePack (de)serialization
+//! * [serde_form](serde_form) - Form and query deserialization with `serde`
 //! * [handlebars_templates](templates) - Handlebars Templating
 //! * [tera_templates](templates) - Tera Templating
 //! * [uuid](uuid) - UUID (de)serialization
 //! * [${database}_pool](databases) - Database Configuration and Pooling
 //! * [helmet](helmet) - Fairing for Security and Privacy Headers
 //! * [compression](compression) - Response compression
+//! * [sessions](sessions) - Server-Side Sessions
+//! * [csrf](csrf) - CSRF Protection
+//! * [etag](etag) - Automatic ETags for Dynamic Responses
 //!
 //! The recommend way to include features from this crate via Cargo in your
 //! project is by adding a `[dependencies.rocket_contrib]` section to your
@@ -47,10 +51,14 @@
 #[cfg(feature="json")] #[macro_use] pub mod json;
 #[cfg(feature="serve")] pub mod serve;
 #[cfg(feature="msgpack")] pub mod msgpack;
+#[cfg(feature="serde_form")] pub mod serde_form;
 #[cfg(feature="templates")] pub mod templates;
 #[cfg(feature="uuid")] pub mod uuid;
 #[cfg(feature="databases")] pub mod databases;
 #[cfg(feature = "helmet")] pub mod helmet;
 #[cfg(any(feature="brotli_compression", feature="gzip_compression"))] pub mod compression;
+#[cfg(feature="sessions")] pub mod sessions;
+#[cfg(feature="csrf")] pub mod csrf;
+#[cfg(feature="etag")] pub mod etag;
 
 #[cfg(feature="databases")] #[doc(hidden)] pub use rocket_contrib_codegen::*;
@@ -0,0 +1,782 @@
+//! Form and query deserialization through `serde`.
+//!
+//! See the [`SerdeForm`] and [`SerdeQuery`] types for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `serde_form` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["serde_form"]
+//! ```
+//!
+//! # Form Syntax
+//!
+//! Forms are read with the same syntax as forms parsed by a `FromForm` type:
+//! keys are paths whose components are separated by dots or enclosed in
+//! brackets, so `address.city=Paris` and `address[city]=Paris` both set the
+//! `city` field of the `address` field. Values are deserialized as follows:
+//!
+//!   * Structures and maps read their fields or entries from the components
+//!     that follow their own key.
+//!   * Sequences read one element for every item with the sequence's key, as in
+//!     `tags=a&tags=b`, and one for every distinct index that follows it, as in
+//!     `items[0].name=x&items[1].name=y`. Items without an index, as in
+//!     `tags[]=a`, each read a new element.
+//!   * Numbers, characters, strings, and unit enum variants are parsed from the
+//!     last value for their key. Booleans are `true` for `true` or `on` and
+//!     `false` for `false` or `off`.
+//!   * An `Option` is `None` when its key is missing or its value is empty.
+//!
+//! Unlike a `FromForm` type, `serde` treats a missing `bool` as a missing
+//! field. Use `#[serde(default)]` on the fields of checkboxes, which browsers
+//! omit when they are unchecked.
+//!
+//! # Strict and Lenient Parsing
+//!
+//! [`SerdeForm`] and [`SerdeQuery`] parse _strictly_: every item must
+//! correspond to a field of a structure, a sequence element, or a map entry,
+//! with the exception of the `_method` field. Their lenient counterparts,
+//! [`LenientSerdeForm`] and [`LenientSerdeQuery`], ignore extra items.
+//!
+//! # Errors
+//!
+//! A form that fails to deserialize results in a `Failure` of
+//! [`FormDataError::Parse`](rocket::request::FormDataError::Parse) with a
+//! status of `422 Unprocessable Entity`. The [`FormErrors`] in the error carry
+//! the paths of the failing fields and the values that were submitted, as they
+//! do for `FromForm` types. `serde` stops at the first field that fails to
+//! deserialize, so at most one such field is reported, along with every
+//! unexpected field when parsing strictly.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::min;
+use std::fmt;
+use std::io::{self, Read};
+use std::ops::{Deref, DerefMut};
+
+use rocket::request::{self, Request, FromRequest, FormItems, split_form_key, field_path};
+use rocket::request::{FormError, FormDataError, FormErrors, FieldError, FieldErrorKind};
+use rocket::outcome::Outcome::*;
+use rocket::data::{Outcome, Transform, Transform::*, Transformed, Data, FromData};
+use rocket::http::{RawStr, Status};
+
+use serde::forward_to_deserialize_any;
+use serde::de::{self, Deserialize, Deserializer, Visitor, IntoDeserializer};
+
+/// A data guard that deserializes an `application/x-www-form-urlencoded`
+/// request body into a `T` through `serde`, strictly.
+///
+/// If the content type of the request isn't a form, the request is forwarded.
+/// The size of the body is limited by the `forms` limit: a larger body results
+/// in a `Failure` with a status of `413 Payload Too Large`, while a malformed
+/// form results in a `400 Bad Request`. See the [module
+/// documentation](crate::serde_form) for the syntax of forms and for the
+/// errors that result when deserialization fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::serde_form::SerdeForm;
+///
+/// #[derive(Deserialize)]
+/// struct Login {
+///     username: String,
+///     password: String,
+///     #[serde(default)]
+///     remember: bool,
+/// }
+///
+/// #[post("/login", data = "<login>")]
+/// fn login(login: SerdeForm<Login>) -> String {
+///     format!("Welcome, {}!", login.username)
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct SerdeForm<T>(pub T);
+
+/// A data guard like [`SerdeForm`] that ignores extra form items.
+#[derive(Debug)]
+pub struct LenientSerdeForm<T>(pub T);
+
+/// A request guard that deserializes the request's query string into a `T`
+/// through `serde`, strictly.
+///
+/// A request without a query string is deserialized as an empty form, so a
+/// `T` whose fields are all optional always succeeds. See the [module
+/// documentation](crate::serde_form) for the syntax of queries and for the
+/// errors that result when deserialization fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::serde_form::SerdeQuery;
+///
+/// #[derive(Deserialize)]
+/// struct Search {
+///     q: String,
+///     page: Option<usize>,
+/// }
+///
+/// #[get("/search")]
+/// fn search(search: SerdeQuery<Search>) -> String {
+///     format!("'{}', page {}", search.q, search.page.unwrap_or(1))
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct SerdeQuery<T>(pub T);
+
+/// A request guard like [`SerdeQuery`] that ignores extra query items.
+#[derive(Debug)]
+pub struct LenientSerdeQuery<T>(pub T);
+
+macro_rules! impl_wrapper {
+    ($($name:ident),*) => ($(
+        impl<T> $name<T> {
+            /// Consumes `self` and returns the deserialized value.
+            #[inline(always)]
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            #[inline(always)]
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            #[inline(always)]
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+    )*)
+}
+
+impl_wrapper!(SerdeForm, LenientSerdeForm, SerdeQuery, LenientSerdeQuery);
+
+fn read_form<'f>(request: &Request<'_>, data: Data) -> Transform<Outcome<String, FormError<'f>>> {
+    if !request.content_type().map_or(false, |ct| ct.is_form()) {
+        warn_!("Form data does not have form content type.");
+        return Borrowed(Forward(data));
+    }
+
+    let limit = request.limits().get("forms").unwrap_or(32 * 1024);
+    let mut string = String::with_capacity(min(4096, limit) as usize);
+    match data.open().take(limit.saturating_add(1)).read_to_string(&mut string) {
+        Ok(n) if n as u64 > limit => {
+            error_!("The request's form exceeds the 'forms' limit of {} bytes.", limit);
+            let message = "form exceeds the 'forms' limit";
+            let error = io::Error::new(io::ErrorKind::InvalidData, message);
+            Borrowed(Failure((Status::PayloadTooLarge, FormDataError::Io(error))))
+        }
+        Ok(_) => Borrowed(Success(string)),
+        Err(e) => Borrowed(Failure((Status::BadRequest, FormDataError::Io(e)))),
+    }
+}
+
+fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, (Status, FormError<'f>)>
+    where T: Deserialize<'f>
+{
+    match from_form_str(string, strict) {
+        Ok(value) => Ok(value),
+        Err(e @ FormDataError::Malformed(_)) => {
+            error_!("The request's form string was malformed.");
+            Err((Status::BadRequest, e))
+        }
+        Err(e) => {
+            error_!("The incoming form failed to deserialize.");
+            Err((Status::UnprocessableEntity, e))
+        }
+    }
+}
+
+impl<'f, T: Deserialize<'f>> FromData<'f> for SerdeForm<T> {
+    type Error = FormError<'f>;
+    type Owned = String;
+    type Borrowed = str;
+
+    #[inline(always)]
+    fn transform(r: &Request<'_>, d: Data) -> Transform<Outcome<Self::Owned, Self::Error>> {
+        read_form(r, d)
+    }
+
+    fn from_data(_: &Request<'_>, o: Transformed<'f, Self>) -> Outcome<Self, Self::Error> {
+        match parse(try_outcome!(o.borrowed()), true) {
+            Ok(value) => Success(SerdeForm(value)),
+            Err(e) => Failure(e)
+        }
+    }
+}
+
+impl<'f, T: Deserialize<'f>> FromData<'f> for LenientSerdeForm<T> {
+    type Error = FormError<'f>;
+    type Owned = String;
+    type Borrowed = str;
+
+    #[inline(always)]
+    fn transform(r: &Request<'_>, d: Data) -> Transform<Outcome<Self::Owned, Self::Error>> {
+        read_form(r, d)
+    }
+
+    fn from_data(_: &Request<'_>, o: Transformed<'f, Self>) -> Outcome<Self, Self::Error> {
+        match parse(try_outcome!(o.borrowed()), false) {
+            Ok(value) => Success(LenientSerdeForm(value)),
+            Err(e) => Failure(e)
+        }
+    }
+}
+
+impl<'a, 'r, T: Deserialize<'a>> FromRequest<'a, 'r> for SerdeQuery<T> {
+    type Error = FormError<'a>;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match parse(request.uri().query().unwrap_or(""), true) {
+            Ok(value) => Success(SerdeQuery(value)),
+            Err(e) => Failure(e)
+        }
+    }
+}
+
+impl<'a, 'r, T: Deserialize<'a>> FromRequest<'a, 'r> for LenientSerdeQuery<T> {
+    type Error = FormError<'a>;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match parse(request.uri().query().unwrap_or(""), false) {
+            Ok(value) => Success(LenientSerdeQuery(value)),
+            Err(e) => Failure(e)
+        }
+    }
+}
+
+/// Deserializes a `T` from the URL-encoded form string `string`, strictly if
+/// `strict` is `true`.
+///
+/// Returns [`FormDataError::Malformed`] if `string` isn't a valid form string
+/// and [`FormDataError::Parse`] with every error if `T` fails to deserialize.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # extern crate rocket_contrib;
+/// use rocket::request::FormDataError;
+/// use rocket_contrib::serde_form::from_form_str;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Item {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let item: Item = from_form_str("name=Rocket%21&tags=a&tags=b", true).unwrap();
+/// assert_eq!(item, Item { name: "Rocket!".into(), tags: vec!["a".into(), "b".into()] });
+///
+/// match from_form_str::<Item>("tags=a&color=red", true) {
+///     Err(FormDataError::Parse(errors, _)) => {
+///         assert_eq!(errors.get("color"), Some("unexpected field"));
+///         assert_eq!(errors.get("name"), Some("missing field"));
+///     }
+///     _ => unreachable!("the form is invalid"),
+/// }
+/// ```
+pub fn from_form_str<'f, T>(string: &'f str, strict: bool) -> Result<T, FormError<'f>>
+    where T: Deserialize<'f>
+{
+    let mut items = FormItems::from(string);
+    let mut root = Node::default();
+    for item in &mut items {
+        root.insert(&key_path(item.key), item.value);
+    }
+
+    if !items.exhaust() {
+        return Err(FormDataError::Malformed(string));
+    }
+
+    let context = Context { strict, unknown: RefCell::new(vec![]) };
+    let result = T::deserialize(NodeDeserializer {
+        node: &root,
+        path: String::new(),
+        context: &context
+    });
+
+    let mut errors = FormErrors::new();
+    for path in context.unknown.into_inner() {
+        let message = "unexpected field".into();
+        errors.push(FieldError { path, kind: FieldErrorKind::Unknown, message });
+    }
+
+    match result {
+        Ok(value) if errors.is_empty() => return Ok(value),
+        Ok(_) => { /* only unexpected fields */ }
+        Err(e) => errors.push(FieldError {
+            path: e.path.unwrap_or_default(),
+            kind: e.kind,
+            message: e.message
+        }),
+    }
+
+    errors.set_values(&FormItems::from(string).collect::<Vec<_>>());
+    Err(FormDataError::Parse(errors, string))
+}
+
+/// Returns `raw` percent-decoded, borrowing it if there's nothing to decode.
+fn decode(raw: &RawStr) -> Cow<'_, str> {
+    match raw.contains('%') || raw.contains('+') {
+        true => Cow::Owned(raw.url_decode_lossy()),
+        false => Cow::Borrowed(raw.as_str())
+    }
+}
+
+/// Returns the percent-decoded components of `key`: `a.b[c][]` is `a`, `b`,
+/// `c`, and the empty component. The key is split before it is decoded, so an
+/// encoded `.` is part of a component rather than a separator.
+fn key_path(key: &RawStr) -> Vec<Cow<'_, str>> {
+    let mut path = vec![];
+    let mut key = key;
+    while !key.is_empty() {
+        let (head, rest) = split_form_key(key);
+        path.push(decode(head));
+        key = rest;
+    }
+
+    path
+}
+
+/// The form items, arranged by the components of their keys: the values of the
+/// items whose key ends at this node, and a node for every distinct component
+/// that follows.
+#[derive(Debug, Default)]
+struct Node<'f> {
+    values: Vec<&'f RawStr>,
+    children: Vec<(Cow<'f, str>, Node<'f>)>,
+}
+
+impl<'f> Node<'f> {
+    fn insert(&mut self, path: &[Cow<'f, str>], value: &'f RawStr) {
+        let (head, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return self.values.push(value)
+        };
+
+        // An empty component, as in `a[]`, always refers to a new node.
+        let existing = match head.is_empty() {
+            true => None,
+            false => self.children.iter().position(|(key, _)| key == head)
+        };
+
+        let i = existing.unwrap_or_else(|| {
+            self.children.push((head.clone(), Node::default()));
+            self.children.len() - 1
+        });
+
+        self.children[i].1.insert(rest, value);
+    }
+}
+
+/// State shared by every deserializer for a single form.
+struct Context {
+    strict: bool,
+    unknown: RefCell<Vec<String>>,
+}
+
+/// An error while deserializing. The path is unknown for errors raised by a
+/// `Deserialize` implementation and is filled in by the deserializer it was
+/// raised to.
+#[derive(Debug)]
+struct Error {
+    path: Option<String>,
+    field: Option<String>,
+    kind: FieldErrorKind,
+    message: String,
+}
+
+impl Error {
+    fn new(path: &str, kind: FieldErrorKind, message: &str) -> Error {
+        Error { path: Some(path.into()), field: None, kind, message: message.into() }
+    }
+
+    fn at(mut self, path: &str) -> Error {
+        if self.path.is_none() {
+            self.path = Some(match self.field.take() {
+                Some(field) => field_path(path, &field),
+                None => path.to_string()
+            });
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {  }
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Error {
+        Error { path: None, field: None, kind: FieldErrorKind::BadValue, message: msg.to_string() }
+    }
+
+    fn missing_field(field: &'static str) -> Error {
+        let (field, kind) = (Some(field.into()), FieldErrorKind::Missing);
+        Error { path: None, field, kind, message: "missing field".into() }
+    }
+
+    fn unknown_field(field: &str, _: &'static [&'static str]) -> Error {
+        let (field, kind) = (Some(field.into()), FieldErrorKind::Unknown);
+        Error { path: None, field, kind, message: "unexpected field".into() }
+    }
+}
+
+/// Deserializes a single string: the value of an item or the key of a map.
+struct ValueDeserializer<'f> {
+    value: Cow<'f, str>,
+    path: String,
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident),*) => ($(
+        fn $method<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.value.parse() {
+                Ok(value) => visitor.$visit(value).map_err(|e| e.at(&self.path)),
+                Err(_) => Err(Error::new(&self.path, FieldErrorKind::BadValue, "invalid value"))
+            }
+        }
+    )*)
+}
+
+impl<'f> Deserializer<'f> for ValueDeserializer<'f> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        let path = self.path;
+        match self.value {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }.map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_bool<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &*self.value {
+            "true" | "on" => visitor.visit_bool(true),
+            "false" | "off" => visitor.visit_bool(false),
+            _ => Err(Error::new(&self.path, FieldErrorKind::BadValue, "invalid value"))
+        }
+    }
+
+    parse_value! {
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32, deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8, deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32, deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32, deserialize_f64 => visit_f64
+    }
+
+    fn deserialize_bytes<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        let path = self.path;
+        match self.value {
+            Cow::Borrowed(value) => visitor.visit_borrowed_bytes(value.as_bytes()),
+            Cow::Owned(value) => visitor.visit_byte_buf(value.into_bytes()),
+        }.map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value.is_empty() {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        let path = self.path;
+        let variant: de::value::CowStrDeserializer<'f, Error> = self.value.into_deserializer();
+        visitor.visit_enum(variant).map_err(|e| e.at(&path))
+    }
+
+    forward_to_deserialize_any! {
+        <V: Visitor<'f>>
+        char str string seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Deserializes the value at a node of the form.
+struct NodeDeserializer<'a, 'f> {
+    node: &'a Node<'f>,
+    path: String,
+    context: &'a Context,
+}
+
+macro_rules! forward_to_value {
+    ($($method:ident),*) => ($(
+        fn $method<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.value()?.$method(visitor)
+        }
+    )*)
+}
+
+impl<'a, 'f> NodeDeserializer<'a, 'f> {
+    fn unexpected(&self, path: String) {
+        if self.context.strict {
+            self.context.unknown.borrow_mut().push(path);
+        }
+    }
+
+    /// Returns a deserializer for the node's value. Any components following
+    /// the node's key are unexpected.
+    fn value(&self) -> Result<ValueDeserializer<'f>, Error> {
+        for (key, _) in &self.node.children {
+            self.unexpected(field_path(&self.path, key));
+        }
+
+        match self.node.values.last() {
+            Some(&value) => Ok(ValueDeserializer { value: decode(value), path: self.path.clone() }),
+            None => Err(Error::new(&self.path, FieldErrorKind::BadValue, "expected a value"))
+        }
+    }
+
+    fn map(self) -> MapDeserializer<'a, 'f> {
+        if !self.node.values.is_empty() {
+            self.unexpected(self.path.clone());
+        }
+
+        MapDeserializer {
+            children: self.node.children.iter(),
+            next: None,
+            path: self.path,
+            context: self.context
+        }
+    }
+}
+
+impl<'a, 'f> Deserializer<'f> for NodeDeserializer<'a, 'f> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.node.children.is_empty() {
+            true => self.value()?.deserialize_any(visitor),
+            false => self.deserialize_map(visitor)
+        }
+    }
+
+    forward_to_value! {
+        deserialize_bool, deserialize_i8, deserialize_i16, deserialize_i32,
+        deserialize_i64, deserialize_u8, deserialize_u16, deserialize_u32,
+        deserialize_u64, deserialize_f32, deserialize_f64, deserialize_char,
+        deserialize_str, deserialize_string, deserialize_bytes,
+        deserialize_byte_buf, deserialize_unit, deserialize_identifier
+    }
+
+    fn deserialize_option<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        let empty = self.node.values.last().map_or(true, |value| value.is_empty());
+        match self.node.children.is_empty() && empty {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        // Elements are ordered by index when every index is a number.
+        let mut children = self.node.children.iter().collect::<Vec<_>>();
+        if children.iter().all(|(key, _)| key.parse::<usize>().is_ok()) {
+            children.sort_by_key(|(key, _)| key.parse::<usize>().unwrap_or(0));
+        }
+
+        let path = self.path.clone();
+        visitor.visit_seq(SeqDeserializer {
+            values: self.node.values.iter(),
+            children: children.into_iter(),
+            index: 0,
+            path: self.path,
+            context: self.context
+        }).map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_tuple<V: Visitor<'f>>(self, _: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        let path = self.path.clone();
+        visitor.visit_map(self.map()).map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_struct<V: Visitor<'f>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        for (key, _) in &self.node.children {
+            let method = self.path.is_empty() && key == "_method";
+            if !method && !fields.iter().any(|field| *field == &**key) {
+                self.unexpected(field_path(&self.path, key));
+            }
+        }
+
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'f>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Error> {
+        self.value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'f>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqDeserializer<'a, 'f> {
+    values: std::slice::Iter<'a, &'f RawStr>,
+    children: std::vec::IntoIter<&'a (Cow<'f, str>, Node<'f>)>,
+    index: usize,
+    path: String,
+    context: &'a Context,
+}
+
+impl<'a, 'f> de::SeqAccess<'f> for SeqDeserializer<'a, 'f> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+        where T: de::DeserializeSeed<'f>
+    {
+        let path = field_path(&self.path, &self.index.to_string());
+        self.index += 1;
+        if let Some(value) = self.values.next() {
+            let node = Node { values: vec![*value], children: vec![] };
+            let de = NodeDeserializer { node: &node, path, context: self.context };
+            return seed.deserialize(de).map(Some);
+        }
+
+        match self.children.next() {
+            Some((_, node)) => {
+                let de = NodeDeserializer { node, path, context: self.context };
+                seed.deserialize(de).map(Some)
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len() + self.children.len())
+    }
+}
+
+struct MapDeserializer<'a, 'f> {
+    children: std::slice::Iter<'a, (Cow<'f, str>, Node<'f>)>,
+    next: Option<&'a (Cow<'f, str>, Node<'f>)>,
+    path: String,
+    context: &'a Context,
+}
+
+impl<'a, 'f> de::MapAccess<'f> for MapDeserializer<'a, 'f> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+        where K: de::DeserializeSeed<'f>
+    {
+        match self.children.next() {
+            Some(entry) => {
+                self.next = Some(entry);
+                let value = entry.0.clone();
+                let path = field_path(&self.path, &value);
+                seed.deserialize(ValueDeserializer { value, path }).map(Some)
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+        where V: de::DeserializeSeed<'f>
+    {
+        let (key, node) = self.next.take().expect("next_value_seed called before next_key_seed");
+        let path = field_path(&self.path, key);
+        seed.deserialize(NodeDeserializer { node, path, context: self.context })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.children.len())
+    }
+}
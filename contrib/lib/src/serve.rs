@@ -110,6 +110,13 @@ impl std::ops::BitOr for Options {
 /// The handler's functionality can be customized by passing an [`Options`] to
 /// [`StaticFiles::new()`].
 ///
+/// # Caching and Ranges
+///
+/// Files are served with [`NamedFile`], so responses carry the `ETag` and
+/// `Last-Modified` validators enabled in the `validators` configuration
+/// parameter, conditional requests are answered with a `304 Not Modified`, and
+/// `Range` requests are honored.
+///
 /// # Example
 ///
 /// To serve files from the `/static` local file system directory at the
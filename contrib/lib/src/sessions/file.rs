@@ -0,0 +1,148 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::{RngCore, rngs::OsRng};
+
+use super::{SessionStore, SessionRecord};
+
+/// A session store that keeps each session in a file.
+///
+/// Each session is stored in a file named after the session ID in a single
+/// directory. The first line of a file holds the session's expiry as seconds
+/// since the UNIX epoch, the second its user as a JSON string or `null`, and
+/// the remainder the session data. Files are replaced atomically on save.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::sessions::{Sessions, FileStore};
+///
+/// let store = FileStore::new("/var/lib/my-app/sessions").expect("session directory");
+/// # #[allow(unused_variables)]
+/// let rocket = rocket::ignite().attach(Sessions::fairing(store));
+/// ```
+#[derive(Debug)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Returns a `FileStore` that keeps sessions in the directory `dir`,
+    /// creating the directory if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` cannot be created.
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<FileStore> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(FileStore { dir })
+    }
+
+    fn path(&self, id: &str) -> io::Result<PathBuf> {
+        // Session IDs are generated by us, but the ID passed to `load` comes
+        // from a (decrypted) cookie. Never let one escape the directory.
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid session ID"));
+        }
+
+        Ok(self.dir.join(id))
+    }
+
+    fn read(path: &Path) -> io::Result<SessionRecord> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed session file");
+
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.splitn(3, '\n');
+        let secs = lines.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        let user = lines.next().and_then(|s| serde_json::from_str(s).ok()).ok_or_else(invalid)?;
+        let data = lines.next().ok_or_else(invalid)?.to_string();
+        let expires = UNIX_EPOCH + Duration::from_secs(secs);
+        Ok(SessionRecord { data, user, expires })
+    }
+
+    fn records(&self) -> io::Result<Vec<(PathBuf, SessionRecord)>> {
+        let mut records = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some() {
+                continue;
+            }
+
+            match FileStore::read(&path) {
+                Ok(record) => records.push((path, record)),
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(records)
+    }
+
+    fn remove_where<F>(&self, f: F) -> io::Result<usize>
+        where F: Fn(&SessionRecord) -> bool
+    {
+        let mut removed = 0;
+        for (path, record) in self.records()? {
+            if f(&record) {
+                match fs::remove_file(path) {
+                    Ok(()) => removed += 1,
+                    Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load(&self, id: &str) -> io::Result<Option<SessionRecord>> {
+        match FileStore::read(&self.path(id)?) {
+            Ok(record) => Ok(Some(record)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()> {
+        let secs = record.expires.duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let user = serde_json::to_string(&record.user)?;
+        let contents = format!("{}\n{}\n{}", secs, user, record.data);
+
+        // Write to a temporary file first so readers never see a partial file.
+        // Each save gets its own temporary file so that concurrent saves of
+        // the same session can't interleave their writes.
+        let path = self.path(id)?;
+        let tmp = self.dir.join(format!("{}.{:016x}.tmp", id, OsRng.next_u64()));
+        let result = fs::write(&tmp, contents).and_then(|_| fs::rename(&tmp, &path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+
+        result
+    }
+
+    fn remove(&self, id: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(id)?) {
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn remove_user(&self, user: &str) -> io::Result<usize> {
+        self.remove_where(|record| record.user.as_ref().map(|u| u.as_str()) == Some(user))
+    }
+
+    fn remove_expired(&self, now: SystemTime) -> io::Result<usize> {
+        self.remove_where(|record| record.expires <= now)
+    }
+}
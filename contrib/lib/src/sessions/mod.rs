@@ -0,0 +1,425 @@
+//! Server-side sessions with pluggable storage.
+//!
+//! This module provides the [`Session`] request guard, which gives a handler
+//! typed access to the data of the client's session, and a
+//! [fairing](Sessions::fairing()) that configures sessions for an application.
+//! Only a randomly generated session ID is sent to the client, in a private
+//! cookie. Session data lives on the server in a [`SessionStore`], so its size
+//! is not limited by the size of a cookie, and sessions can be revoked by the
+//! server at any time.
+//!
+//! Three stores are provided:
+//!
+//!   * [`MemoryStore`]: sessions are kept in memory and lost on restart.
+//!   * [`FileStore`]: each session is kept in a file in a directory.
+//!   * [`SqliteStore`]: sessions are kept in a SQLite table. Requires the
+//!     `sqlite_sessions` feature.
+//!
+//! Other stores can be implemented via the [`SessionStore`] trait.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `sessions` feature is enabled. The
+//! SQLite store additionally requires the `sqlite_sessions` feature. Enable
+//! them in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["sessions"]
+//! ```
+//!
+//! # Usage
+//!
+//! Attach the fairing returned by [`Sessions::fairing()`] with a store of
+//! choice, then use [`Session<T>`](Session) as a request guard, where `T` is
+//! the type of the session data:
+//!
+//! ```rust
+//! # #![feature(proc_macro_hygiene)]
+//! # #[macro_use] extern crate rocket;
+//! # extern crate rocket_contrib;
+//! use std::io;
+//!
+//! use rocket_contrib::sessions::{Session, Sessions, MemoryStore};
+//!
+//! #[get("/visits")]
+//! fn visits(mut session: Session<u64>) -> io::Result<String> {
+//!     let count = session.get().cloned().unwrap_or(0) + 1;
+//!     session.set(count)?;
+//!     Ok(format!("visit #{}", count))
+//! }
+//!
+//! fn main() {
+//!     rocket::ignite()
+//!         .attach(Sessions::fairing(MemoryStore::new()))
+//!         .mount("/", routes![visits])
+//!         # ;
+//! }
+//! ```
+//!
+//! # Configuration
+//!
+//! Sessions expire after a time-to-live, 24 hours by default, that is renewed
+//! whenever session data is written. The TTL and the name of the session
+//! cookie can be set via the `sessions` configuration table:
+//!
+//! ```toml
+//! [global.sessions]
+//! ttl = 3600                  # seconds
+//! cookie = "rocket_session"
+//! ```
+//!
+//! # Security
+//!
+//! The session ID should change whenever the privilege level of a session
+//! changes, such as on login, to prevent session fixation attacks.
+//! [`Session::set_user()`] does this automatically; [`Session::regenerate()`]
+//! can be used to do so manually. All sessions belonging to a user can be
+//! revoked, for instance on a password change, via [`Sessions::revoke_user()`].
+
+mod store;
+mod file;
+#[cfg(feature = "sqlite_sessions")] mod sqlite;
+
+pub use self::store::{SessionStore, SessionRecord, MemoryStore};
+pub use self::file::FileStore;
+#[cfg(feature = "sqlite_sessions")] pub use self::sqlite::SqliteStore;
+
+use std::io;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::{RngCore, rngs::OsRng};
+use serde::{Serialize, de::DeserializeOwned};
+
+use rocket::{Request, State, Outcome};
+use rocket::config::{ConfigError, Value};
+use rocket::fairing::{AdHoc, Fairing};
+use rocket::request::{self, FromRequest};
+use rocket::http::{Cookie, Status};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_COOKIE: &str = "rocket_session";
+
+/// The session configuration and store of an application.
+///
+/// A `Sessions` is managed by Rocket once the fairing returned by
+/// [`Sessions::fairing()`] is attached. It can be retrieved with a
+/// `State<Sessions>` request guard to manage sessions other than the current
+/// request's, for instance to revoke all of a user's sessions:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::io;
+///
+/// use rocket::State;
+/// use rocket_contrib::sessions::Sessions;
+///
+/// #[post("/users/<user>/revoke")]
+/// fn revoke(user: String, sessions: State<Sessions>) -> io::Result<String> {
+///     let count = sessions.revoke_user(&user)?;
+///     Ok(format!("revoked {} sessions", count))
+/// }
+/// # fn main() {  }
+/// ```
+pub struct Sessions {
+    store: Box<dyn SessionStore>,
+    ttl: Duration,
+    cookie_name: String,
+}
+
+impl Sessions {
+    /// Returns a fairing that initializes sessions using `store` to keep
+    /// session data. The fairing reads the `sessions` configuration table as
+    /// described in the [module documentation](self) and removes expired
+    /// sessions from `store`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use rocket_contrib::sessions::{Sessions, MemoryStore};
+    ///
+    /// fn main() {
+    ///     rocket::ignite()
+    ///         .attach(Sessions::fairing(MemoryStore::new()))
+    ///         // ...
+    ///     # ;
+    /// }
+    /// ```
+    pub fn fairing<S: SessionStore>(store: S) -> impl Fairing {
+        AdHoc::on_attach("Sessions", move |rocket| {
+            let mut sessions = Sessions {
+                store: Box::new(store),
+                ttl: DEFAULT_TTL,
+                cookie_name: DEFAULT_COOKIE.into(),
+            };
+
+            match rocket.config().get_table("sessions") {
+                Ok(table) => sessions.configure(table),
+                Err(ConfigError::Missing(_)) => { /* ignore missing */ }
+                Err(e) => {
+                    e.pretty_print();
+                    warn_!("Using default session configuration.");
+                }
+            }
+
+            match sessions.purge_expired() {
+                Ok(n) if n > 0 => info_!("Removed {} expired sessions.", n),
+                Ok(_) => { /* nothing to report */ }
+                Err(e) => warn_!("Failed to remove expired sessions: {}", e),
+            }
+
+            Ok(rocket.manage(sessions))
+        })
+    }
+
+    fn configure(&mut self, table: &rocket::config::Table) {
+        match table.get("ttl") {
+            Some(Value::Integer(secs)) if *secs > 0 => {
+                self.ttl = Duration::from_secs(*secs as u64);
+            }
+            Some(value) => warn_!("Ignoring invalid session TTL '{}'.", value),
+            None => { /* use the default */ }
+        }
+
+        match table.get("cookie") {
+            Some(Value::String(name)) if !name.is_empty() => {
+                self.cookie_name = name.clone();
+            }
+            Some(value) => warn_!("Ignoring invalid session cookie name '{}'.", value),
+            None => { /* use the default */ }
+        }
+    }
+
+    /// Revokes all sessions associated with the user `user` via
+    /// [`Session::set_user()`]. Returns the number of sessions revoked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session store fails to remove the sessions.
+    pub fn revoke_user(&self, user: &str) -> io::Result<usize> {
+        self.store.remove_user(user)
+    }
+
+    /// Removes all expired sessions from the store, returning the number of
+    /// sessions removed. Expired sessions are never returned to a request, but
+    /// they may linger in a store until removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session store fails to remove the sessions.
+    pub fn purge_expired(&self) -> io::Result<usize> {
+        self.store.remove_expired(SystemTime::now())
+    }
+
+    fn expiry(&self) -> SystemTime {
+        // Rounded to the second so that stores needn't keep subseconds.
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        UNIX_EPOCH + Duration::from_secs(now.as_secs()) + self.ttl
+    }
+}
+
+impl fmt::Debug for Sessions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sessions")
+            .field("ttl", &self.ttl)
+            .field("cookie_name", &self.cookie_name)
+            .finish()
+    }
+}
+
+/// Request guard for the session of the current request.
+///
+/// A `Session<T>` provides access to session data of type `T` which must be
+/// (de)serializable with `serde`. The guard always succeeds once the
+/// [`Sessions`] fairing is attached; if the client has no valid session, the
+/// session is empty until data is [set](Session::set()). If the fairing isn't
+/// attached, the guard fails with `Status::InternalServerError`.
+///
+/// Changes are written to the store immediately. Because changes may set the
+/// session cookie, a [`Cookies`](rocket::http::Cookies) instance must not be
+/// alive while a `Session` is modified, and at most one `Session` guard should
+/// be used per request.
+///
+/// See the [module documentation](self) for a usage example.
+pub struct Session<'a, 'r, T> {
+    request: &'a Request<'r>,
+    sessions: &'a Sessions,
+    id: Option<String>,
+    user: Option<String>,
+    data: Option<T>,
+}
+
+impl<'a, 'r, T: Serialize + DeserializeOwned> Session<'a, 'r, T> {
+    /// Returns the ID of the session, or `None` if the session is empty.
+    #[inline]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_ref().map(|id| id.as_str())
+    }
+
+    /// Returns the session data, if any.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.data.as_ref()
+    }
+
+    /// Returns the user associated with the session, if any.
+    #[inline]
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_ref().map(|user| user.as_str())
+    }
+
+    /// Sets the session data to `data` and renews the session's expiry. If the
+    /// session is empty, a new session is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` fails to serialize or the store fails to
+    /// save the session.
+    pub fn set(&mut self, data: T) -> io::Result<()> {
+        self.data = Some(data);
+        self.save()
+    }
+
+    /// Associates the session with the user `user`, which allows it to be
+    /// revoked via [`Sessions::revoke_user()`]. Since this marks a change in
+    /// privilege, the session ID is [regenerated](Session::regenerate()). If
+    /// the session is empty, a new session is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails to save the session.
+    pub fn set_user<U: Into<String>>(&mut self, user: U) -> io::Result<()> {
+        self.user = Some(user.into());
+        self.regenerate()
+    }
+
+    /// Assigns a new ID to the session, invalidating the old one, and renews
+    /// the session's expiry. If the session is empty, a new session is started.
+    ///
+    /// The session ID should be regenerated whenever the session's privilege
+    /// level changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails to save the session.
+    pub fn regenerate(&mut self) -> io::Result<()> {
+        if let Some(old_id) = self.id.take() {
+            self.sessions.store.remove(&old_id)?;
+        }
+
+        self.save()
+    }
+
+    /// Destroys the session, removing it from the store and removing the
+    /// session cookie from the client. The session is empty afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails to remove the session.
+    pub fn destroy(&mut self) -> io::Result<()> {
+        self.data = None;
+        self.user = None;
+        if let Some(id) = self.id.take() {
+            self.sessions.store.remove(&id)?;
+            let cookie = Cookie::named(self.sessions.cookie_name.clone());
+            self.request.cookies().remove_private(cookie);
+        }
+
+        Ok(())
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        let data = serde_json::to_string(&self.data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let record = SessionRecord {
+            data,
+            user: self.user.clone(),
+            expires: self.sessions.expiry(),
+        };
+
+        let id = self.id.get_or_insert_with(generate_id);
+        self.sessions.store.save(id, &record)?;
+
+        // The cookie is always reissued so that its expiry is renewed too.
+        let cookie = Cookie::build(self.sessions.cookie_name.clone(), id.clone())
+            .max_age(time::Duration::seconds(self.sessions.ttl.as_secs() as i64))
+            .finish();
+
+        self.request.cookies().add_private(cookie);
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Session<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("user", &self.user)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<'a, 'r, T: Serialize + DeserializeOwned> FromRequest<'a, 'r> for Session<'a, 'r, T> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let sessions = match request.guard::<State<'_, Sessions>>().succeeded() {
+            Some(sessions) => sessions.inner(),
+            None => {
+                error_!("Uninitialized sessions: missing fairing.");
+                info_!("To use sessions, you must attach `Sessions::fairing()`.");
+                info_!("See the `sessions` documentation for more information.");
+                return Outcome::Failure((Status::InternalServerError, ()));
+            }
+        };
+
+        let mut session = Session { request, sessions, id: None, user: None, data: None };
+        let id = match request.cookies().get_private(&sessions.cookie_name) {
+            Some(cookie) => cookie.value().to_string(),
+            None => return Outcome::Success(session),
+        };
+
+        let record = match sessions.store.load(&id) {
+            Ok(Some(record)) => record,
+            Ok(None) => return Outcome::Success(session),
+            Err(e) => {
+                error_!("Failed to load session: {}", e);
+                return Outcome::Failure((Status::InternalServerError, ()));
+            }
+        };
+
+        if record.expires <= SystemTime::now() {
+            if let Err(e) = sessions.store.remove(&id) {
+                warn_!("Failed to remove expired session: {}", e);
+            }
+
+            return Outcome::Success(session);
+        }
+
+        match serde_json::from_str(&record.data) {
+            Ok(data) => {
+                session.id = Some(id);
+                session.user = record.user;
+                session.data = data;
+            }
+            Err(e) => warn_!("Ignoring session with malformed data: {}", e),
+        }
+
+        Outcome::Success(session)
+    }
+}
+
+/// Generates a new session ID: 256 random bits, hex encoded.
+fn generate_id() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
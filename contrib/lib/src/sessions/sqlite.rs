@@ -0,0 +1,125 @@
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, Error};
+use rusqlite::types::ToSql;
+
+use super::{SessionStore, SessionRecord};
+
+/// A session store that keeps sessions in a SQLite database.
+///
+/// Sessions are stored in a table named `rocket_sessions`, which is created if
+/// it does not exist. The table can share a database with application data.
+///
+/// This store is only available when the `sqlite_sessions` feature is enabled.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::sessions::{Sessions, SqliteStore};
+///
+/// let store = SqliteStore::open("db.sqlite").expect("session database");
+/// # #[allow(unused_variables)]
+/// let rocket = rocket::ignite().attach(Sessions::fairing(store));
+/// ```
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+fn io_error(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+fn to_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+impl SqliteStore {
+    /// Opens the SQLite database at `path` and returns a store that keeps
+    /// sessions in it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or the sessions table
+    /// cannot be created.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<SqliteStore> {
+        SqliteStore::new(Connection::open(path).map_err(io_error)?)
+    }
+
+    /// Returns a store that keeps sessions in the database connected to via
+    /// `conn`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sessions table cannot be created.
+    pub fn new(conn: Connection) -> io::Result<SqliteStore> {
+        conn.execute("CREATE TABLE IF NOT EXISTS rocket_sessions (
+                      id              TEXT PRIMARY KEY,
+                      user            TEXT,
+                      expires         INTEGER NOT NULL,
+                      data            TEXT NOT NULL
+                      )", &[] as &[&dyn ToSql])
+            .map_err(io_error)?;
+
+        Ok(SqliteStore { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> io::Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn.lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "session store lock poisoned"))
+    }
+}
+
+impl SessionStore for SqliteStore {
+    fn load(&self, id: &str) -> io::Result<Option<SessionRecord>> {
+        let result = self.conn()?.query_row(
+            "SELECT user, expires, data FROM rocket_sessions WHERE id = $1",
+            &[&id as &dyn ToSql],
+            |row| (row.get(0), row.get(1), row.get(2))
+        );
+
+        match result {
+            Ok((user, expires, data)) => {
+                let expires = UNIX_EPOCH + Duration::from_secs(i64::max(expires, 0) as u64);
+                Ok(Some(SessionRecord { data, user, expires }))
+            }
+            Err(Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()> {
+        self.conn()?.execute(
+            "INSERT OR REPLACE INTO rocket_sessions (id, user, expires, data)
+             VALUES ($1, $2, $3, $4)",
+            &[&id as &dyn ToSql, &record.user, &to_secs(record.expires), &record.data]
+        ).map_err(io_error)?;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> io::Result<()> {
+        self.conn()?
+            .execute("DELETE FROM rocket_sessions WHERE id = $1", &[&id as &dyn ToSql])
+            .map_err(io_error)?;
+
+        Ok(())
+    }
+
+    fn remove_user(&self, user: &str) -> io::Result<usize> {
+        self.conn()?
+            .execute("DELETE FROM rocket_sessions WHERE user = $1", &[&user as &dyn ToSql])
+            .map_err(io_error)
+    }
+
+    fn remove_expired(&self, now: SystemTime) -> io::Result<usize> {
+        self.conn()?
+            .execute("DELETE FROM rocket_sessions WHERE expires <= $1",
+                     &[&to_secs(now) as &dyn ToSql])
+            .map_err(io_error)
+    }
+}
@@ -0,0 +1,126 @@
+use std::io;
+use std::sync::RwLock;
+use std::time::SystemTime;
+use std::collections::HashMap;
+
+/// A session as kept in a [`SessionStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionRecord {
+    /// The session data, serialized as JSON.
+    pub data: String,
+    /// The user associated with the session, if any.
+    pub user: Option<String>,
+    /// The time after which the session is no longer valid. Always a whole
+    /// number of seconds after the UNIX epoch.
+    pub expires: SystemTime,
+}
+
+/// Trait implemented by session storage backends.
+///
+/// A `SessionStore` maps session IDs to [`SessionRecord`]s. Session IDs are
+/// 64-character, lowercase hexadecimal strings. Stores need not check whether
+/// a record has expired when loading it; expiry is checked by the
+/// [`Session`](super::Session) request guard.
+///
+/// All methods return an `io::Error` if the underlying storage fails.
+///
+/// # Example
+///
+/// A store that keeps no sessions at all:
+///
+/// ```rust
+/// # extern crate rocket_contrib;
+/// use std::io;
+/// use std::time::SystemTime;
+///
+/// use rocket_contrib::sessions::{SessionStore, SessionRecord};
+///
+/// struct NullStore;
+///
+/// impl SessionStore for NullStore {
+///     fn load(&self, _: &str) -> io::Result<Option<SessionRecord>> { Ok(None) }
+///     fn save(&self, _: &str, _: &SessionRecord) -> io::Result<()> { Ok(()) }
+///     fn remove(&self, _: &str) -> io::Result<()> { Ok(()) }
+///     fn remove_user(&self, _: &str) -> io::Result<usize> { Ok(0) }
+///     fn remove_expired(&self, _: SystemTime) -> io::Result<usize> { Ok(0) }
+/// }
+/// ```
+pub trait SessionStore: Send + Sync + 'static {
+    /// Returns the record for the session `id`, if there is one.
+    fn load(&self, id: &str) -> io::Result<Option<SessionRecord>>;
+
+    /// Saves `record` as the record for the session `id`, replacing any
+    /// existing record.
+    fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()>;
+
+    /// Removes the session `id`, if it exists.
+    fn remove(&self, id: &str) -> io::Result<()>;
+
+    /// Removes every session associated with `user`, returning the number of
+    /// sessions removed.
+    fn remove_user(&self, user: &str) -> io::Result<usize>;
+
+    /// Removes every session that expires at or before `now`, returning the
+    /// number of sessions removed.
+    fn remove_expired(&self, now: SystemTime) -> io::Result<usize>;
+}
+
+/// A session store that keeps sessions in memory.
+///
+/// Sessions are lost when the application exits.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket_contrib::sessions::{Sessions, MemoryStore};
+///
+/// # #[allow(unused_variables)]
+/// let rocket = rocket::ignite().attach(Sessions::fairing(MemoryStore::new()));
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    sessions: RwLock<HashMap<String, SessionRecord>>,
+}
+
+impl MemoryStore {
+    /// Returns a new, empty `MemoryStore`.
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+}
+
+fn poisoned<T>(_: T) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "session store lock poisoned")
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self, id: &str) -> io::Result<Option<SessionRecord>> {
+        Ok(self.sessions.read().map_err(poisoned)?.get(id).cloned())
+    }
+
+    fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()> {
+        self.sessions.write().map_err(poisoned)?.insert(id.into(), record.clone());
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> io::Result<()> {
+        self.sessions.write().map_err(poisoned)?.remove(id);
+        Ok(())
+    }
+
+    fn remove_user(&self, user: &str) -> io::Result<usize> {
+        let mut sessions = self.sessions.write().map_err(poisoned)?;
+        let len = sessions.len();
+        sessions.retain(|_, record| record.user.as_ref().map(|u| u.as_str()) != Some(user));
+        Ok(len - sessions.len())
+    }
+
+    fn remove_expired(&self, now: SystemTime) -> io::Result<usize> {
+        let mut sessions = self.sessions.write().map_err(poisoned)?;
+        let len = sessions.len();
+        sessions.retain(|_, record| record.expires > now);
+        Ok(len - sessions.len())
+    }
+}
@@ -156,6 +156,36 @@ mod compression_fairing_tests {
         assert_eq!(s, String::from(HELLO));
     }
 
+    #[test]
+    fn test_respects_zero_quality() {
+        let client = Client::new(rocket()).expect("valid rocket instance");
+        let mut response = client
+            .get("/")
+            .header(Header::new("Accept-Encoding", "gzip;q=0, br;q=0"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get("Content-Encoding").next().is_none());
+        assert_eq!(
+            String::from_utf8(response.body_bytes().unwrap()).unwrap(),
+            String::from(HELLO)
+        );
+
+        let mut response = client
+            .get("/")
+            .header(Header::new("Accept-Encoding", "br;q=0, gzip;q=0.5"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response
+            .headers()
+            .get("Content-Encoding")
+            .any(|x| x == "gzip"));
+        let mut s = String::new();
+        GzDecoder::new(&response.body_bytes().unwrap()[..])
+            .read_to_string(&mut s)
+            .expect("decompress response");
+        assert_eq!(s, String::from(HELLO));
+    }
+
     #[test]
     fn test_does_not_recompress() {
         let client = Client::new(rocket()).expect("valid rocket instance");
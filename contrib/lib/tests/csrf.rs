@@ -0,0 +1,241 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "csrf")]
+extern crate rocket;
+
+#[cfg(feature = "csrf")]
+mod csrf_tests {
+    use rocket::Rocket;
+    use rocket::http::{ContentType, Cookie, Header, Status};
+    use rocket::local::Client;
+    use rocket::request::FromForm;
+    use rocket_contrib::csrf::{Csrf, CsrfForm, CsrfToken, HEADER_NAME};
+
+    #[get("/token")]
+    fn token(token: CsrfToken, again: CsrfToken) -> String {
+        assert_eq!(token, again);
+        token.to_string()
+    }
+
+    #[post("/submit")]
+    fn submit() -> &'static str {
+        "submitted"
+    }
+
+    #[delete("/submit")]
+    fn delete() -> &'static str {
+        "deleted"
+    }
+
+    #[get("/submit")]
+    fn get() -> &'static str {
+        "read"
+    }
+
+    #[derive(FromForm)]
+    struct Message {
+        message: String,
+    }
+
+    #[post("/message", data = "<form>")]
+    fn message(form: CsrfForm<Message>) -> String {
+        form.into_inner().message
+    }
+
+    #[post("/webhooks/github")]
+    fn webhook() -> &'static str {
+        "hooked"
+    }
+
+    fn rocket() -> Rocket {
+        rocket::ignite()
+            .attach(Csrf::fairing().exempt("/webhooks/"))
+            .mount("/", routes![token, submit, delete, get, message, webhook])
+    }
+
+    fn client_with_token() -> (Client, String) {
+        let client = Client::new(rocket()).unwrap();
+        let token = client.get("/token").dispatch().body_string().unwrap();
+        assert_eq!(token.len(), 64);
+        (client, token)
+    }
+
+    #[test]
+    fn token_is_stable() {
+        let (client, token) = client_with_token();
+        let mut response = client.get("/token").dispatch();
+        assert!(response.headers().get_one("Set-Cookie").is_none());
+        assert_eq!(response.body_string(), Some(token));
+    }
+
+    #[test]
+    fn missing_token_is_forbidden() {
+        let (client, _) = client_with_token();
+        let response = client.post("/submit").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client.delete("/submit").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        // A token without the cookie is forbidden as well.
+        let client = Client::new(rocket()).unwrap();
+        let response = client.post("/submit")
+            .header(Header::new(HEADER_NAME, "0".repeat(64)))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn wrong_token_is_forbidden() {
+        let (client, token) = client_with_token();
+        let wrong = token.chars().rev().collect::<String>();
+        let response = client.post("/submit")
+            .header(Header::new(HEADER_NAME, wrong))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client.post("/submit")
+            .header(ContentType::Form)
+            .body("csrf_token=abc")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        // An unencrypted cookie doesn't count.
+        let client = Client::new(rocket()).unwrap();
+        let response = client.post("/submit")
+            .cookie(Cookie::new("csrf_token", "abc"))
+            .header(Header::new(HEADER_NAME, "abc"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn header_token_is_accepted() {
+        let (client, token) = client_with_token();
+        let mut response = client.post("/submit")
+            .header(Header::new(HEADER_NAME, token.clone()))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("submitted".into()));
+
+        let mut response = client.delete("/submit")
+            .header(Header::new(HEADER_NAME, token))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("deleted".into()));
+    }
+
+    #[test]
+    fn form_token_is_accepted() {
+        let (client, token) = client_with_token();
+        let mut response = client.post("/submit")
+            .header(ContentType::Form)
+            .body(format!("csrf_token={}&message=hi", token))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("submitted".into()));
+
+        // The method override is validated as well.
+        let response = client.post("/submit")
+            .header(ContentType::Form)
+            .body("_method=delete")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let mut response = client.post("/submit")
+            .header(ContentType::Form)
+            .body(format!("_method=delete&csrf_token={}", token))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("deleted".into()));
+    }
+
+    #[test]
+    fn safe_methods_are_not_checked() {
+        let client = Client::new(rocket()).unwrap();
+        let mut response = client.get("/submit").dispatch();
+        assert_eq!(response.body_string(), Some("read".into()));
+
+        let response = client.head("/submit").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn exempt_paths_are_not_checked() {
+        let client = Client::new(rocket()).unwrap();
+        let mut response = client.post("/webhooks/github").dispatch();
+        assert_eq!(response.body_string(), Some("hooked".into()));
+    }
+    #[test]
+    fn large_form_token_is_checked_by_guard() {
+        let (client, token) = client_with_token();
+        let long = "a".repeat(1024);
+        let mut response = client.post("/message")
+            .header(ContentType::Form)
+            .body(format!("message={}&csrf_token={}", long, token))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some(long.clone()));
+
+        let response = client.post("/message")
+            .header(ContentType::Form)
+            .body(format!("message={}", long))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let wrong = token.chars().rev().collect::<String>();
+        let response = client.post("/message")
+            .header(ContentType::Form)
+            .body(format!("message={}&csrf_token={}", long, wrong))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn multipart_form_token_is_checked_by_guard() {
+        const BOUNDARY: &str = "X-BOUNDARY";
+
+        fn body(parts: &[(&str, &str)]) -> String {
+            let mut body = String::new();
+            for &(name, value) in parts {
+                body.push_str(&format!("--{}\r\nContent-Disposition: form-data; \
+                    name=\"{}\"\r\n\r\n{}\r\n", BOUNDARY, name, value));
+            }
+
+            body.push_str(&format!("--{}--\r\n", BOUNDARY));
+            body
+        }
+
+        let multipart = ContentType::with_params("multipart", "form-data", ("boundary", BOUNDARY));
+        let (client, token) = client_with_token();
+        let mut response = client.post("/message")
+            .header(multipart.clone())
+            .body(body(&[("message", "hi"), ("csrf_token", token.as_str())]))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("hi".into()));
+
+        let response = client.post("/message")
+            .header(multipart.clone())
+            .body(body(&[("message", "hi")]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let mut response = client.post("/message")
+            .header(multipart)
+            .header(Header::new(HEADER_NAME, token))
+            .body(body(&[("message", "hi")]))
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some("hi".into()));
+    }
+}
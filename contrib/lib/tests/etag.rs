@@ -0,0 +1,96 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "etag")]
+extern crate rocket;
+
+#[cfg(feature = "etag")]
+mod etag_tests {
+    use std::io::Cursor;
+
+    use rocket::Rocket;
+    use rocket::http::{Cookie, Cookies, Header, Status};
+    use rocket::local::Client;
+    use rocket::response::{Stream, Tagged};
+    use rocket_contrib::etag::ETag;
+
+    #[get("/")]
+    fn index() -> &'static str {
+        "Hello, world!"
+    }
+
+    #[get("/large")]
+    fn large() -> String {
+        "a".repeat(2048)
+    }
+
+    #[get("/stream")]
+    fn stream() -> Stream<Cursor<&'static str>> {
+        Stream::from(Cursor::new("streamed"))
+    }
+
+    #[get("/tagged")]
+    fn tagged() -> Tagged<&'static str> {
+        Tagged::hashed("tagged")
+    }
+
+    #[get("/cookie")]
+    fn cookie(mut cookies: Cookies<'_>) -> &'static str {
+        cookies.add(Cookie::new("a", "b"));
+        "cookie"
+    }
+
+    #[get("/missing")]
+    fn missing() -> Status {
+        Status::NotFound
+    }
+
+    fn rocket() -> Rocket {
+        rocket::ignite()
+            .attach(ETag::fairing().max_size(1024))
+            .mount("/", routes![index, large, stream, tagged, cookie, missing])
+    }
+
+    #[test]
+    fn test_tags_and_not_modified() {
+        let client = Client::new(rocket()).unwrap();
+        let mut response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("Hello, world!".into()));
+        let etag = response.headers().get_one("ETag").expect("ETag").to_string();
+
+        // The tag is stable across requests.
+        let response = client.get("/").dispatch();
+        assert_eq!(response.headers().get_one("ETag"), Some(&*etag));
+
+        let mut response = client.get("/").header(Header::new("If-None-Match", etag.clone())).dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("ETag"), Some(&*etag));
+        assert!(response.body_bytes().map_or(true, |body| body.is_empty()));
+
+        let response = client.head("/").header(Header::new("If-None-Match", etag)).dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+
+        let mut response = client.get("/").header(Header::new("If-None-Match", "\"x\"")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("Hello, world!".into()));
+    }
+
+    #[test]
+    fn test_skipped_responses() {
+        let client = Client::new(rocket()).unwrap();
+        for path in &["/large", "/stream", "/cookie", "/missing"] {
+            let response = client.get(*path).dispatch();
+            assert!(response.headers().get_one("ETag").is_none(), "{} was tagged", path);
+        }
+
+        // An existing validator is kept.
+        let response = client.get("/tagged").dispatch();
+        let expected = Tagged::hashed("tagged").0.to_string();
+        assert_eq!(response.headers().get_one("ETag"), Some(&*expected));
+
+        // Unsafe methods aren't tagged.
+        let response = client.post("/").dispatch();
+        assert!(response.headers().get_one("ETag").is_none());
+    }
+}
@@ -0,0 +1,94 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "json")]
+extern crate rocket;
+
+#[macro_use]
+#[cfg(feature = "json")]
+extern crate rocket_contrib;
+
+#[cfg(feature = "json")]
+mod json_problem_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::Client;
+    use rocket_contrib::json::{Problem, problem_catchers};
+
+    #[get("/credit")]
+    fn credit() -> Problem {
+        Problem::new(Status::Forbidden)
+            .type_uri("https://example.com/probs/out-of-credit")
+            .title("You do not have enough credit.")
+            .detail("Your current balance is 30, but that costs 50.")
+            .instance("/account/12345/msgs/abc")
+            .extension("balance", 30)
+            .extension("accounts", json!(["/account/12345", "/account/67890"]))
+            .extension("status", 200)
+    }
+
+    #[get("/blank")]
+    fn blank() -> Problem {
+        Problem::from(Status::Conflict)
+    }
+
+    #[get("/fail")]
+    fn fail() -> Result<(), Status> {
+        Err(Status::ServiceUnavailable)
+    }
+
+    fn problem_content_type() -> ContentType {
+        ContentType::new("application", "problem+json")
+    }
+
+    fn json_body(body: Option<String>) -> serde_json::Value {
+        serde_json::from_str(&body.expect("body")).expect("valid JSON")
+    }
+
+    #[test]
+    fn problem_responds_with_members() {
+        let client = Client::new(rocket::ignite().mount("/", routes![credit, blank])).unwrap();
+        let mut response = client.get("/credit").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(response.content_type(), Some(problem_content_type()));
+        assert_eq!(json_body(response.body_string()), serde_json::json!({
+            "type": "https://example.com/probs/out-of-credit",
+            "title": "You do not have enough credit.",
+            "status": 403,
+            "detail": "Your current balance is 30, but that costs 50.",
+            "instance": "/account/12345/msgs/abc",
+            "balance": 30,
+            "accounts": ["/account/12345", "/account/67890"]
+        }));
+
+        let mut response = client.get("/blank").dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+        assert_eq!(json_body(response.body_string()), serde_json::json!({
+            "type": "about:blank",
+            "title": "Conflict",
+            "status": 409
+        }));
+    }
+
+    #[test]
+    fn catchers_render_problems() {
+        let rocket = rocket::ignite()
+            .mount("/", routes![fail])
+            .register(problem_catchers());
+
+        let client = Client::new(rocket).unwrap();
+        let mut response = client.get("/missing").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.content_type(), Some(problem_content_type()));
+        assert_eq!(json_body(response.body_string()), serde_json::json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404
+        }));
+
+        let mut response = client.get("/fail").dispatch();
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        let body = json_body(response.body_string());
+        assert_eq!(body["status"], 503);
+        assert_eq!(body["title"], "Service Unavailable");
+    }
+}
@@ -0,0 +1,110 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "json")]
+extern crate rocket;
+
+#[cfg(feature = "json")]
+mod json_streams_tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::Client;
+    use rocket_contrib::json::{JsonLines, JsonArrayStream, OnError};
+
+    use serde::{Serialize, Serializer};
+    use serde::ser::Error;
+
+    /// Serializes as its number, unless the number is odd.
+    struct Even(usize);
+
+    impl Serialize for Even {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.0 % 2 {
+                0 => serializer.serialize_u64(self.0 as u64),
+                _ => Err(S::Error::custom("odd number")),
+            }
+        }
+    }
+
+    #[get("/lines")]
+    fn lines() -> JsonLines<Vec<&'static str>> {
+        JsonLines::new(vec!["a", "b", "c"])
+    }
+
+    #[get("/array")]
+    fn array() -> JsonArrayStream<std::ops::Range<usize>> {
+        JsonArrayStream::new(0..3)
+    }
+
+    #[get("/empty")]
+    fn empty() -> JsonArrayStream<Vec<usize>> {
+        JsonArrayStream::new(vec![])
+    }
+
+    #[get("/large")]
+    fn large() -> JsonLines<impl Iterator<Item = usize>> {
+        JsonLines::new(0..100_000)
+    }
+
+    #[get("/skip")]
+    fn skip() -> JsonArrayStream<impl Iterator<Item = Even>> {
+        JsonArrayStream::new((0..5).map(Even)).on_error(OnError::Skip)
+    }
+
+    #[get("/abort")]
+    fn abort() -> JsonArrayStream<impl Iterator<Item = Even>> {
+        JsonArrayStream::new((0..5).map(Even))
+    }
+
+    #[get("/abort_lines")]
+    fn abort_lines() -> JsonLines<impl Iterator<Item = Even>> {
+        JsonLines::new(vec![0, 2, 3, 4].into_iter().map(Even))
+    }
+
+    fn client() -> Client {
+        let routes = routes![lines, array, empty, large, skip, abort, abort_lines];
+        Client::new(rocket::ignite().mount("/", routes)).unwrap()
+    }
+
+    #[test]
+    fn streams_json_lines() {
+        let client = client();
+        let mut response = client.get("/lines").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "x-ndjson")));
+        assert_eq!(response.body_string(), Some("\"a\"\n\"b\"\n\"c\"\n".into()));
+    }
+
+    #[test]
+    fn streams_json_arrays() {
+        let client = client();
+        let mut response = client.get("/array").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.body_string(), Some("[0,1,2]".into()));
+
+        let mut response = client.get("/empty").dispatch();
+        assert_eq!(response.body_string(), Some("[]".into()));
+    }
+
+    #[test]
+    fn streams_many_items() {
+        let client = client();
+        let mut response = client.get("/large").dispatch();
+        let body = response.body_string().unwrap();
+        assert_eq!(body.lines().count(), 100_000);
+        assert!(body.ends_with("99998\n99999\n"));
+    }
+
+    #[test]
+    fn skips_or_aborts_on_errors() {
+        let client = client();
+        let mut response = client.get("/skip").dispatch();
+        assert_eq!(response.body_string(), Some("[0,2,4]".into()));
+
+        let mut response = client.get("/abort").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[0".into()));
+
+        let mut response = client.get("/abort_lines").dispatch();
+        assert_eq!(response.body_string(), Some("0\n2\n".into()));
+    }
+}
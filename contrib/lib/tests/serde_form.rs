@@ -0,0 +1,215 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "serde_form")]
+extern crate rocket;
+
+#[macro_use]
+#[cfg(feature = "serde_form")]
+extern crate serde_derive;
+
+#[cfg(feature = "serde_form")]
+mod serde_form_tests {
+    use std::collections::HashMap;
+
+    use rocket::Rocket;
+    use rocket::config::{Config, Environment, Limits};
+    use rocket::http::{ContentType, Status};
+    use rocket::local::Client;
+    use rocket::request::{FormError, FormDataError, FieldErrorKind::*};
+    use rocket_contrib::serde_form::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Plan {
+        Free,
+        Pro,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address {
+        city: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Signup<'r> {
+        name: String,
+        handle: &'r str,
+        age: u8,
+        plan: Plan,
+        #[serde(default)]
+        newsletter: bool,
+        address: Address,
+        tags: Vec<String>,
+        #[serde(default)]
+        scores: HashMap<String, f32>,
+    }
+
+    fn errors<T>(result: Result<T, FormError<'_>>) -> Vec<(String, rocket::request::FieldErrorKind)> {
+        match result {
+            Err(FormDataError::Parse(errors, _)) => {
+                errors.into_iter().map(|e| (e.path, e.kind)).collect()
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn deserializes_nested_values() {
+        let form = "name=Jane+Doe&handle=jd&age=30&plan=pro&newsletter=on&\
+            address.city=S%C3%A3o+Paulo&address[zip]=1234&tags[]=a&tags[]=b&\
+            scores.math=9.5&scores%5Bart%5D=7";
+
+        let signup: Signup<'_> = from_form_str(form, true).unwrap();
+        let mut scores = HashMap::new();
+        scores.insert("math".to_string(), 9.5);
+        scores.insert("art".to_string(), 7.0);
+        assert_eq!(signup, Signup {
+            name: "Jane Doe".into(),
+            handle: "jd",
+            age: 30,
+            plan: Plan::Pro,
+            newsletter: true,
+            address: Address { city: "São Paulo".into(), zip: Some(1234) },
+            tags: vec!["a".into(), "b".into()],
+            scores,
+        });
+
+        let form = "name=J&handle=j&age=1&plan=free&address.city=X&address.zip=&\
+            tags[1]=second&tags[0]=first&_method=post";
+
+        let signup: Signup<'_> = from_form_str(form, true).unwrap();
+        assert_eq!(signup.address.zip, None);
+        assert!(!signup.newsletter && signup.scores.is_empty());
+        assert_eq!(signup.tags, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn encoded_separators_are_not_split() {
+        let form = "name=J&handle=j&age=1&plan=free&address.city=X&tags=a&\
+            scores.a%2Eb=1&scores[c%2Ed]=2";
+
+        let signup: Signup<'_> = from_form_str(form, true).unwrap();
+        let mut scores = HashMap::new();
+        scores.insert("a.b".to_string(), 1.0);
+        scores.insert("c.d".to_string(), 2.0);
+        assert_eq!(signup.scores, scores);
+    }
+
+    #[test]
+    fn reports_field_errors() {
+        let form = "name=J&handle=j&age=300&plan=free&address.city=X&tags=a";
+        assert_eq!(errors(from_form_str::<Signup<'_>>(form, true)), vec![("age".into(), BadValue)]);
+
+        let form = "name=J&handle=j&age=3&plan=gold&address.city=X&tags=a";
+        assert_eq!(errors(from_form_str::<Signup<'_>>(form, true)), vec![("plan".into(), BadValue)]);
+
+        let form = "name=J&handle=j&age=3&plan=free&address.zip=1&tags=a";
+        assert_eq!(errors(from_form_str::<Signup<'_>>(form, true)),
+            vec![("address.city".into(), Missing)]);
+
+        let form = "name=J&handle=j&age=3&plan=free&address.city=X&address.street=Y&extra=1&tags=a";
+        assert_eq!(errors(from_form_str::<Signup<'_>>(form, true)),
+            vec![("extra".into(), Unknown), ("address.street".into(), Unknown)]);
+
+        let signup = from_form_str::<Signup<'_>>(form, false).unwrap();
+        assert_eq!(signup.address.city, "X");
+
+        match from_form_str::<Signup<'_>>("age=x&handle=%E2%9C%93", false) {
+            Err(FormDataError::Parse(errors, _)) => {
+                assert_eq!(errors.value("age"), Some("x"));
+                assert_eq!(errors.value("handle"), Some("✓"));
+            }
+            _ => panic!("expected a parse error"),
+        }
+
+        match from_form_str::<Address>("city=a=b", true) {
+            Err(FormDataError::Malformed(string)) => assert_eq!(string, "city=a=b"),
+            _ => panic!("expected a malformed form"),
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Search {
+        q: String,
+        page: Option<usize>,
+    }
+
+    #[post("/", data = "<form>")]
+    fn strict(form: SerdeForm<Search>) -> String {
+        format!("{} {:?}", form.q, form.page)
+    }
+
+    #[post("/lenient", data = "<form>")]
+    fn lenient(form: LenientSerdeForm<Search>) -> String {
+        format!("{} {:?}", form.q, form.page)
+    }
+
+    #[post("/errors", data = "<form>")]
+    fn errors_route(form: Result<SerdeForm<Search>, FormError<'_>>) -> String {
+        match form {
+            Ok(_) => "ok".into(),
+            Err(FormDataError::Parse(errors, _)) => errors.to_string(),
+            Err(_) => "other".into(),
+        }
+    }
+
+    #[get("/search")]
+    fn query(search: SerdeQuery<Search>) -> String {
+        format!("{} {:?}", search.q, search.page)
+    }
+
+    #[get("/search/lenient")]
+    fn lenient_query(search: LenientSerdeQuery<Search>) -> String {
+        format!("{} {:?}", search.q, search.page)
+    }
+
+    fn rocket(limits: Limits) -> Rocket {
+        let config = Config::build(Environment::Development).limits(limits).unwrap();
+        rocket::custom(config)
+            .mount("/", routes![strict, lenient, errors_route, query, lenient_query])
+    }
+
+    fn post(client: &Client, uri: &'static str, body: &str) -> (Status, Option<String>) {
+        let mut response = client.post(uri).header(ContentType::Form).body(body).dispatch();
+        (response.status(), response.body_string())
+    }
+
+    #[test]
+    fn form_guards() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        assert_eq!(post(&client, "/", "q=rocket&page=2"), (Status::Ok, Some("rocket Some(2)".into())));
+        assert_eq!(post(&client, "/", "q=rocket&x=1").0, Status::UnprocessableEntity);
+        assert_eq!(post(&client, "/", "page=two").0, Status::UnprocessableEntity);
+        assert_eq!(post(&client, "/", "q=a=b").0, Status::BadRequest);
+        assert_eq!(post(&client, "/lenient", "q=rocket&x=1").1, Some("rocket None".into()));
+        assert_eq!(post(&client, "/errors", "q=a&page=x").1, Some("page: invalid value".into()));
+
+        let response = client.post("/").header(ContentType::JSON).body("{}").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn form_limit() {
+        let client = Client::new(rocket(Limits::default().limit("forms", 8))).unwrap();
+        assert_eq!(post(&client, "/", "q=rocket").0, Status::Ok);
+        assert_eq!(post(&client, "/", "q=rockets").0, Status::PayloadTooLarge);
+    }
+
+    #[test]
+    fn query_guards() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let mut response = client.get("/search?q=a+b&page=3").dispatch();
+        assert_eq!(response.body_string(), Some("a b Some(3)".into()));
+
+        let response = client.get("/search?q=a&sort=asc").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let response = client.get("/search").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+
+        let mut response = client.get("/search/lenient?q=a&sort=asc").dispatch();
+        assert_eq!(response.body_string(), Some("a None".into()));
+    }
+}
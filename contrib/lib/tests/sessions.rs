@@ -0,0 +1,205 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "sessions")]
+extern crate rocket;
+
+#[cfg(feature = "sessions")]
+mod sessions_tests {
+    use std::io;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use rocket::{Rocket, State};
+    use rocket::http::{Cookie, Status};
+    use rocket::local::Client;
+    use rocket_contrib::sessions::*;
+
+    #[get("/data")]
+    fn get_data(session: Session<String>) -> Option<String> {
+        session.get().cloned()
+    }
+
+    #[post("/data/<value>")]
+    fn set_data(mut session: Session<String>, value: String) -> io::Result<()> {
+        session.set(value)
+    }
+
+    #[get("/id")]
+    fn id(session: Session<String>) -> Option<String> {
+        session.id().map(|id| id.to_string())
+    }
+
+    #[post("/login/<user>")]
+    fn login(mut session: Session<String>, user: String) -> io::Result<()> {
+        session.set_user(user)
+    }
+
+    #[post("/logout")]
+    fn logout(mut session: Session<String>) -> io::Result<()> {
+        session.destroy()
+    }
+
+    #[post("/revoke/<user>")]
+    fn revoke(sessions: State<'_, Sessions>, user: String) -> io::Result<String> {
+        sessions.revoke_user(&user).map(|n| n.to_string())
+    }
+
+    fn rocket<S: SessionStore>(store: S) -> Rocket {
+        rocket::ignite()
+            .attach(Sessions::fairing(store))
+            .mount("/", routes![get_data, set_data, id, login, logout, revoke])
+    }
+
+    fn body(client: &Client, uri: &'static str) -> Option<String> {
+        let mut response = client.get(uri).dispatch();
+        match response.status() {
+            Status::Ok => response.body_string(),
+            _ => None
+        }
+    }
+
+    fn post(client: &Client, uri: String) -> Option<String> {
+        let mut response = client.post(uri).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        response.body_string()
+    }
+
+    fn check_session_lifecycle<S: SessionStore>(store: S) {
+        let client = Client::new(rocket(store)).unwrap();
+        assert_eq!(body(&client, "/data"), None);
+        assert_eq!(body(&client, "/id"), None);
+
+        post(&client, "/data/hello".into());
+        assert_eq!(body(&client, "/data"), Some("hello".into()));
+        let id = body(&client, "/id").expect("session started");
+        assert_eq!(id.len(), 64);
+
+        // Logging in regenerates the ID but keeps the data.
+        post(&client, "/login/bob".into());
+        assert_eq!(body(&client, "/data"), Some("hello".into()));
+        let new_id = body(&client, "/id").expect("session kept");
+        assert_ne!(id, new_id);
+
+        // The old ID no longer refers to a session.
+        let response = client.get("/data")
+            .private_cookie(Cookie::new("rocket_session", id))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        // Revoking the user's sessions ends this session.
+        assert_eq!(post(&client, "/revoke/alice".into()), Some("0".into()));
+        assert_eq!(post(&client, "/revoke/bob".into()), Some("1".into()));
+        assert_eq!(body(&client, "/data"), None);
+
+        // Destroying a session ends it.
+        post(&client, "/data/again".into());
+        assert_eq!(body(&client, "/data"), Some("again".into()));
+        post(&client, "/logout".into());
+        assert_eq!(body(&client, "/data"), None);
+        assert_eq!(body(&client, "/id"), None);
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("rocket-sessions-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record(user: Option<&str>, expires: SystemTime) -> SessionRecord {
+        SessionRecord { data: "\"data\"".into(), user: user.map(|u| u.into()), expires }
+    }
+
+    fn check_store<S: SessionStore>(store: S) {
+        let future = UNIX_EPOCH + Duration::from_secs(4_000_000_000);
+        let past = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        assert_eq!(store.load("a").unwrap(), None);
+        store.save("a", &record(Some("bob"), future)).unwrap();
+        store.save("b", &record(Some("bob"), past)).unwrap();
+        store.save("c", &record(None, past)).unwrap();
+        store.save("d", &record(Some("alice"), future)).unwrap();
+        assert_eq!(store.load("a").unwrap(), Some(record(Some("bob"), future)));
+
+        assert_eq!(store.remove_expired(SystemTime::now()).unwrap(), 2);
+        assert_eq!(store.load("b").unwrap(), None);
+        assert_eq!(store.load("c").unwrap(), None);
+
+        assert_eq!(store.remove_user("bob").unwrap(), 1);
+        assert_eq!(store.load("a").unwrap(), None);
+        assert!(store.load("d").unwrap().is_some());
+
+        store.remove("d").unwrap();
+        store.remove("d").unwrap();
+        assert_eq!(store.load("d").unwrap(), None);
+    }
+
+    #[test]
+    fn memory_store_session_lifecycle() {
+        check_session_lifecycle(MemoryStore::new());
+    }
+
+    #[test]
+    fn file_store_session_lifecycle() {
+        let dir = temp_dir("lifecycle");
+        check_session_lifecycle(FileStore::new(&dir).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn memory_store_operations() {
+        check_store(MemoryStore::new());
+    }
+
+    #[test]
+    fn file_store_operations() {
+        let dir = temp_dir("operations");
+        check_store(FileStore::new(&dir).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_store_rejects_path_ids() {
+        let dir = temp_dir("paths");
+        let store = FileStore::new(&dir).unwrap();
+        assert!(store.load("../etc/passwd").is_err());
+        assert!(store.save("", &record(None, SystemTime::now())).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite_sessions")]
+    fn sqlite_store_operations() {
+        check_store(SqliteStore::open(":memory:").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite_sessions")]
+    fn sqlite_store_session_lifecycle() {
+        check_session_lifecycle(SqliteStore::open(":memory:").unwrap());
+    }
+
+    #[test]
+    fn expired_sessions_are_not_loaded() {
+        let dir = temp_dir("expired");
+        let store = FileStore::new(&dir).unwrap();
+        let id = "0".repeat(64);
+        let past = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        store.save(&id, &record(None, past)).unwrap();
+
+        let client = Client::new(rocket(FileStore::new(&dir).unwrap())).unwrap();
+        let response = client.get("/data")
+            .private_cookie(Cookie::new("rocket_session", id.clone()))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(store.load(&id).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_fairing_fails() {
+        let client = Client::new(rocket::ignite().mount("/", routes![get_data])).unwrap();
+        let response = client.get("/data").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
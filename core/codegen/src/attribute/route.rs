@@ -10,8 +10,9 @@ use crate::proc_macro_ext::{Diagnostics, StringLit};
 use crate::syn_ext::{syn_to_diag, IdentExt};
 use self::syn::{Attribute, parse::Parser};
 
-use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, Optional};
+use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, Limit, Optional};
 use crate::attribute::segments::{Source, Kind, Segment};
+use crate::derive::from_form::Form;
 use crate::{ROUTE_FN_PREFIX, ROUTE_STRUCT_PREFIX, URI_MACRO_PREFIX, ROCKET_PARAM_PREFIX};
 
 /// The raw, parsed `#[route]` attribute.
@@ -23,6 +24,7 @@ struct RouteAttribute {
     data: Option<SpanWrapped<DataSegment>>,
     format: Option<MediaType>,
     rank: Option<isize>,
+    limit: Option<SpanWrapped<Limit>>,
 }
 
 /// The raw, parsed `#[method]` (e.g, `get`, `put`, `post`, etc.) attribute.
@@ -33,6 +35,7 @@ struct MethodRouteAttribute {
     data: Option<SpanWrapped<DataSegment>>,
     format: Option<MediaType>,
     rank: Option<isize>,
+    limit: Option<SpanWrapped<Limit>>,
 }
 
 /// This structure represents the parsed `route` attribute and associated items.
@@ -48,9 +51,53 @@ struct Route {
     /// as the user wrote it, while the second ident is the identifier that
     /// should be used during code generation, the `rocket_ident`.
     inputs: Vec<(syn::Ident, syn::Ident, syn::Type)>,
+    /// The default values of query parameters, from `#[form(default)]`
+    /// attributes on the function's arguments, keyed by the user's ident.
+    defaults: Vec<(syn::Ident, syn::Expr)>,
 }
 
-fn parse_route(attr: RouteAttribute, function: syn::ItemFn) -> Result<Route> {
+/// Removes the `#[form]` attributes from the arguments of `function`, returning
+/// the defaults they declare.
+fn strip_form_attributes(
+    function: &mut syn::ItemFn,
+    diags: &mut Diagnostics
+) -> Vec<(syn::Ident, syn::Expr)> {
+    let is_form = |attr: &Attribute| attr.path.segments.len() == 1
+        && attr.path.segments[0].ident == "form";
+
+    let mut defaults = vec![];
+    for input in function.sig.inputs.iter_mut() {
+        let arg = match input {
+            syn::FnArg::Typed(arg) => arg,
+            _ => continue
+        };
+
+        let form = Form::from_attrs(&arg.attrs);
+        arg.attrs.retain(|attr| !is_form(attr));
+        let form = match form {
+            Ok(form) => form,
+            Err(diag) => { diags.push(diag); continue; }
+        };
+
+        if let Some(field) = form.field {
+            diags.push(field.span.error("`field` is not supported on handler arguments")
+                .help("only `default` is supported on handler arguments"));
+        }
+
+        if let Some(expr) = form.validate.first() {
+            diags.push(expr.span().error("`validate` is not supported on handler arguments")
+                .help("only `default` is supported on handler arguments"));
+        }
+
+        if let (syn::Pat::Ident(ref pat), Some(default)) = (&*arg.pat, form.default) {
+            defaults.push((pat.ident.clone(), default));
+        }
+    }
+
+    defaults
+}
+
+fn parse_route(attr: RouteAttribute, mut function: syn::ItemFn) -> Result<Route> {
     // Gather diagnostics as we proceed.
     let mut diags = Diagnostics::new();
 
@@ -64,6 +111,16 @@ fn parse_route(attr: RouteAttribute, function: syn::ItemFn) -> Result<Route> {
         }
     }
 
+    // Likewise, a `limit` on a non-payload method is almost certainly a mistake.
+    if let Some(ref limit) = attr.limit {
+        if !attr.method.0.supports_payload() {
+            let msg = format!("'{}' does not typically support payloads", attr.method.0);
+            limit.full_span.warning("`limit` used with non-payload-supporting method")
+                .span_note(attr.method.span, msg)
+                .emit()
+        }
+    }
+
     // Collect all of the dynamic segments in an `IndexSet`, checking for dups.
     let mut segments: IndexSet<Segment> = IndexSet::new();
     fn dup_check<I>(set: &mut IndexSet<Segment>, iter: I, diags: &mut Diagnostics)
@@ -82,6 +139,18 @@ fn parse_route(attr: RouteAttribute, function: syn::ItemFn) -> Result<Route> {
     attr.path.query.as_ref().map(|q| dup_check(&mut segments, q.iter().cloned(), &mut diags));
     dup_check(&mut segments, attr.data.clone().map(|s| s.value.0).into_iter(), &mut diags);
 
+    // Only single query parameters can be missing, so only they have defaults.
+    let defaults = strip_form_attributes(&mut function, &mut diags);
+    for (ident, _) in &defaults {
+        let segment: Segment = ident.into();
+        match segments.get(&segment) {
+            Some(seg) if seg.source == Source::Query && seg.kind == Kind::Single => continue,
+            _ => diags.push(ident.span().unstable()
+                .error("`default` is only supported on single query parameters")
+                .help("a query parameter is declared as `?<param>` in the route's path"))
+        }
+    }
+
     // Check the validity of function arguments.
     let mut inputs = vec![];
     let mut fn_segments: IndexSet<Segment> = IndexSet::new();
@@ -123,7 +192,7 @@ fn parse_route(attr: RouteAttribute, function: syn::ItemFn) -> Result<Route> {
             .span_note(span, format!("expected argument named `{}` here", missing.name)))
     }
 
-    diags.head_err_or(Route { attribute: attr, function, inputs, segments })
+    diags.head_err_or(Route { attribute: attr, function, inputs, segments, defaults })
 }
 
 fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream2 {
@@ -257,7 +326,18 @@ fn query_exprs(route: &Route) -> Option<TokenStream2> {
             }
         };
 
+        let default = route.defaults.iter()
+            .find(|(ident, _)| ident == &segment.name)
+            .map(|(_, expr)| expr);
+
         let builder = match segment.kind {
+            Kind::Single if default.is_some() => quote_spanned! { span =>
+                #[allow(non_snake_case)]
+                let #ident = match #ident {
+                    #_Some(__v) => __v,
+                    #_None => #default,
+                };
+            },
             Kind::Single => quote_spanned! { span =>
                 #[allow(non_snake_case)]
                 let #ident = match #ident.or_else(<#ty as #request::FromFormValue>::default) {
@@ -415,6 +495,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     let path = route.attribute.path.origin.0.to_string();
     let rank = Optional(route.attribute.rank);
     let format = Optional(route.attribute.format);
+    let limit = Optional(route.attribute.limit.map(|l| l.value));
 
     Ok(quote! {
         #user_handler_fn
@@ -444,6 +525,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
                 handler: #generated_fn_name,
                 format: #format,
                 rank: #rank,
+                limit: #limit,
             };
     }.into())
 }
@@ -492,6 +574,7 @@ fn incomplete_route(
         data: method_attribute.data,
         format: method_attribute.format,
         rank: method_attribute.rank,
+        limit: method_attribute.limit,
     };
 
     codegen_route(parse_route(attribute, function)?)
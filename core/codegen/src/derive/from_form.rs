@@ -1,9 +1,16 @@
 use proc_macro::{Span, TokenStream};
-use devise::{*, ext::{TypeExt, Split3}};
+use devise::{*, ext::TypeExt};
 
-#[derive(FromMeta)]
+use crate::proc_macro2::{self, TokenStream as TokenStream2, TokenTree, Delimiter};
+use crate::syn_ext::{syn_to_diag, IdentExt};
+use self::syn::{Token, parse::{Parse, ParseStream, Parser}, punctuated::Punctuated};
+
+/// The merged contents of every `#[form(..)]` attribute on a field.
+#[derive(Default)]
 pub struct Form {
-    pub field: FormField,
+    pub field: Option<FormField>,
+    pub default: Option<syn::Expr>,
+    pub validate: Vec<syn::Expr>,
 }
 
 pub struct FormField {
@@ -22,14 +29,121 @@ fn is_valid_field_name(s: &str) -> bool {
     s.chars().all(|c| (c >= ' ' && c <= '~') && c != '&' && c != '=' && c != '?')
 }
 
-impl FromMeta for FormField {
-    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
-        let string = String::from_meta(meta)?;
-        if !is_valid_field_name(&string) {
-            return Err(meta.value_span().error("invalid form field name"));
+/// A single parameter in a `#[form(..)]` attribute. Values are parsed as
+/// expressions since `validate` takes arbitrary expressions.
+enum FormParam {
+    Bare(syn::Ident),
+    KeyValue(syn::Ident, syn::Expr),
+    Other(syn::Expr),
+}
+
+impl Parse for FormParam {
+    fn parse(input: ParseStream<'_>) -> syn::parse::Result<Self> {
+        if input.peek(syn::Ident) && input.peek2(Token![=]) {
+            let key = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            return Ok(FormParam::KeyValue(key, input.parse()?));
         }
 
-        Ok(FormField { span: meta.value_span(), name: string })
+        match input.parse::<syn::Expr>()? {
+            syn::Expr::Path(ref e) if e.qself.is_none() && e.path.segments.len() == 1
+                    && e.path.leading_colon.is_none() => {
+                Ok(FormParam::Bare(e.path.segments[0].ident.clone()))
+            }
+            expr => Ok(FormParam::Other(expr))
+        }
+    }
+}
+
+impl Form {
+    /// Parses and merges every `#[form(..)]` attribute in `attrs`.
+    pub fn from_attrs(attrs: &[syn::Attribute]) -> Result<Form> {
+        let mut form = Form::default();
+        let form_attrs = attrs.iter()
+            .filter(|attr| attr.path.segments.len() == 1 && attr.path.segments[0].ident == "form");
+
+        for attr in form_attrs {
+            let mut tokens = attr.tokens.clone().into_iter();
+            let params = match (tokens.next(), tokens.next()) {
+                (Some(TokenTree::Group(ref group)), None)
+                    if group.delimiter() == Delimiter::Parenthesis => group.stream(),
+                _ => return Err(attr.path.span().error("malformed attribute: expected list")
+                    .help("expected syntax: #[form(key = value, ..)]"))
+            };
+
+            let params = Punctuated::<FormParam, Token![,]>::parse_terminated
+                .parse2(params)
+                .map_err(syn_to_diag)?;
+
+            for param in params {
+                form.merge(param)?;
+            }
+        }
+
+        Ok(form)
+    }
+
+    fn merge(&mut self, param: FormParam) -> Result<()> {
+        let (key, value) = match param {
+            FormParam::KeyValue(key, value) => (key, value),
+            FormParam::Bare(ref key) if *key == "default" => {
+                let span = key.span();
+                let default = quote_spanned!(span => ::std::default::Default::default());
+                return self.set_default(key, syn::parse2(default).map_err(syn_to_diag)?);
+            }
+            FormParam::Bare(key) => {
+                return Err(key.span().unstable().error("expected literal or key/value pair"));
+            }
+            FormParam::Other(expr) => {
+                return Err(expr.span().error("expected key/value pair"));
+            }
+        };
+
+        let key_span = key.span().unstable();
+        let span = key_span.join(value.span()).unwrap_or(key_span);
+        match key.to_string().as_str() {
+            "field" => {
+                if self.field.is_some() {
+                    return Err(span.error("duplicate attribute parameter: field"));
+                }
+
+                let name = match value {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(ref s), .. }) => s.value(),
+                    _ => return Err(value.span().error("invalid value: expected string literal"))
+                };
+
+                if !is_valid_field_name(&name) {
+                    return Err(value.span().error("invalid form field name"));
+                }
+
+                self.field = Some(FormField { span: value.span(), name });
+            }
+            "default" => return self.set_default(&key, value),
+            "validate" => match value {
+                syn::Expr::Call(_) | syn::Expr::Path(_) => self.validate.push(value),
+                _ => return Err(value.span().error("invalid validator")
+                    .help("expected a call, as in `len(1..64)`, or a path, as in `email`"))
+            }
+            key => return Err(span.error(format!("unexpected attribute parameter: `{}`", key)))
+        }
+
+        Ok(())
+    }
+
+    fn set_default(&mut self, key: &syn::Ident, value: syn::Expr) -> Result<()> {
+        if self.default.is_some() {
+            return Err(key.span().unstable().error("duplicate attribute parameter: default"));
+        }
+
+        self.default = Some(value);
+        Ok(())
+    }
+
+    /// Returns the name of the form field for the field `ident`.
+    pub fn field_name(&self, ident: &syn::Ident) -> String {
+        self.field.as_ref()
+            .map(|field| field.name.clone())
+            .unwrap_or_else(|| ident.to_string())
     }
 }
 
@@ -41,8 +155,8 @@ fn validate_struct(gen: &DeriveGenerator, data: Struct<'_>) -> Result<()> {
     let mut names = ::std::collections::HashMap::new();
     for field in data.fields().iter() {
         let id = field.ident.as_ref().expect("named field");
-        let field = match Form::from_attrs("form", &field.attrs) {
-            Some(result) => result?.field,
+        let field = match Form::from_attrs(&field.attrs)?.field {
+            Some(field) => field,
             None => FormField { span: Spanned::span(&id), name: id.to_string() }
         };
 
@@ -57,14 +171,159 @@ fn validate_struct(gen: &DeriveGenerator, data: Struct<'_>) -> Result<()> {
     Ok(())
 }
 
+struct FieldInfo {
+    ident: syn::Ident,
+    ctxt: syn::Ident,
+    value: syn::Ident,
+    failed: syn::Ident,
+    name: String,
+    ty: syn::Type,
+    default: Option<syn::Expr>,
+    validate: Vec<syn::Expr>,
+    span: proc_macro2::Span,
+}
+
+fn field_info(fields: Fields<'_>) -> Result<Vec<FieldInfo>> {
+    fields.iter().map(|field| {
+        let ident = field.ident.clone().expect("named");
+        let form = Form::from_attrs(&field.attrs)?;
+        let name = form.field_name(&ident);
+        let ctxt = ident.prepend("__rocket_ctxt_");
+        let value = ident.prepend("__rocket_value_");
+        let failed = ident.prepend("__rocket_failed_");
+        let (ty, span) = (field.ty.with_stripped_lifetimes(), field.span().into());
+        let (default, validate) = (form.default, form.validate);
+        Ok(FieldInfo { ident, ctxt, value, failed, name, ty, default, validate, span })
+    }).collect()
+}
+
+// Generates a call to the validator `expr` with `__value` as the first
+// argument: `len(1..64)` becomes `len(&__value, 1..64)`.
+fn validator_call(expr: &syn::Expr) -> TokenStream2 {
+    match expr {
+        syn::Expr::Call(call) => {
+            let (func, args) = (&call.func, &call.args);
+            match args.is_empty() {
+                true => quote_spanned!(expr.span().into() => #func(&__value)),
+                false => quote_spanned!(expr.span().into() => #func(&__value, #args)),
+            }
+        }
+        _ => quote_spanned!(expr.span().into() => #expr(&__value))
+    }
+}
+
+// Generates the body of `FromFormField::finalize()` given `__strict`,
+// `__prefix`, the path to the structure in the form's syntax, and `__items`, a
+// `Vec<(&RawStr, FormItem)>` of remaining keys and their items.
+fn from_items(fields: Fields<'_>) -> Result<TokenStream2> {
+    define_vars_and_mods!(_None, _Some, _Ok, _Err);
+    let from_form_field = quote!(::rocket::request::FromFormField);
+    let field_error = quote!(::rocket::request::FieldError);
+    let kind = quote!(::rocket::request::FieldErrorKind);
+    let mut fields = field_info(fields)?;
+
+    let inits = fields.iter().map(|FieldInfo { ctxt, failed, ty, span, .. }| quote_spanned! {
+        *span =>
+            let mut #ctxt = <#ty as #from_form_field>::init();
+            let mut #failed = false;
+    }).collect::<Vec<_>>();
+
+    let finalizers = fields.iter().map(|field| {
+        let FieldInfo { ctxt, value, failed, name, ty, span, .. } = field;
+        let validators = field.validate.iter().map(|expr| {
+            let call = validator_call(expr);
+            quote_spanned! { *span => {
+                #[allow(unused_imports)]
+                use ::rocket::request::validate::*;
+
+                if let #_Err(__e) = #call {
+                    let __path = ::rocket::request::field_path(__prefix, #name);
+                    __errors.push(#field_error::new(&__path, #kind::Invalid, __e.to_string()));
+                }
+            }}
+        });
+
+        // An explicit default takes precedence over the type's default. A
+        // value that fails to parse is an error, not a missing value.
+        let default = match field.default {
+            Some(ref expr) => quote_spanned!(expr.span().into() => #_Some(#expr)),
+            None => quote_spanned!(*span => <#ty as #from_form_field>::default()),
+        };
+
+        quote_spanned! { *span =>
+            let #value = match <#ty as #from_form_field>::finalize(#ctxt) {
+                #_Ok(#_Some(__value)) => {
+                    #(#validators)*
+                    #_Some(__value)
+                }
+                #_Ok(#_None) if #failed => #_None,
+                #_Ok(#_None) => match #default {
+                    #_Some(__value) => #_Some(__value),
+                    #_None => {
+                        let __path = ::rocket::request::field_path(__prefix, #name);
+                        __errors.push(#field_error::new(&__path, #kind::Missing, "missing field"));
+                        #_None
+                    }
+                },
+                #_Err(__e) => {
+                    __errors.extend(__e);
+                    #_None
+                }
+            };
+        }
+    }).collect::<Vec<_>>();
+
+    let builders = fields.iter().map(|FieldInfo { ident, value, span, .. }| {
+        quote_spanned!(*span => #ident: #value.expect("value of valid field"),)
+    }).collect::<Vec<_>>();
+
+    // Longer names first so that a field named `a.b` takes precedence over
+    // the nested field `b` of a field named `a`.
+    fields.sort_by(|a, b| b.name.len().cmp(&a.name.len()));
+    let matchers = fields.iter().map(|FieldInfo { ctxt, failed, name, ty, span, .. }| {
+        quote_spanned! { *span =>
+            if let #_Some(__rest) = ::rocket::request::strip_field_name(__key, #name) {
+                let __result = <#ty as #from_form_field>::push(&mut #ctxt, __rest, __item, __strict);
+                if let #_Err(__e) = __result {
+                    __errors.push(__e);
+                    #failed = true;
+                }
+
+                continue;
+            }
+        }
+    });
+
+    Ok(quote! {
+        let mut __errors = ::rocket::request::FormErrors::new();
+        #(#inits)*
+
+        for (__key, __item) in __items {
+            #(#matchers)*
+
+            if __strict && __item.key != "_method" {
+                __errors.push(::rocket::request::FormParseError::Unknown(__item.key, __item.value));
+            }
+        }
+
+        #(#finalizers)*
+
+        if !__errors.is_empty() {
+            return #_Err(__errors);
+        }
+
+        #_Ok(#_Some(Self { #(#builders)* }))
+    })
+}
+
 pub fn derive_from_form(input: TokenStream) -> TokenStream {
-    let form_error = quote!(::rocket::request::FormParseError);
-    DeriveGenerator::build_for(input, quote!(impl<'__f> ::rocket::request::FromForm<'__f>))
+    let from_form_field_impl = DeriveGenerator::build_for(input.clone(),
+            quote!(impl<'__f> ::rocket::request::FromFormField<'__f>))
         .generic_support(GenericSupport::Lifetime | GenericSupport::Type)
         .replace_generic(0, 0)
         .data_support(DataSupport::NamedStruct)
         .map_type_generic(|_, ident, _| quote! {
-            #ident : ::rocket::request::FromFormValue<'__f>
+            #ident : ::rocket::request::FromFormField<'__f>
         })
         .validate_generics(|_, generics| match generics.lifetimes().count() > 1 {
             true => Err(generics.span().error("only one lifetime is supported")),
@@ -72,59 +331,90 @@ pub fn derive_from_form(input: TokenStream) -> TokenStream {
         })
         .validate_struct(validate_struct)
         .function(|_, inner| quote! {
-            type Error = ::rocket::request::FormParseError<'__f>;
+            type Context = (::std::option::Option<(bool, &'__f str)>, ::std::vec::Vec<(
+                &'__f ::rocket::http::RawStr,
+                ::rocket::request::FormItem<'__f>
+            )>);
 
-            fn from_form(
-                __items: &mut ::rocket::request::FormItems<'__f>,
+            #[inline(always)]
+            fn init() -> Self::Context {
+                (::std::option::Option::None, ::std::vec::Vec::new())
+            }
+
+            #[inline(always)]
+            fn push(
+                __ctxt: &mut Self::Context,
+                __key: &'__f ::rocket::http::RawStr,
+                __item: ::rocket::request::FormItem<'__f>,
                 __strict: bool,
-            ) -> ::std::result::Result<Self, Self::Error> {
-                #inner
+            ) -> ::std::result::Result<(), ::rocket::request::FormParseError<'__f>> {
+                // The part of the key preceding `__key` is the path to `self`.
+                let __prefix = &__item.key.as_str()[..(__item.key.len() - __key.len())];
+                __ctxt.0 = ::std::option::Option::Some((__strict, __prefix.trim_end_matches('.')));
+                __ctxt.1.push((__key, __item));
+                ::std::result::Result::Ok(())
             }
-        })
-        .try_map_fields(move |_, fields| {
-            define_vars_and_mods!(_None, _Some, _Ok, _Err);
-            let (constructors, matchers, builders) = fields.iter().map(|field| {
-                let (ident, span) = (&field.ident, field.span().into());
-                let default_name = ident.as_ref().expect("named").to_string();
-                let name = Form::from_attrs("form", &field.attrs)
-                    .map(|result| result.map(|form| form.field.name))
-                    .unwrap_or_else(|| Ok(default_name))?;
-
-                let ty = field.ty.with_stripped_lifetimes();
-                let ty = quote_spanned! {
-                    span => <#ty as ::rocket::request::FromFormValue>
+
+            fn finalize(
+                __ctxt: Self::Context
+            ) -> ::std::result::Result<::std::option::Option<Self>, ::rocket::request::FormErrors> {
+                // A nested structure without any items is missing as a whole.
+                let (__strict, __prefix, __items) = match __ctxt {
+                    (::std::option::Option::Some((__s, __p)), __items) => (__s, __p, __items),
+                    (::std::option::Option::None, _) => {
+                        return ::std::result::Result::Ok(::std::option::Option::None);
+                    }
                 };
 
-                let constructor = quote_spanned!(span => let mut #ident = #_None;);
+                #inner
+            }
+        })
+        .try_map_fields(|_, fields| from_items(fields))
+        .to_tokens();
 
-                let matcher = quote_spanned! { span =>
-                    #name => { #ident = #_Some(#ty::from_form_value(__v)
-                                .map_err(|_| #form_error::BadValue(__k, __v))?); },
-                };
+    // The input was rejected and the errors emitted: don't repeat them.
+    if from_form_field_impl.is_empty() {
+        return from_form_field_impl;
+    }
 
-                let builder = quote_spanned! { span =>
-                    #ident: #ident.or_else(#ty::default)
-                        .ok_or_else(|| #form_error::Missing(#name.into()))?,
-                };
+    let from_form_impl = DeriveGenerator::build_for(input,
+            quote!(impl<'__f> ::rocket::request::FromForm<'__f>))
+        .generic_support(GenericSupport::Lifetime | GenericSupport::Type)
+        .replace_generic(0, 0)
+        .data_support(DataSupport::NamedStruct)
+        .map_type_generic(|_, ident, _| quote! {
+            #ident : ::rocket::request::FromFormField<'__f>
+        })
+        .function(|_, _| quote! {
+            type Error = ::rocket::request::FormErrors;
 
-                Ok((constructor, matcher, builder))
-            }).collect::<Result<Vec<_>>>()?.into_iter().split3();
+            fn from_form(
+                __items: &mut ::rocket::request::FormItems<'__f>,
+                __strict: bool,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                use ::rocket::request::FromFormField;
 
-            Ok(quote! {
-                #(#constructors)*
+                // The form is never missing as a whole, even if it's empty.
+                let __items = __items.collect::<::std::vec::Vec<_>>();
+                let __ctxt = (
+                    ::std::option::Option::Some((__strict, "")),
+                    __items.iter().map(|__item| (__item.key, *__item)).collect(),
+                );
 
-                for (__k, __v) in __items.map(|item| item.key_value()) {
-                    match __k.as_str() {
-                        #(#matchers)*
-                        _ if __strict && __k != "_method" => {
-                            return #_Err(#form_error::Unknown(__k, __v));
-                        }
-                        _ => { /* lenient or "method"; let it pass */ }
+                match <Self as FromFormField<'__f>>::finalize(__ctxt) {
+                    ::std::result::Result::Ok(__value) => {
+                        ::std::result::Result::Ok(__value.expect("top-level form value"))
+                    }
+                    ::std::result::Result::Err(mut __errors) => {
+                        __errors.set_values(&__items);
+                        ::std::result::Result::Err(__errors)
                     }
                 }
-
-                #_Ok(Self { #(#builders)* })
-            })
+            }
         })
-        .to_tokens()
+        .to_tokens();
+
+    let mut ts = TokenStream2::from(from_form_field_impl);
+    ts.extend(TokenStream2::from(from_form_impl));
+    ts.into()
 }
@@ -61,9 +61,7 @@ pub fn derive_uri_display_query(input: TokenStream) -> TokenStream {
             let span = field.span().into();
             let accessor = field.accessor();
             let tokens = if let Some(ref ident) = field.ident {
-                let name = Form::from_attrs("form", &field.attrs)
-                    .map(|result| result.map(|form| form.field.name))
-                    .unwrap_or_else(|| Ok(ident.to_string()))?;
+                let name = Form::from_attrs(&field.attrs)?.field_name(ident);
 
                 quote_spanned!(span => f.write_named_value(#name, &#accessor)?;)
             } else {
@@ -25,6 +25,9 @@ pub struct Origin(pub http::uri::Origin<'static>);
 #[derive(Clone, Debug)]
 pub struct DataSegment(pub Segment);
 
+#[derive(Debug)]
+pub struct Limit(pub u64);
+
 #[derive(Clone, Debug)]
 pub struct Optional<T>(pub Option<T>);
 
@@ -233,6 +236,22 @@ impl FromMeta for RoutePath {
     }
 }
 
+impl FromMeta for Limit {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        http::size::parse_size(&String::from_meta(meta)?)
+            .map(Limit)
+            .map_err(|e| meta.value_span().error(format!("invalid limit: {}", e))
+                .help("a limit is a size such as \"512 B\", \"64 KiB\", or \"2 MiB\""))
+    }
+}
+
+impl ToTokens for Limit {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let limit = self.0;
+        tokens.extend(quote!(#limit));
+    }
+}
+
 impl<T: ToTokens> ToTokens for Optional<T> {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         define_vars_and_mods!(_Some, _None);
@@ -209,12 +209,14 @@ macro_rules! route_attribute {
         /// parameter := 'rank' '=' INTEGER
         ///            | 'format' '=' '"' MEDIA_TYPE '"'
         ///            | 'data' '=' '"' SINGLE_PARAM '"'
+        ///            | 'limit' '=' '"' SIZE '"'
         ///
         /// SINGLE_PARAM := '<' IDENT '>'
         /// MULTI_PARAM := '<' IDENT '..>'
         ///
         /// URI_SEG := valid, non-percent-encoded HTTP URI segment
         /// MEDIA_TYPE := valid HTTP media type or known shorthand
+        /// SIZE := number of bytes with an optional unit, i.e, "64 KiB"
         ///
         /// INTEGER := unsigned integer, as defined by Rust
         /// IDENT := valid identifier, as defined by Rust, except `_`
@@ -262,6 +264,34 @@ macro_rules! route_attribute {
         /// corresponding dynamic parameter is required to implement the
         /// [`FromRequest`] trait.
         ///
+        /// An argument corresponding to a single query parameter may be
+        /// annotated with `#[form(default = expr)]` or `#[form(default)]`. When
+        /// the parameter is missing from the request, the argument's value is
+        /// `expr` or `Default::default()`, respectively, instead of
+        /// [`FromFormValue::default()`]:
+        ///
+        /// ```rust
+        /// # #![feature(proc_macro_hygiene)]
+        /// # #[macro_use] extern crate rocket;
+        /// #[get("/items?<page>")]
+        /// fn items(#[form(default = 1)] page: usize) -> String {
+        ///     format!("page {}", page)
+        /// }
+        /// ```
+        ///
+        /// The `limit` parameter sets the maximum size of the request body the
+        /// route accepts, overriding the `data` limit. A request whose
+        /// `Content-Length` exceeds the limit fails with a `413 Payload Too
+        /// Large` before any guard runs:
+        ///
+        /// ```rust
+        /// # #![feature(proc_macro_hygiene)]
+        /// # #[macro_use] extern crate rocket;
+        /// # use rocket::Data;
+        /// #[post("/upload", data = "<file>", limit = "2 MiB")]
+        /// fn upload(file: Data) { /* .. */ }
+        /// ```
+        ///
         /// The return type of the decorated function must implement the
         /// [`Responder`] trait.
         ///
@@ -269,6 +299,7 @@ macro_rules! route_attribute {
         /// [`FromSegments`]: ../rocket/request/trait.FromSegments.html
         /// [`FromFormValue`]: ../rocket/request/trait.FromFormValue.html
         /// [`FromQuery`]: ../rocket/request/trait.FromQuery.html
+        /// [`FromFormValue::default()`]: ../rocket/request/trait.FromFormValue.html#method.default
         /// [`FromData`]: ../rocket/data/trait.FromData.html
         /// [`FromRequest`]: ../rocket/request/trait.FromRequest.html
         /// [`Route`]: ../rocket/struct.Route.html
@@ -310,9 +341,9 @@ macro_rules! route_attribute {
         ///   2. A static structure used by [`routes!`] to generate a [`Route`].
         ///
         ///      The static structure (and resulting [`Route`]) is populated
-        ///      with the name (the function's name), path, query, rank, and
-        ///      format from the route attribute. The handler is set to the
-        ///      generated handler.
+        ///      with the name (the function's name), path, query, rank,
+        ///      format, and limit from the route attribute. The handler is set
+        ///      to the generated handler.
         ///
         ///   3. A macro used by [`uri!`] to type-check and generate an
         ///      [`Origin`].
@@ -482,25 +513,38 @@ pub fn derive_from_form_value(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
-/// Each field's type is required to implement [`FromFormValue`].
-///
-/// The derive generates an implementation of the [`FromForm`] trait. The
-/// implementation parses a form whose field names match the field names of the
-/// structure on which the derive was applied. Each field's value is parsed with
-/// the [`FromFormValue`] implementation of the field's type. The `FromForm`
-/// implementation succeeds only when all of the field parses succeed. If
-/// parsing fails, an error ([`FromForm::Error`]) of type [`FormParseError`] is
-/// returned.
+/// Each field's type is required to implement [`FromFormField`], which is
+/// implemented for every type that implements [`FromFormValue`], for `Vec` and
+/// `HashMap` collections of such types, and for every structure that derives
+/// `FromForm`.
+///
+/// The derive generates implementations of the [`FromForm`] and
+/// [`FromFormField`] traits. The implementation parses a form whose field names
+/// match the field names of the structure on which the derive was applied.
+/// Each field's value is parsed with the [`FromFormValue`] implementation of
+/// the field's type. Fields of nested structures and collections are named by
+/// extending the field's name with dotted or bracketed components, as in
+/// `address.city` or `items[0][qty]`. The `FromForm` implementation succeeds
+/// only when all of the field parses and validations succeed. Otherwise, an
+/// error ([`FromForm::Error`]) of type [`FormErrors`] containing every error in
+/// every field is returned.
 ///
 /// The derive accepts one field attribute: `form`, with the following syntax:
 ///
 /// ```text
-/// form := 'field' '=' '"' IDENT '"'
+/// form := param (',' param)*
+///
+/// param := 'field' '=' '"' IDENT '"'
+///        | 'default' ('=' EXPR)?
+///        | 'validate' '=' VALIDATOR
 ///
 /// IDENT := valid identifier, as defined by Rust
+/// EXPR := any valid expression, as defined by Rust
+/// VALIDATOR := a call expression or path to a validator
 /// ```
 ///
-/// When applied, the attribute looks as follows:
+/// The attribute may be applied more than once to a field. When applied, the
+/// attribute looks as follows:
 ///
 /// ```rust
 /// # #[macro_use] extern crate rocket;
@@ -509,19 +553,39 @@ pub fn derive_from_form_value(input: TokenStream) -> TokenStream {
 /// struct MyStruct {
 ///     field: usize,
 ///     #[form(field = "renamed_field")]
-///     other: String
+///     #[form(validate = len(1..))]
+///     other: String,
+///     #[form(default = 1)]
+///     page: usize,
 /// }
 /// ```
 ///
-/// The field attribute directs that a different incoming field name is
-/// expected, and the value of the `field` attribute is used instead of the
+/// The `field` parameter directs that a different incoming field name is
+/// expected, and the value of the `field` parameter is used instead of the
 /// structure's actual field name when parsing a form. In the example above, the
 /// value of the `MyStruct::other` struct field will be parsed from the incoming
 /// form's `renamed_field` field.
 ///
+/// The `default` parameter provides the value of a field that is missing from
+/// the form: `default = expr` uses `expr` while a bare `default` uses
+/// `Default::default()`. In the example above, `MyStruct::page` is `1` when the
+/// form has no `page` field. A field that is present but fails to parse is
+/// still an error. Validators are not run on default values.
+///
+/// The `validate` parameter, which may be repeated, names a validator to run on
+/// the parsed value of the field: `validate = len(1..)` calls
+/// `len(&value, 1..)`, and `validate = email` calls `email(&value)`. Each
+/// validator that fails adds an error with the validator's message to the
+/// [`FormErrors`]. Rocket's built-in validators, documented in [`validate`],
+/// are always in scope. Any other function in scope with the signature `fn(&T,
+/// ..) -> Result<(), E>`, where `T` is the type of the field and `E`
+/// implements `Display`, can be used as well.
+///
 /// [`FromForm`]: ../rocket/request/trait.FromForm.html
+/// [`FromFormField`]: ../rocket/request/trait.FromFormField.html
 /// [`FromFormValue`]: ../rocket/request/trait.FromFormValue.html
-/// [`FormParseError`]: ../rocket/request/enum.FormParseError.html
+/// [`FormErrors`]: ../rocket/request/struct.FormErrors.html
+/// [`validate`]: ../rocket/request/validate/index.html
 /// [`FromForm::Error`]: ../rocket/request/trait.FromForm.html#associatedtype.Error
 #[proc_macro_derive(FromForm, attributes(form))]
 pub fn derive_from_form(input: TokenStream) -> TokenStream {
@@ -1,10 +1,13 @@
 #[macro_use] extern crate rocket;
 
-use rocket::request::{FromForm, FormItems, FormParseError};
+use std::collections::HashMap;
+
+use rocket::request::{FromForm, FormItems, FormErrors, FieldErrorKind};
+use rocket::request::FieldErrorKind::*;
 use rocket::http::RawStr;
 
-fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormErrors>
+    where T: FromForm<'f, Error = FormErrors>
 {
     let mut items = FormItems::from(string);
     let result = T::from_form(items.by_ref(), strict);
@@ -15,14 +18,21 @@ fn parse<'f, T>(string: &'f str, strict: bool) -> Result<T, FormParseError<'f>>
     result
 }
 
-fn strict<'f, T>(string: &'f str) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn strict<'f, T>(string: &'f str) -> Result<T, FormErrors>
+    where T: FromForm<'f, Error = FormErrors>
 {
     parse(string, true)
 }
 
-fn lenient<'f, T>(string: &'f str) -> Result<T, FormParseError<'f>>
-    where T: FromForm<'f, Error = FormParseError<'f>>
+fn errors<T>(result: Result<T, FormErrors>) -> Vec<(String, FieldErrorKind)> {
+    match result {
+        Ok(_) => vec![],
+        Err(errors) => errors.into_iter().map(|e| (e.path, e.kind)).collect()
+    }
+}
+
+fn lenient<'f, T>(string: &'f str) -> Result<T, FormErrors>
+    where T: FromForm<'f, Error = FormErrors>
 {
     parse(string, false)
 }
@@ -296,24 +306,263 @@ fn form_errors() {
     let form: Result<WhoopsForm, _> = strict("complete=true&other=781");
     assert_eq!(form, Ok(WhoopsForm { complete: true, other: 781 }));
 
-    let form: Result<WhoopsForm, _> = strict("complete=true&other=unknown");
-    assert_eq!(form, Err(FormParseError::BadValue("other".into(), "unknown".into())));
+    let form = strict::<WhoopsForm>("complete=true&other=unknown");
+    assert_eq!(errors(form), [("other".into(), BadValue)]);
+
+    let form = strict::<WhoopsForm>("complete=true&other=1&extra=foo");
+    assert_eq!(errors(form), [("extra".into(), Unknown)]);
 
-    let form: Result<WhoopsForm, _> = strict("complete=unknown&other=unknown");
-    assert_eq!(form, Err(FormParseError::BadValue("complete".into(), "unknown".into())));
+    let form = strict::<WhoopsForm>("complete=true");
+    assert_eq!(errors(form), [("other".into(), Missing)]);
 
-    let form: Result<WhoopsForm, _> = strict("complete=true&other=1&extra=foo");
-    assert_eq!(form, Err(FormParseError::Unknown("extra".into(), "foo".into())));
+    // Every error is reported: first bad values and unknown key/values for
+    // strict parses in form order, then missing fields.
+    let form = strict::<WhoopsForm>("complete=unknown&other=unknown");
+    assert_eq!(errors(form), [("complete".into(), BadValue), ("other".into(), BadValue)]);
+
+    let form = strict::<WhoopsForm>("unknown=foo&complete=unknown");
+    assert_eq!(errors(form), [
+        ("unknown".into(), Unknown), ("complete".into(), BadValue), ("other".into(), Missing)
+    ]);
+
+    // A field whose value failed to parse isn't also missing.
+    let form = lenient::<WhoopsForm>("other=x&unknown=foo");
+    assert_eq!(errors(form), [("other".into(), BadValue)]);
+
+    // The submitted values are kept for re-rendering.
+    let errors = strict::<WhoopsForm>("complete=on&other=a%20b").unwrap_err();
+    assert_eq!(errors.get("other"), Some("invalid value"));
+    assert_eq!(errors.value("other"), Some("a b"));
+    assert_eq!(errors.value("complete"), Some("on"));
+    assert!(!errors.contains("complete"));
+}
 
-    // Bad values take highest precedence.
-    let form: Result<WhoopsForm, _> = strict("complete=unknown&unknown=foo");
-    assert_eq!(form, Err(FormParseError::BadValue("complete".into(), "unknown".into())));
+#[derive(Debug, PartialEq, FromForm)]
+struct Address {
+    city: String,
+    zip: usize,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Person {
+    name: String,
+    address: Address,
+}
+
+#[test]
+fn nested_structs() {
+    let person: Result<Person, _> = strict("name=Bob&address.city=Paris&address[zip]=75001");
+    assert_eq!(person, Ok(Person {
+        name: "Bob".into(),
+        address: Address { city: "Paris".into(), zip: 75001 },
+    }));
 
-    // Then unknown key/values for strict parses.
-    let form: Result<WhoopsForm, _> = strict("complete=true&unknown=foo");
-    assert_eq!(form, Err(FormParseError::Unknown("unknown".into(), "foo".into())));
+    let person: Result<Person, _> = strict("address%5Bcity%5D=Paris&address.zip=1&name=Bob");
+    assert_eq!(person.map(|p| p.address.city), Ok("Paris".into()));
+
+    let person = strict::<Person>("name=Bob");
+    assert_eq!(errors(person), [("address".into(), Missing)]);
+
+    let person = strict::<Person>("name=Bob&address.city=Paris");
+    assert_eq!(errors(person), [("address.zip".into(), Missing)]);
+
+    let person = strict::<Person>("name=Bob&address[city]=Paris&address[zip]=x");
+    assert_eq!(errors(person), [("address.zip".into(), BadValue)]);
+
+    let form = "name=Bob&address.city=Paris&address.zip=1&address.street=Main";
+    let person = strict::<Person>(form);
+    assert_eq!(errors(person), [("address.street".into(), Unknown)]);
+
+    let person: Result<Person, _> = lenient(form);
+    assert_eq!(person.map(|p| p.address.zip), Ok(1));
+
+    // A value for the structure itself is unexpected.
+    let person = strict::<Person>("name=Bob&address=Paris");
+    assert_eq!(errors(person), [
+        ("address".into(), Unknown),
+        ("address.city".into(), Missing),
+        ("address.zip".into(), Missing),
+    ]);
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Item {
+    name: String,
+    qty: usize,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Order {
+    tags: Vec<String>,
+    items: Vec<Item>,
+}
+
+#[test]
+fn vectors() {
+    let order: Result<Order, _> = strict("tags=a&tags[]=b&tags%5B%5D=c&tags.x=d");
+    assert_eq!(order, Ok(Order { tags: vec!["a".into(), "b".into(), "c".into(), "d".into()],
+        items: vec![] }));
+
+    let order: Result<Order, _> = strict("items[1].name=Pear&items[0][name]=Apple&\
+        items[1][qty]=1&items.0.qty=3");
+
+    assert_eq!(order, Ok(Order { tags: vec![], items: vec![
+        Item { name: "Apple".into(), qty: 3 },
+        Item { name: "Pear".into(), qty: 1 },
+    ]}));
+
+    let order = strict::<Order>("items[0].name=Apple&items[1].qty=x");
+    assert_eq!(errors(order), [
+        ("items.0.qty".into(), Missing),
+        ("items.1.qty".into(), BadValue),
+        ("items.1.name".into(), Missing),
+    ]);
+
+    let order = strict::<Order>("items[0].name=Apple&items[0].qty=1&items[0].x=1");
+    assert_eq!(errors(order), [("items.0.x".into(), Unknown)]);
+
+    let order = strict::<Order>("tags[0].x=a");
+    assert_eq!(errors(order), [("tags.0.x".into(), Unknown)]);
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Maps {
+    notes: HashMap<String, String>,
+    counts: HashMap<usize, Vec<usize>>,
+}
+
+#[test]
+fn maps() {
+    let maps: Maps = strict("notes.gift=yes&notes[to]=Bob&counts[1]=2&counts.1=3&counts[7][]=0")
+        .unwrap();
+
+    assert_eq!(maps.notes.len(), 2);
+    assert_eq!(maps.notes["gift"], "yes");
+    assert_eq!(maps.notes["to"], "Bob");
+    assert_eq!(maps.counts[&1], vec![2, 3]);
+    assert_eq!(maps.counts[&7], vec![0]);
+
+    let maps = strict::<Maps>("counts.x=1");
+    assert_eq!(errors(maps), [("counts.x".into(), BadValue)]);
+
+    let maps = strict::<Maps>("notes=yes");
+    assert_eq!(errors(maps), [("notes".into(), Unknown)]);
+
+    let maps: Result<Maps, _> = lenient("notes=yes");
+    assert_eq!(maps.map(|m| m.notes.is_empty()), Ok(true));
+}
+
+fn not_reserved(name: &str) -> Result<(), String> {
+    match name {
+        "admin" | "root" => Err(format!("'{}' is reserved", name)),
+        _ => Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Account<'r> {
+    #[form(validate = len(1..=8), validate = not_reserved())]
+    name: String,
+    #[form(field = "mail", validate = email)]
+    email: &'r RawStr,
+    #[form(validate = range(13..))]
+    age: u8,
+    #[form(validate = one_of(&["free", "pro"]))]
+    plan: String,
+    #[form(validate = len(..=2))]
+    tags: Vec<String>,
+}
+
+#[test]
+fn validation() {
+    let account = strict::<Account<'_>>("name=bob&mail=bob@example.com&age=30&plan=pro");
+    assert_eq!(account.map(|a| (a.name, a.age, a.plan)), Ok(("bob".into(), 30, "pro".into())));
+
+    // A `&RawStr` is validated by its percent-decoded value.
+    assert!(strict::<Account<'_>>("name=bob&mail=bob%40example.com&age=30&plan=pro").is_ok());
+    let errors = strict::<Account<'_>>("name=bob&mail=bob%20b@example.com&age=30&plan=pro");
+    assert_eq!(errors.unwrap_err().get("mail"), Some("value must be an email address"));
+
+    let form = "name=&mail=bob&age=12&plan=gold&tags=a&tags=b&tags=c";
+    let errors = strict::<Account<'_>>(form).unwrap_err();
+    assert_eq!(errors.len(), 5);
+    assert_eq!(errors.get("name"), Some("length must be at least 1 and at most 8"));
+    assert_eq!(errors.get("mail"), Some("value must be an email address"));
+    assert_eq!(errors.get("age"), Some("value must be at least 13"));
+    assert_eq!(errors.get("plan"), Some("value must be one of: free, pro"));
+    assert_eq!(errors.get("tags"), Some("length must be at most 2"));
+    assert!(errors.iter().all(|e| e.kind == Invalid));
+    assert_eq!(errors.value("plan"), Some("gold"));
+
+    // Only failing validators add errors; values that fail to parse
+    // aren't validated.
+    let errors = strict::<Account<'_>>("name=administrator&mail=a@b.c&age=x&plan=free").unwrap_err();
+    assert_eq!(errors.get_all("name").collect::<Vec<_>>(), ["length must be at least 1 and at most 8"]);
+    assert_eq!(errors.get("age"), Some("invalid value"));
+
+    let errors = strict::<Account<'_>>("name=root&mail=a@b.c&age=20&plan=free").unwrap_err();
+    assert_eq!(errors.get("name"), Some("'root' is reserved"));
+    assert_eq!(errors.to_string(), "name: 'root' is reserved");
+}
+
+fn default_tags() -> Vec<String> {
+    vec!["new".into()]
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Listing {
+    title: String,
+    #[form(default = 1)]
+    page: usize,
+    #[form(default)]
+    sort: String,
+    #[form(default = default_tags())]
+    tags: Vec<String>,
+    #[form(default = Range { min: 0, max: 10 })]
+    range: Range,
+    #[form(default = 5, validate = range(1..=10))]
+    size: u8,
+    #[form(default = true)]
+    notify: bool,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Range {
+    min: usize,
+    max: usize,
+}
+
+#[test]
+fn defaults() {
+    let listing = strict::<Listing>("title=Books");
+    assert_eq!(listing, Ok(Listing {
+        title: "Books".into(),
+        page: 1,
+        sort: "".into(),
+        tags: vec!["new".into()],
+        range: Range { min: 0, max: 10 },
+        size: 5,
+        notify: true,
+    }));
+
+    let form = "title=Books&page=3&sort=price&tags=a&range.min=2&range.max=4&size=2&notify=off";
+    let listing = strict::<Listing>(form);
+    assert_eq!(listing, Ok(Listing {
+        title: "Books".into(),
+        page: 3,
+        sort: "price".into(),
+        tags: vec!["a".into()],
+        range: Range { min: 2, max: 4 },
+        size: 2,
+        notify: false,
+    }));
 
-    // Finally, missing.
-    let form: Result<WhoopsForm, _> = strict("complete=true");
-    assert_eq!(form, Err(FormParseError::Missing("other".into())));
+    // Fields without a default are still required, and present values that
+    // fail to parse or validate are still errors.
+    assert_eq!(errors(strict::<Listing>("page=x&range.min=1&size=11")), vec![
+        ("page".into(), BadValue),
+        ("title".into(), Missing),
+        ("range.max".into(), Missing),
+        ("size".into(), Invalid),
+    ]);
 }
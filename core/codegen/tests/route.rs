@@ -122,3 +122,23 @@ fn test_full_route() {
     assert_eq!(response.body_string().unwrap(), format!("({}, {}, {}, {}, {}, {}) ({})",
             sky, name, "A A", "inside", path, simple, expected_uri));
 }
+
+#[get("/?<page>&<sort>&<limit>")]
+fn list(#[form(default = 1)] page: usize, #[form(default)] sort: String, limit: Option<u8>) -> String {
+    format!("{} {:?} {:?}", page, sort, limit)
+}
+
+#[test]
+fn test_query_defaults() {
+    let client = Client::new(rocket::ignite().mount("/", routes![list])).unwrap();
+
+    let mut response = client.get("/").dispatch();
+    assert_eq!(response.body_string().unwrap(), "1 \"\" None");
+
+    let mut response = client.get("/?sort=name&page=3&limit=7").dispatch();
+    assert_eq!(response.body_string().unwrap(), "3 \"name\" Some(7)");
+
+    // A parameter that is present but invalid is not replaced by the default.
+    let response = client.get("/?page=first").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
@@ -115,6 +115,26 @@ fn uri_display_bam() {
     assert_uri_display_query!(bam, "foo=hi%20hi&baz=tony");
 }
 
+#[derive(UriDisplayQuery)]
+struct Collections {
+    tags: Vec<String>,
+    notes: std::collections::HashMap<String, usize>,
+    bars: Vec<Bar<'static>>,
+}
+
+#[test]
+fn uri_display_collections() {
+    let mut notes = std::collections::HashMap::new();
+    notes.insert("a.b c".to_string(), 1);
+    let bars = vec![Bar { foo: Foo::First("x".into()), baz: "y".into() }];
+    let collections = Collections { tags: vec!["a".into(), "b c".into()], notes, bars };
+    assert_uri_display_query!(collections,
+        "tags.0=a&tags.1=b%20c&notes.a%2Eb%20c=1&bars.0.foo=x&bars.0.baz=y");
+
+    let collections = Collections { tags: vec![], notes: Default::default(), bars: vec![] };
+    assert_uri_display_query!(collections, "");
+}
+
 macro_rules! assert_uri_display_path {
     ($v:expr, $s:expr) => (
         let uri_string = format!("{}", &$v as &dyn UriDisplay<Path>);
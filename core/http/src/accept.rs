@@ -7,6 +7,7 @@ use smallvec::SmallVec;
 use crate::{Header, MediaType};
 use crate::ext::IntoCollection;
 use crate::parse::parse_accept;
+use crate::uncased::{UncasedStr, uncased_eq};
 
 /// A `MediaType` with an associated quality value.
 #[derive(Debug, Clone, PartialEq)]
@@ -275,6 +276,87 @@ impl Accept {
         preferred
     }
 
+    /// Selects the media type in `available` that is most acceptable to the
+    /// client, following the quality rules in [RFC 7231 5.3.2].
+    ///
+    /// The quality of each available media type is the quality value of the
+    /// most specific media range in `self` that matches it, or `1` if that
+    /// range has no quality value. Available media types with a quality of `0`,
+    /// or that aren't matched by any range, are not acceptable. Among media
+    /// types with equal quality, the one appearing first in `available` is
+    /// chosen. If `self` is empty, every media type is acceptable. Returns
+    /// `None` if no media type in `available` is acceptable.
+    ///
+    /// [RFC 7231 5.3.2]: https://tools.ietf.org/html/rfc7231#section-5.3.2
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{MediaType, Accept};
+    ///
+    /// let available = [MediaType::JSON, MediaType::MsgPack, MediaType::HTML];
+    ///
+    /// let accept: Accept = "text/html, application/*; q=0.5".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&MediaType::HTML));
+    ///
+    /// let accept: Accept = "application/msgpack, */*; q=0.1".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&MediaType::MsgPack));
+    ///
+    /// let accept: Accept = "text/*; q=0, */*".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&MediaType::JSON));
+    ///
+    /// let accept: Accept = "image/png".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), None);
+    /// ```
+    pub fn preferred_of<'a>(&self, available: &'a [MediaType]) -> Option<&'a MediaType> {
+        let mut preferred: Option<(&'a MediaType, f32)> = None;
+        for media_type in available {
+            let quality = self.quality_of(media_type);
+            if quality <= 0.0 {
+                continue;
+            }
+
+            match preferred {
+                Some((_, q)) if q >= quality => continue,
+                _ => preferred = Some((media_type, quality)),
+            }
+        }
+
+        preferred.map(|(media_type, _)| media_type)
+    }
+
+    /// Returns the quality with which the client accepts `media_type`: the
+    /// weight of the most specific range in `self` matching `media_type`.
+    fn quality_of(&self, media_type: &MediaType) -> f32 {
+        if self.iter().next().is_none() {
+            return 1.0;
+        }
+
+        let matches = |range: &MediaType| {
+            let covers = |a: &UncasedStr, b: &UncasedStr| a == "*" || a == b;
+            let has_param = |(k, v): (&str, &str)| {
+                media_type.params().any(|(k2, v2)| uncased_eq(k, k2) && v == v2)
+            };
+
+            covers(range.top(), media_type.top())
+                && covers(range.sub(), media_type.sub())
+                && range.params().filter(|&(k, _)| !uncased_eq(k, "q")).all(has_param)
+        };
+
+        let mut best: Option<(&QMediaType, usize)> = None;
+        for range in self.iter().filter(|range| matches(range.media_type())) {
+            let params = range.params().filter(|&(k, _)| !uncased_eq(k, "q")).count();
+            let precedence = range.specificity() as usize + params;
+            match best {
+                Some((_, p)) if p >= precedence => continue,
+                _ => best = Some((range, precedence)),
+            }
+        }
+
+        best.map(|(range, _)| range.weight_or(1.0)).unwrap_or(0.0)
+    }
+
     /// Retrieve the first media type in `self`, if any.
     ///
     /// # Example
@@ -432,4 +514,35 @@ mod test {
         assert_preference!("a/b; q=0.6; v=1, a/b; q=0.5; v=1; c=2",
             "a/b; q=0.6; v=1");
     }
+
+    macro_rules! assert_preferred_of {
+        ($string:expr, [$($available:expr),*], $expect:expr) => (
+            let accept: Accept = $string.parse().expect("accept string parse");
+            let available: Vec<MediaType> = vec![$($available.parse().unwrap()),*];
+            let expected: Option<MediaType> = $expect.map(|s: &str| s.parse().unwrap());
+            let preferred = accept.preferred_of(&available);
+            assert_eq!(preferred.map(|m| m.to_string()), expected.map(|m| m.to_string()));
+        )
+    }
+
+    #[test]
+    fn test_preferred_of() {
+        assert_preferred_of!("*/*", ["a/b", "c/d"], Some("a/b"));
+        assert_preferred_of!("c/d", ["a/b", "c/d"], Some("c/d"));
+        assert_preferred_of!("c/*", ["a/b", "c/d"], Some("c/d"));
+        assert_preferred_of!("e/f", ["a/b", "c/d"], None);
+
+        assert_preferred_of!("a/b; q=0.5, c/d", ["a/b", "c/d"], Some("c/d"));
+        assert_preferred_of!("a/b, c/d; q=0.5", ["a/b", "c/d"], Some("a/b"));
+        assert_preferred_of!("a/b, c/d", ["c/d", "a/b"], Some("c/d"));
+        assert_preferred_of!("*/*; q=0.1, c/d", ["a/b", "c/d"], Some("c/d"));
+        assert_preferred_of!("*/*, c/*; q=0.1", ["c/d", "a/b"], Some("a/b"));
+
+        assert_preferred_of!("a/b; q=0", ["a/b"], None);
+        assert_preferred_of!("a/*; q=0, */*", ["a/b", "c/d"], Some("c/d"));
+        assert_preferred_of!("a/*, a/b; q=0", ["a/b", "a/c"], Some("a/c"));
+
+        assert_preferred_of!("a/b; v=1", ["a/b", "a/b; v=1"], Some("a/b; v=1"));
+        assert_preferred_of!("a/b; q=0; v=1, a/b", ["a/b; v=1", "a/b"], Some("a/b"));
+    }
 }
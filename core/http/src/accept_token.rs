@@ -0,0 +1,514 @@
+use std::str::FromStr;
+use std::fmt;
+
+use crate::Header;
+use crate::uncased::{Uncased, UncasedStr};
+
+/// A token, such as a language range, content-coding, or charset, with an
+/// associated quality value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QToken(pub Uncased<'static>, pub Option<f32>);
+
+impl QToken {
+    /// Retrieve the weight of the token, if there is any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::QToken;
+    ///
+    /// let q_token = QToken("gzip".into(), Some(0.3));
+    /// assert_eq!(q_token.weight(), Some(0.3));
+    /// ```
+    #[inline(always)]
+    pub fn weight(&self) -> Option<f32> {
+        self.1
+    }
+
+    /// Retrieve the weight of the token or a given default value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::QToken;
+    ///
+    /// let q_token = QToken("gzip".into(), Some(0.3));
+    /// assert_eq!(q_token.weight_or(0.9), 0.3);
+    ///
+    /// let q_token = QToken("gzip".into(), None);
+    /// assert_eq!(q_token.weight_or(0.9), 0.9);
+    /// ```
+    #[inline(always)]
+    pub fn weight_or(&self, default: f32) -> f32 {
+        self.1.unwrap_or(default)
+    }
+
+    /// Borrow the token itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::QToken;
+    ///
+    /// let q_token = QToken("en-US".into(), Some(0.3));
+    /// assert_eq!(q_token.token(), "en-us");
+    /// assert_eq!(q_token.token().as_str(), "en-US");
+    /// ```
+    #[inline(always)]
+    pub fn token(&self) -> &UncasedStr {
+        &self.0
+    }
+}
+
+impl fmt::Display for QToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(q) => write!(f, "{}; q={}", self.0, q),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// Parses a comma-separated list of tokens, each with an optional quality
+/// value, as found in the `Accept-*` family of headers. Parameters other than
+/// `q` are ignored. Empty list elements are skipped as per RFC 7230 7.
+fn parse_qtokens(raw: &str, is_token_char: fn(char) -> bool) -> Result<Vec<QToken>, String> {
+    let mut tokens = vec![];
+    for element in raw.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let mut parts = element.split(';').map(|p| p.trim());
+        let token = parts.next().unwrap_or("");
+        if token.is_empty() || !token.chars().all(is_token_char) {
+            return Err(format!("invalid token '{}'", token));
+        }
+
+        let mut weight = None;
+        for param in parts {
+            let (key, value) = match param.find('=') {
+                Some(i) => (param[..i].trim(), param[(i + 1)..].trim()),
+                None => return Err(format!("invalid parameter '{}'", param)),
+            };
+
+            if key.eq_ignore_ascii_case("q") {
+                weight = match value.parse::<f32>().ok() {
+                    Some(q) if value.len() > 5 => return Err(format!("invalid weight {}", q)),
+                    Some(q) if q > 1. => return Err("q value must be <= 1".into()),
+                    Some(q) if q < 0. => return Err("q value must be >= 0".into()),
+                    Some(q) => Some(q),
+                    None => return Err(format!("invalid weight '{}'", value)),
+                };
+            }
+        }
+
+        tokens.push(QToken(Uncased::from(token.to_string()), weight));
+    }
+
+    Ok(tokens)
+}
+
+/// Returns the quality of the most specific token in `tokens` that matches
+/// `value`, or `None` if no token matches. `specificity` returns `None` when a
+/// token does not match and the specificity of the match otherwise.
+fn quality_of<F>(tokens: &[QToken], specificity: F) -> Option<f32>
+    where F: Fn(&UncasedStr) -> Option<usize>
+{
+    let mut best: Option<(&QToken, usize)> = None;
+    for token in tokens {
+        let precedence = match specificity(token.token()) {
+            Some(precedence) => precedence,
+            None => continue,
+        };
+
+        match best {
+            Some((_, p)) if p >= precedence => continue,
+            _ => best = Some((token, precedence)),
+        }
+    }
+
+    best.map(|(token, _)| token.weight_or(1.0))
+}
+
+/// Returns the value in `available` with the highest non-zero quality as
+/// determined by `quality`, preferring earlier values among equals.
+fn preferred_of<'a, S, F>(available: &'a [S], quality: F) -> Option<&'a S>
+    where S: AsRef<str>, F: Fn(&UncasedStr) -> f32
+{
+    let mut preferred: Option<(&'a S, f32)> = None;
+    for value in available {
+        let q = quality(value.as_ref().into());
+        if q <= 0.0 {
+            continue;
+        }
+
+        match preferred {
+            Some((_, best)) if best >= q => continue,
+            _ => preferred = Some((value, q)),
+        }
+    }
+
+    preferred.map(|(value, _)| value)
+}
+
+macro_rules! accept_token_header {
+    ($(#[$attr:meta])* $name:ident, $header:expr, $is_char:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name(Vec<QToken>);
+
+        impl $name {
+            /// Constructs a new header from the given quality tokens.
+            #[inline(always)]
+            pub fn new(tokens: Vec<QToken>) -> $name {
+                $name(tokens)
+            }
+
+            /// Returns an iterator over all of the quality tokens in `self` in
+            /// the order in which they appear in the header.
+            #[inline(always)]
+            pub fn iter<'a>(&'a self) -> impl Iterator<Item=&'a QToken> + 'a {
+                self.0.iter()
+            }
+
+            /// Returns an iterator over all of the (bare) tokens in `self` in
+            /// the order in which they appear in the header.
+            #[inline(always)]
+            pub fn tokens<'a>(&'a self) -> impl Iterator<Item=&'a UncasedStr> + 'a {
+                self.iter().map(|q_token| q_token.token())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for (i, token) in self.iter().enumerate() {
+                    if i >= 1 {
+                        write!(f, ", {}", token)?;
+                    } else {
+                        write!(f, "{}", token)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            #[inline]
+            fn from_str(raw: &str) -> Result<$name, String> {
+                parse_qtokens(raw, $is_char).map($name)
+            }
+        }
+
+        /// Creates a new `Header` with the name of this header and the value
+        /// set to the HTTP rendering of this header.
+        impl Into<Header<'static>> for $name {
+            #[inline(always)]
+            fn into(self) -> Header<'static> {
+                Header::new($header, self.to_string())
+            }
+        }
+    }
+}
+
+accept_token_header! {
+    /// The HTTP Accept-Language header.
+    ///
+    /// An `Accept-Language` header is composed of zero or more language ranges
+    /// (such as `en`, `en-US`, or `*`), each of which may have an optional
+    /// quality value. A language range matches a language tag if it is equal to
+    /// the tag, is a prefix of the tag followed by a `-`, or is `*`, as per the
+    /// "basic filtering" scheme of [RFC 4647 3.3.1].
+    ///
+    /// [RFC 4647 3.3.1]: https://tools.ietf.org/html/rfc4647#section-3.3.1
+    ///
+    /// # Usage
+    ///
+    /// The [`preferred_of()`](AcceptLanguage::preferred_of()) method selects
+    /// the language, among those available, that the client most prefers:
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept: AcceptLanguage = "fr-CH, fr; q=0.9, en; q=0.8".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["en-US", "fr-FR"]), Some(&"fr-FR"));
+    /// assert_eq!(accept.preferred_of(&["en-US", "de"]), Some(&"en-US"));
+    /// assert_eq!(accept.preferred_of(&["de"]), None);
+    /// ```
+    AcceptLanguage, "Accept-Language",
+    |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '*'
+}
+
+accept_token_header! {
+    /// The HTTP Accept-Encoding header.
+    ///
+    /// An `Accept-Encoding` header is composed of zero or more content-codings
+    /// (such as `gzip`, `br`, or `*`), each of which may have an optional
+    /// quality value. As per [RFC 7231 5.3.4], the `identity` coding is always
+    /// acceptable, albeit least preferred, unless it is explicitly excluded via
+    /// `identity; q=0` or `*; q=0`. An empty header only allows `identity`.
+    ///
+    /// [RFC 7231 5.3.4]: https://tools.ietf.org/html/rfc7231#section-5.3.4
+    ///
+    /// # Usage
+    ///
+    /// The [`preferred_of()`](AcceptEncoding::preferred_of()) method selects
+    /// the coding, among those available, that the client most prefers:
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptEncoding;
+    ///
+    /// let available = ["br", "gzip", "identity"];
+    ///
+    /// let accept: AcceptEncoding = "gzip, br".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&"br"));
+    ///
+    /// let accept: AcceptEncoding = "gzip; q=0, br; q=0.5".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&"br"));
+    ///
+    /// let accept: AcceptEncoding = "deflate, *; q=0".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), None);
+    ///
+    /// let accept: AcceptEncoding = "deflate".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&available), Some(&"identity"));
+    /// ```
+    AcceptEncoding, "Accept-Encoding",
+    |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+accept_token_header! {
+    /// The HTTP Accept-Charset header.
+    ///
+    /// An `Accept-Charset` header is composed of zero or more charsets (such as
+    /// `utf-8` or `*`), each of which may have an optional quality value, as
+    /// described in [RFC 7231 5.3.3]. An empty header accepts any charset.
+    ///
+    /// [RFC 7231 5.3.3]: https://tools.ietf.org/html/rfc7231#section-5.3.3
+    ///
+    /// # Usage
+    ///
+    /// The [`preferred_of()`](AcceptCharset::preferred_of()) method selects the
+    /// charset, among those available, that the client most prefers:
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptCharset;
+    ///
+    /// let accept: AcceptCharset = "iso-8859-5, utf-8; q=0.8".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["UTF-8", "ISO-8859-5"]), Some(&"ISO-8859-5"));
+    /// assert_eq!(accept.preferred_of(&["UTF-8", "US-ASCII"]), Some(&"UTF-8"));
+    /// ```
+    AcceptCharset, "Accept-Charset",
+    |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+impl AcceptLanguage {
+    /// Selects the language tag in `available` that is most acceptable to the
+    /// client. The quality of each tag is the quality value of the most
+    /// specific language range in `self` that matches it. Tags with a quality
+    /// of `0` or that aren't matched by any range are not acceptable. Among
+    /// tags with equal quality, the one appearing first in `available` is
+    /// chosen. If `self` is empty, every tag is acceptable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept: AcceptLanguage = "en; q=0.5, en-GB, *; q=0.1".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["en-US", "en-GB"]), Some(&"en-GB"));
+    /// assert_eq!(accept.preferred_of(&["de", "en-US"]), Some(&"en-US"));
+    /// assert_eq!(accept.preferred_of(&["de", "fr"]), Some(&"de"));
+    /// ```
+    pub fn preferred_of<'a, S: AsRef<str>>(&self, available: &'a [S]) -> Option<&'a S> {
+        if self.0.is_empty() {
+            return available.first();
+        }
+
+        preferred_of(available, |tag| {
+            quality_of(&self.0, |range| {
+                if range == "*" {
+                    Some(0)
+                } else if range == tag {
+                    Some(range.as_str().split('-').count())
+                } else {
+                    let (len, tag) = (range.as_str().len(), tag.as_str());
+                    let prefix = tag.get(..len)?;
+                    if range == prefix && tag[len..].starts_with('-') {
+                        Some(range.as_str().split('-').count())
+                    } else {
+                        None
+                    }
+                }
+            }).unwrap_or(0.0)
+        })
+    }
+}
+
+impl AcceptEncoding {
+    /// Selects the content-coding in `available` that is most acceptable to the
+    /// client. The quality of each coding is the quality value of the token in
+    /// `self` naming it or, if there is none, that of `*`. Codings with a
+    /// quality of `0` or that aren't matched by any token are not acceptable
+    /// with the exception of `identity`, which is always acceptable with the
+    /// lowest non-zero quality unless it is explicitly excluded. Among codings
+    /// with equal quality, the one appearing first in `available` is chosen.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptEncoding;
+    ///
+    /// let accept: AcceptEncoding = "gzip; q=0.8, br".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["gzip", "br"]), Some(&"br"));
+    ///
+    /// let accept: AcceptEncoding = "".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["gzip", "identity"]), Some(&"identity"));
+    /// ```
+    pub fn preferred_of<'a, S: AsRef<str>>(&self, available: &'a [S]) -> Option<&'a S> {
+        preferred_of(available, |coding| {
+            let quality = quality_of(&self.0, |token| {
+                if token == "*" {
+                    Some(0)
+                } else if token == coding {
+                    Some(1)
+                } else {
+                    None
+                }
+            });
+
+            match quality {
+                Some(q) => q,
+                None if coding == "identity" => 0.001,
+                None => 0.0
+            }
+        })
+    }
+}
+
+impl AcceptCharset {
+    /// Selects the charset in `available` that is most acceptable to the
+    /// client. The quality of each charset is the quality value of the token in
+    /// `self` naming it or, if there is none, that of `*`. Charsets with a
+    /// quality of `0` or that aren't matched by any token are not acceptable.
+    /// Among charsets with equal quality, the one appearing first in
+    /// `available` is chosen. If `self` is empty, every charset is acceptable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::AcceptCharset;
+    ///
+    /// let accept: AcceptCharset = "utf-8, *; q=0.5".parse().unwrap();
+    /// assert_eq!(accept.preferred_of(&["us-ascii", "utf-8"]), Some(&"utf-8"));
+    /// assert_eq!(accept.preferred_of(&["us-ascii"]), Some(&"us-ascii"));
+    /// ```
+    pub fn preferred_of<'a, S: AsRef<str>>(&self, available: &'a [S]) -> Option<&'a S> {
+        if self.0.is_empty() {
+            return available.first();
+        }
+
+        preferred_of(available, |charset| {
+            quality_of(&self.0, |token| {
+                if token == "*" {
+                    Some(0)
+                } else if token == charset {
+                    Some(1)
+                } else {
+                    None
+                }
+            }).unwrap_or(0.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! assert_preferred_of {
+        ($T:ident: $string:expr, [$($available:expr),*], $expect:expr) => ({
+            let header: $T = $string.parse().expect("header string parse");
+            let available: &[&str] = &[$($available),*];
+            let preferred = header.preferred_of(available).map(|s| *s);
+            assert_eq!(preferred, $expect, "{:?}", $string);
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let header: AcceptEncoding = " gzip;q=0.5 , , br ; Q=1,identity;q=0".parse().unwrap();
+        let tokens: Vec<_> = header.iter().cloned().collect();
+        assert_eq!(tokens, vec![
+            QToken("gzip".into(), Some(0.5)),
+            QToken("br".into(), Some(1.0)),
+            QToken("identity".into(), Some(0.0)),
+        ]);
+
+        assert_eq!(header.to_string(), "gzip; q=0.5, br; q=1, identity; q=0");
+
+        assert!("gzip; q=2".parse::<AcceptEncoding>().is_err());
+        assert!("gzip; q=-1".parse::<AcceptEncoding>().is_err());
+        assert!("gzip; q=0.55555".parse::<AcceptEncoding>().is_err());
+        assert!("gzip; q".parse::<AcceptEncoding>().is_err());
+        assert!("gz ip".parse::<AcceptEncoding>().is_err());
+        assert!("en_US".parse::<AcceptLanguage>().is_err());
+        assert!("; q=1".parse::<AcceptCharset>().is_err());
+    }
+
+    #[test]
+    fn test_parse_zero_weight() {
+        let header: AcceptEncoding = "gzip; q=0".parse().unwrap();
+        let tokens: Vec<_> = header.iter().cloned().collect();
+        assert_eq!(tokens, vec![QToken("gzip".into(), Some(0.0))]);
+
+        let error = "gzip; q=-0.1".parse::<AcceptEncoding>().unwrap_err();
+        assert_eq!(error, "q value must be >= 0");
+    }
+
+    #[test]
+    fn test_language_preferred_of() {
+        assert_preferred_of!(AcceptLanguage: "", ["en", "fr"], Some("en"));
+        assert_preferred_of!(AcceptLanguage: "*", ["en", "fr"], Some("en"));
+        assert_preferred_of!(AcceptLanguage: "fr", ["en", "fr"], Some("fr"));
+        assert_preferred_of!(AcceptLanguage: "FR", ["en", "fr-ca"], Some("fr-ca"));
+        assert_preferred_of!(AcceptLanguage: "fr-CA", ["en", "fr"], None);
+        assert_preferred_of!(AcceptLanguage: "fr", ["en", "fra"], None);
+        assert_preferred_of!(AcceptLanguage: "en, fr", ["fr", "en"], Some("fr"));
+        assert_preferred_of!(AcceptLanguage: "en; q=0.5, fr", ["en", "fr"], Some("fr"));
+        assert_preferred_of!(AcceptLanguage: "en, en-GB; q=0", ["en-GB", "en-US"], Some("en-US"));
+        assert_preferred_of!(AcceptLanguage: "*; q=0.1, de", ["en", "de-AT"], Some("de-AT"));
+        assert_preferred_of!(AcceptLanguage: "*; q=0", ["en", "de"], None);
+    }
+
+    #[test]
+    fn test_encoding_preferred_of() {
+        assert_preferred_of!(AcceptEncoding: "", ["gzip", "identity"], Some("identity"));
+        assert_preferred_of!(AcceptEncoding: "", ["gzip"], None);
+        assert_preferred_of!(AcceptEncoding: "*", ["br", "gzip"], Some("br"));
+        assert_preferred_of!(AcceptEncoding: "gzip", ["br", "gzip"], Some("gzip"));
+        assert_preferred_of!(AcceptEncoding: "GZIP", ["br", "gzip"], Some("gzip"));
+        assert_preferred_of!(AcceptEncoding: "gzip;q=0", ["gzip", "identity"], Some("identity"));
+        assert_preferred_of!(AcceptEncoding: "gzip;q=0.1", ["gzip", "identity"], Some("gzip"));
+        assert_preferred_of!(AcceptEncoding: "gzip, identity;q=0", ["identity"], None);
+        assert_preferred_of!(AcceptEncoding: "gzip, *;q=0", ["br", "identity"], None);
+        assert_preferred_of!(AcceptEncoding: "*;q=0.5, br", ["gzip", "br"], Some("br"));
+        assert_preferred_of!(AcceptEncoding: "br;q=0.5, gzip", ["br", "gzip"], Some("gzip"));
+    }
+
+    #[test]
+    fn test_charset_preferred_of() {
+        assert_preferred_of!(AcceptCharset: "", ["utf-8", "latin1"], Some("utf-8"));
+        assert_preferred_of!(AcceptCharset: "latin1", ["utf-8", "latin1"], Some("latin1"));
+        assert_preferred_of!(AcceptCharset: "UTF-8", ["utf-8"], Some("utf-8"));
+        assert_preferred_of!(AcceptCharset: "latin1", ["utf-8"], None);
+        assert_preferred_of!(AcceptCharset: "*, utf-8;q=0", ["utf-8", "latin1"], Some("latin1"));
+    }
+}
@@ -0,0 +1,248 @@
+use std::str::FromStr;
+use std::fmt;
+
+use crate::Header;
+
+/// The HTTP Cache-Control response header.
+///
+/// A `CacheControl` is a set of the response directives defined in [RFC 7234
+/// 5.2.2], [RFC 5861], and [RFC 8246] that govern how clients and shared
+/// caches, such as CDNs, may store and reuse a response:
+///
+///   * **public**: any cache may store the response.
+///   * **private**: only the client's own cache may store the response.
+///   * **no-cache**: a stored response must be revalidated before each use.
+///   * **no-store**: no cache may store the response.
+///   * **must-revalidate**: a stale response must be revalidated before use.
+///   * **immutable**: the response won't change while fresh, so it needn't be
+///     revalidated, even on reload.
+///   * **max-age=N**: the response is fresh for `N` seconds.
+///   * **s-maxage=N**: the response is fresh for `N` seconds in shared caches,
+///     overriding `max-age`.
+///   * **stale-while-revalidate=N**: a stale response may be used for up to
+///     `N` seconds while it is revalidated in the background.
+///
+/// [RFC 7234 5.2.2]: https://tools.ietf.org/html/rfc7234#section-5.2.2
+/// [RFC 5861]: https://tools.ietf.org/html/rfc5861
+/// [RFC 8246]: https://tools.ietf.org/html/rfc8246
+///
+/// # Usage
+///
+/// A `CacheControl` is built with its builder methods or parsed from its HTTP
+/// rendering via [`FromStr`]. It converts into a `Cache-Control` [`Header`]:
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::CacheControl;
+///
+/// let assets = CacheControl::new().public().max_age(31536000).immutable();
+/// assert_eq!(assets.to_string(), "public, max-age=31536000, immutable");
+///
+/// let api: CacheControl = "private, no-store".parse().unwrap();
+/// assert_eq!(api, CacheControl::new().private().no_store());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CacheControl {
+    public: bool,
+    private: bool,
+    no_cache: bool,
+    no_store: bool,
+    must_revalidate: bool,
+    immutable: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    stale_while_revalidate: Option<u64>,
+}
+
+impl CacheControl {
+    /// Returns a new `CacheControl` with no directives.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::CacheControl;
+    ///
+    /// let cache_control = CacheControl::new();
+    /// assert!(cache_control.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn new() -> CacheControl {
+        CacheControl::default()
+    }
+
+    /// Adds the `public` directive.
+    #[inline(always)]
+    pub fn public(mut self) -> CacheControl {
+        self.public = true;
+        self
+    }
+
+    /// Adds the `private` directive.
+    #[inline(always)]
+    pub fn private(mut self) -> CacheControl {
+        self.private = true;
+        self
+    }
+
+    /// Adds the `no-cache` directive.
+    #[inline(always)]
+    pub fn no_cache(mut self) -> CacheControl {
+        self.no_cache = true;
+        self
+    }
+
+    /// Adds the `no-store` directive.
+    #[inline(always)]
+    pub fn no_store(mut self) -> CacheControl {
+        self.no_store = true;
+        self
+    }
+
+    /// Adds the `must-revalidate` directive.
+    #[inline(always)]
+    pub fn must_revalidate(mut self) -> CacheControl {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// Adds the `immutable` directive.
+    #[inline(always)]
+    pub fn immutable(mut self) -> CacheControl {
+        self.immutable = true;
+        self
+    }
+
+    /// Sets the `max-age` directive to `seconds`.
+    #[inline(always)]
+    pub fn max_age(mut self, seconds: u64) -> CacheControl {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets the `s-maxage` directive to `seconds`.
+    #[inline(always)]
+    pub fn s_maxage(mut self, seconds: u64) -> CacheControl {
+        self.s_maxage = Some(seconds);
+        self
+    }
+
+    /// Sets the `stale-while-revalidate` directive to `seconds`.
+    #[inline(always)]
+    pub fn stale_while_revalidate(mut self, seconds: u64) -> CacheControl {
+        self.stale_while_revalidate = Some(seconds);
+        self
+    }
+
+    /// Returns `true` if `self` has no directives.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::CacheControl;
+    ///
+    /// assert!(CacheControl::new().is_empty());
+    /// assert!(!CacheControl::new().no_store().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        *self == CacheControl::default()
+    }
+}
+
+impl fmt::Display for CacheControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives = vec![];
+        if self.public { directives.push("public".to_string()); }
+        if self.private { directives.push("private".to_string()); }
+        if self.no_cache { directives.push("no-cache".to_string()); }
+        if self.no_store { directives.push("no-store".to_string()); }
+        if self.must_revalidate { directives.push("must-revalidate".to_string()); }
+        if let Some(n) = self.max_age { directives.push(format!("max-age={}", n)); }
+        if let Some(n) = self.s_maxage { directives.push(format!("s-maxage={}", n)); }
+        if let Some(n) = self.stale_while_revalidate {
+            directives.push(format!("stale-while-revalidate={}", n));
+        }
+
+        if self.immutable { directives.push("immutable".to_string()); }
+        write!(f, "{}", directives.join(", "))
+    }
+}
+
+impl FromStr for CacheControl {
+    type Err = String;
+
+    /// Parses a comma-separated list of directives. Directive names are
+    /// case-insensitive. Unknown directives are rejected.
+    fn from_str(raw: &str) -> Result<CacheControl, String> {
+        let mut cache_control = CacheControl::new();
+        for directive in raw.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            let (name, value) = match directive.find('=') {
+                Some(i) => (directive[..i].trim(), Some(directive[(i + 1)..].trim())),
+                None => (directive, None),
+            };
+
+            let seconds = || -> Result<u64, String> {
+                value.map(|v| v.trim_matches('"'))
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| format!("'{}' requires a number of seconds", name))
+            };
+
+            let flag = |set: &mut bool| -> Result<(), String> {
+                match value {
+                    Some(_) => Err(format!("'{}' does not take a value", name)),
+                    None => {
+                        *set = true;
+                        Ok(())
+                    }
+                }
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "public" => flag(&mut cache_control.public)?,
+                "private" => flag(&mut cache_control.private)?,
+                "no-cache" => flag(&mut cache_control.no_cache)?,
+                "no-store" => flag(&mut cache_control.no_store)?,
+                "must-revalidate" => flag(&mut cache_control.must_revalidate)?,
+                "immutable" => flag(&mut cache_control.immutable)?,
+                "max-age" => cache_control.max_age = Some(seconds()?),
+                "s-maxage" => cache_control.s_maxage = Some(seconds()?),
+                "stale-while-revalidate" => {
+                    cache_control.stale_while_revalidate = Some(seconds()?);
+                }
+                _ => return Err(format!("unknown directive '{}'", name)),
+            }
+        }
+
+        Ok(cache_control)
+    }
+}
+
+/// Creates a new `Cache-Control` header with the value set to the HTTP
+/// rendering of this header.
+impl Into<Header<'static>> for CacheControl {
+    #[inline(always)]
+    fn into(self) -> Header<'static> {
+        Header::new("Cache-Control", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CacheControl;
+
+    #[test]
+    fn test_parse_cache_control() {
+        let all = CacheControl::new().public().private().no_cache().no_store()
+            .must_revalidate().immutable().max_age(1).s_maxage(2).stale_while_revalidate(3);
+
+        assert_eq!(all.to_string().parse(), Ok(all));
+        assert_eq!("Max-Age=\"60\", PUBLIC".parse(), Ok(CacheControl::new().public().max_age(60)));
+        assert_eq!("".parse(), Ok(CacheControl::new()));
+
+        assert!("max-age".parse::<CacheControl>().is_err());
+        assert!("max-age=-1".parse::<CacheControl>().is_err());
+        assert!("public=1".parse::<CacheControl>().is_err());
+        assert!("forever".parse::<CacheControl>().is_err());
+    }
+}
@@ -36,17 +36,26 @@ mod key {
 ///
 /// A type of `Cookies` can be retrieved via its `FromRequest` implementation as
 /// a request guard or via the [`Request::cookies()`] method. Individual cookies
-/// can be retrieved via the [`get()`] and [`get_private()`] methods. Cookies
-/// can be added or removed via the [`add()`], [`add_private()`], [`remove()`],
-/// and [`remove_private()`] methods.
+/// can be retrieved via the [`get()`], [`get_private()`], and [`get_signed()`]
+/// methods. Cookies can be added or removed via the [`add()`],
+/// [`add_private()`], [`add_signed()`], [`remove()`], [`remove_private()`],
+/// and [`remove_signed()`] methods.
+///
+/// Default attributes for added cookies, such as `SameSite` and `Secure`, can
+/// be set via the `cookies` configuration parameter; see [`CookieDefaults`].
+/// Cookies whose names use the `__Host-` or `__Secure-` prefixes are held to
+/// the rules of their prefix; see [`CookiePrefix`].
 ///
 /// [`Request::cookies()`]: rocket::Request::cookies()
 /// [`get()`]: #method.get
 /// [`get_private()`]: #method.get_private
+/// [`get_signed()`]: #method.get_signed
 /// [`add()`]: #method.add
 /// [`add_private()`]: #method.add_private
+/// [`add_signed()`]: #method.add_signed
 /// [`remove()`]: #method.remove
 /// [`remove_private()`]: #method.remove_private
+/// [`remove_signed()`]: #method.remove_signed
 ///
 /// ## Examples
 ///
@@ -122,13 +131,32 @@ mod key {
 /// restart. Rocket will emit a warning if an application is run in production
 /// mode without a configured `secret_key`.
 ///
+/// To rotate the key, the `secret_keys` configuration parameter can be used in
+/// place of `secret_key` to specify a primary key along with any number of
+/// retired keys. New cookies are always encrypted with the primary key. Private
+/// and signed cookies that can only be read with a retired key are reissued,
+/// encrypted or signed with the primary key, in the response.
+///
 /// Generating a string suitable for use as a `secret_key` configuration value
 /// is usually done through tools like `openssl`. Using `openssl`, for instance,
 /// a 256-bit base64 key can be generated with the command `openssl rand -base64
 /// 32`.
+///
+/// # Signed Cookies
+///
+/// _Signed_ cookies sit between regular and private cookies: their values are
+/// signed with the same `secret_key` used for private cookies, so they cannot
+/// be tampered with or manufactured by clients, but they are not encrypted.
+/// Their values remain readable by clients, including by client-side
+/// JavaScript. Use signed cookies for values that the client must be able to
+/// read but must not be able to forge.
+///
+/// Signed cookies can be retrieved, added, and removed from a `Cookies`
+/// collection via the [`get_signed()`], [`add_signed()`], and
+/// [`remove_signed()`] methods.
 pub enum Cookies<'a> {
     #[doc(hidden)]
-    Jarred(RefMut<'a, CookieJar>, &'a Key),
+    Jarred(RefMut<'a, CookieJar>, &'a Key, &'a [Key], &'a CookieDefaults),
     #[doc(hidden)]
     Empty(CookieJar)
 }
@@ -137,8 +165,13 @@ impl<'a> Cookies<'a> {
     /// WARNING: This is unstable! Do not use this method outside of Rocket!
     #[inline]
     #[doc(hidden)]
-    pub fn new(jar: RefMut<'a, CookieJar>, key: &'a Key) -> Cookies<'a> {
-        Cookies::Jarred(jar, key)
+    pub fn new(
+        jar: RefMut<'a, CookieJar>,
+        key: &'a Key,
+        retired: &'a [Key],
+        defaults: &'a CookieDefaults
+    ) -> Cookies<'a> {
+        Cookies::Jarred(jar, key, retired, defaults)
     }
 
     /// WARNING: This is unstable! Do not use this method outside of Rocket!
@@ -160,7 +193,7 @@ impl<'a> Cookies<'a> {
     #[inline]
     #[doc(hidden)]
     pub fn add_original(&mut self, cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, _) = *self {
+        if let Cookies::Jarred(ref mut jar, ..) = *self {
             jar.add_original(cookie)
         }
     }
@@ -180,13 +213,17 @@ impl<'a> Cookies<'a> {
     /// ```
     pub fn get(&self, name: &str) -> Option<&Cookie<'static>> {
         match *self {
-            Cookies::Jarred(ref jar, _) => jar.get(name),
+            Cookies::Jarred(ref jar, ..) => jar.get(name),
             Cookies::Empty(_) => None
         }
     }
 
     /// Adds `cookie` to this collection.
     ///
+    /// Any attribute that is unset in `cookie` but has a configured default in
+    /// the `cookies` configuration parameter is set to the configured default
+    /// before `cookie` is added. See [`CookieDefaults`] for details.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -204,8 +241,9 @@ impl<'a> Cookies<'a> {
     ///     cookies.add(cookie);
     /// }
     /// ```
-    pub fn add(&mut self, cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, _) = *self {
+    pub fn add(&mut self, mut cookie: Cookie<'static>) {
+        if let Cookies::Jarred(ref mut jar, _, _, defaults) = *self {
+            defaults.apply(&mut cookie);
             jar.add(cookie)
         }
     }
@@ -220,6 +258,10 @@ impl<'a> Cookies<'a> {
     /// cookie but has an empty value, a max-age of 0, and an expiration date
     /// far in the past.
     ///
+    /// If `cookie` has no `path` or `domain` and a default for either is
+    /// configured, the configured default is used, matching the attributes set
+    /// by [`add()`](#method.add).
+    ///
     /// # Example
     ///
     /// ```rust
@@ -230,8 +272,10 @@ impl<'a> Cookies<'a> {
     ///     cookies.remove(Cookie::named("name"));
     /// }
     /// ```
-    pub fn remove(&mut self, cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, _) = *self {
+    pub fn remove(&mut self, mut cookie: Cookie<'static>) {
+        if let Cookies::Jarred(ref mut jar, _, _, defaults) = *self {
+            CookiePrefix::fill(&mut cookie);
+            defaults.apply_location(&mut cookie);
             jar.remove(cookie)
         }
     }
@@ -242,7 +286,7 @@ impl<'a> Cookies<'a> {
     #[doc(hidden)]
     pub fn reset_delta(&mut self) {
         match *self {
-            Cookies::Jarred(ref mut jar, _) => jar.reset_delta(),
+            Cookies::Jarred(ref mut jar, ..) => jar.reset_delta(),
             Cookies::Empty(ref mut jar) => jar.reset_delta()
         }
     }
@@ -263,7 +307,7 @@ impl<'a> Cookies<'a> {
     /// ```
     pub fn iter(&self) -> impl Iterator<Item=&Cookie<'static>> {
         match *self {
-            Cookies::Jarred(ref jar, _) => jar.iter(),
+            Cookies::Jarred(ref jar, ..) => jar.iter(),
             Cookies::Empty(ref jar) => jar.iter()
         }
     }
@@ -273,7 +317,7 @@ impl<'a> Cookies<'a> {
     #[doc(hidden)]
     pub fn delta(&self) -> Delta<'_> {
         match *self {
-            Cookies::Jarred(ref jar, _) => jar.delta(),
+            Cookies::Jarred(ref jar, ..) => jar.delta(),
             Cookies::Empty(ref jar) => jar.delta()
         }
     }
@@ -286,6 +330,13 @@ impl Cookies<'_> {
     /// `Cookie` with the decrypted value. If the cookie cannot be found, or the
     /// cookie fails to authenticate or decrypt, `None` is returned.
     ///
+    /// The cookie is first decrypted with the primary secret key and then with
+    /// each retired secret key, if any, in turn. A cookie that decrypts only
+    /// with a retired key is reissued: it is re-encrypted with the primary key,
+    /// with the defaults listed in [`add_private`](#method.add_private)
+    /// applied, and added to the collection so that it is sent with the
+    /// response.
+    ///
     /// This method is only available when the `private-cookies` feature is
     /// enabled.
     ///
@@ -301,7 +352,21 @@ impl Cookies<'_> {
     /// ```
     pub fn get_private(&mut self, name: &str) -> Option<Cookie<'static>> {
         match *self {
-            Cookies::Jarred(ref mut jar, key) => jar.private(key).get(name),
+            Cookies::Jarred(ref mut jar, key, retired, defaults) => {
+                if let Some(cookie) = jar.private(key).get(name) {
+                    return Some(cookie);
+                }
+
+                let cookie = retired.iter()
+                    .filter_map(|old_key| jar.private(old_key).get(name))
+                    .next()?;
+
+                let mut reissued = cookie.clone();
+                defaults.apply(&mut reissued);
+                Cookies::set_private_defaults(&mut reissued);
+                jar.private(key).add(reissued);
+                Some(cookie)
+            }
             Cookies::Empty(_) => None
         }
     }
@@ -318,10 +383,12 @@ impl Cookies<'_> {
     ///    * `path`: `"/"`
     ///    * `SameSite`: `Strict`
     ///    * `HttpOnly`: `true`
-    ///    * `Expires`: 1 week from now
+    ///    * `Expires`: 1 week from now, unless `Max-Age` is set
     ///
     /// These defaults ensure maximum usability and security. For additional
-    /// security, you may wish to set the `secure` flag.
+    /// security, you may wish to set the `secure` flag. Defaults from the
+    /// `cookies` configuration parameter take precedence over these defaults;
+    /// see [`CookieDefaults`].
     ///
     /// This method is only available when the `private-cookies` feature is
     /// enabled.
@@ -337,7 +404,8 @@ impl Cookies<'_> {
     /// }
     /// ```
     pub fn add_private(&mut self, mut cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, key) = *self {
+        if let Cookies::Jarred(ref mut jar, key, _, defaults) = *self {
+            defaults.apply(&mut cookie);
             Cookies::set_private_defaults(&mut cookie);
             jar.private(key).add(cookie)
         }
@@ -347,7 +415,7 @@ impl Cookies<'_> {
     /// WARNING: This is unstable! Do not use this method outside of Rocket!
     #[doc(hidden)]
     pub fn add_original_private(&mut self, mut cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, key) = *self {
+        if let Cookies::Jarred(ref mut jar, key, ..) = *self {
             Cookies::set_private_defaults(&mut cookie);
             jar.private(key).add_original(cookie)
         }
@@ -361,7 +429,7 @@ impl Cookies<'_> {
     ///    * `path`: `"/"`
     ///    * `SameSite`: `Strict`
     ///    * `HttpOnly`: `true`
-    ///    * `Expires`: 1 week from now
+    ///    * `Expires`: 1 week from now, unless `Max-Age` is set
     ///
     fn set_private_defaults(cookie: &mut Cookie<'static>) {
         if cookie.path().is_none() {
@@ -372,7 +440,7 @@ impl Cookies<'_> {
             cookie.set_http_only(true);
         }
 
-        if cookie.expires().is_none() {
+        if cookie.expires().is_none() && cookie.max_age().is_none() {
             cookie.set_expires(time::OffsetDateTime::now() + time::Duration::weeks(1));
         }
 
@@ -401,7 +469,9 @@ impl Cookies<'_> {
     /// }
     /// ```
     pub fn remove_private(&mut self, mut cookie: Cookie<'static>) {
-        if let Cookies::Jarred(ref mut jar, key) = *self {
+        if let Cookies::Jarred(ref mut jar, key, _, defaults) = *self {
+            CookiePrefix::fill(&mut cookie);
+            defaults.apply_location(&mut cookie);
             if cookie.path().is_none() {
                 cookie.set_path("/");
             }
@@ -411,10 +481,471 @@ impl Cookies<'_> {
     }
 }
 
+#[cfg(feature = "private-cookies")]
+impl Cookies<'_> {
+    /// Returns a reference to the `Cookie` inside this collection with the name
+    /// `name` and verifies the cookie's signature, returning a `Cookie` with the
+    /// original, unsigned value. If the cookie cannot be found, or the cookie
+    /// fails to verify, `None` is returned.
+    ///
+    /// The signature is first verified with the primary secret key and then
+    /// with each retired secret key, if any, in turn. A cookie that verifies
+    /// only with a retired key is reissued: it is re-signed with the primary
+    /// key, with the defaults listed in [`add_signed`](#method.add_signed)
+    /// applied, and added to the collection so that it is sent with the
+    /// response.
+    ///
+    /// This method is only available when the `private-cookies` feature is
+    /// enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::Cookies;
+    ///
+    /// fn handler(mut cookies: Cookies) {
+    ///     let cookie = cookies.get_signed("name");
+    /// }
+    /// ```
+    pub fn get_signed(&mut self, name: &str) -> Option<Cookie<'static>> {
+        match *self {
+            Cookies::Jarred(ref mut jar, key, retired, defaults) => {
+                if let Some(cookie) = jar.signed(key).get(name) {
+                    return Some(cookie);
+                }
+
+                let cookie = retired.iter()
+                    .filter_map(|old_key| jar.signed(old_key).get(name))
+                    .next()?;
+
+                let mut reissued = cookie.clone();
+                defaults.apply(&mut reissued);
+                Cookies::set_signed_defaults(&mut reissued);
+                jar.signed(key).add(reissued);
+                Some(cookie)
+            }
+            Cookies::Empty(_) => None
+        }
+    }
+
+    /// Adds `cookie` to the collection. The cookie's value is signed assuring
+    /// integrity and authenticity but _not_ confidentiality: the value remains
+    /// readable by the client, including by client-side JavaScript, but cannot
+    /// be tampered with or manufactured. The cookie can later be retrieved
+    /// using [`get_signed`](#method.get_signed) and removed using
+    /// [`remove_signed`](#method.remove_signed).
+    ///
+    /// Unless a value is supplied for the given key, the following defaults are
+    /// set on `cookie` before being added to `self`:
+    ///
+    ///    * `path`: `"/"`
+    ///    * `SameSite`: `Strict`
+    ///    * `Expires`: 1 week from now, unless `Max-Age` is set
+    ///
+    /// Unlike with [`add_private`](#method.add_private), `HttpOnly` is not set
+    /// by default so that scripts may read the cookie's value. Defaults from the
+    /// `cookies` configuration parameter take precedence over these defaults;
+    /// see [`CookieDefaults`].
+    ///
+    /// This method is only available when the `private-cookies` feature is
+    /// enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{Cookie, Cookies};
+    ///
+    /// fn handler(mut cookies: Cookies) {
+    ///     cookies.add_signed(Cookie::new("name", "value"));
+    /// }
+    /// ```
+    pub fn add_signed(&mut self, mut cookie: Cookie<'static>) {
+        if let Cookies::Jarred(ref mut jar, key, _, defaults) = *self {
+            defaults.apply(&mut cookie);
+            Cookies::set_signed_defaults(&mut cookie);
+            jar.signed(key).add(cookie)
+        }
+    }
+
+    /// Adds an original, signed `cookie` to the collection.
+    /// WARNING: This is unstable! Do not use this method outside of Rocket!
+    #[doc(hidden)]
+    pub fn add_original_signed(&mut self, mut cookie: Cookie<'static>) {
+        if let Cookies::Jarred(ref mut jar, key, ..) = *self {
+            Cookies::set_signed_defaults(&mut cookie);
+            jar.signed(key).add_original(cookie)
+        }
+    }
+
+    /// For each property mentioned below, this method checks
+    /// if there is a provided value and if there is none, sets
+    /// a default value.
+    /// Default values are:
+    ///
+    ///    * `path`: `"/"`
+    ///    * `SameSite`: `Strict`
+    ///    * `Expires`: 1 week from now, unless `Max-Age` is set
+    ///
+    fn set_signed_defaults(cookie: &mut Cookie<'static>) {
+        if cookie.path().is_none() {
+            cookie.set_path("/");
+        }
+
+        if cookie.expires().is_none() && cookie.max_age().is_none() {
+            cookie.set_expires(time::OffsetDateTime::now() + time::Duration::weeks(1));
+        }
+
+        if cookie.same_site().is_none() {
+            cookie.set_same_site(SameSite::Strict);
+        }
+    }
+
+    /// Removes the signed `cookie` from the collection.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set. If a path is not set
+    /// on `cookie`, the `"/"` path will automatically be set.
+    ///
+    /// This method is only available when the `private-cookies` feature is
+    /// enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{Cookie, Cookies};
+    ///
+    /// fn handler(mut cookies: Cookies) {
+    ///     cookies.remove_signed(Cookie::named("name"));
+    /// }
+    /// ```
+    pub fn remove_signed(&mut self, mut cookie: Cookie<'static>) {
+        if let Cookies::Jarred(ref mut jar, key, _, defaults) = *self {
+            CookiePrefix::fill(&mut cookie);
+            defaults.apply_location(&mut cookie);
+            if cookie.path().is_none() {
+                cookie.set_path("/");
+            }
+
+            jar.signed(key).remove(cookie)
+        }
+    }
+}
+
+/// Default attributes for cookies added to [`Cookies`].
+///
+/// A `CookieDefaults` is typically configured via the `cookies` configuration
+/// parameter. When a cookie is added with [`Cookies::add()`],
+/// [`Cookies::add_private()`], or [`Cookies::add_signed()`], each attribute
+/// that is unset in the cookie but set in the defaults is set to the default
+/// value. Attributes that are set in the cookie are never changed. The defaults
+/// for private and signed cookies described in their respective methods are
+/// applied only after the configured defaults.
+///
+/// Cookies whose names begin with a [`CookiePrefix`] are treated specially:
+/// `Secure` is always set on them, unless it is set explicitly, and a
+/// `__Host-` cookie never receives the default `Path` or `Domain`.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::{CookieDefaults, SameSite};
+///
+/// # #[allow(unused_variables)]
+/// let defaults = CookieDefaults::new()
+///     .same_site(SameSite::Lax)
+///     .secure(true)
+///     .path("/");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CookieDefaults {
+    same_site: Option<SameSite>,
+    secure: Option<bool>,
+    http_only: Option<bool>,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<time::Duration>,
+}
+
+impl CookieDefaults {
+    /// Returns a new `CookieDefaults` which sets no attributes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::CookieDefaults;
+    ///
+    /// let defaults = CookieDefaults::new();
+    /// assert!(defaults.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn new() -> CookieDefaults {
+        CookieDefaults::default()
+    }
+
+    /// Sets the default `SameSite` attribute to `same_site`.
+    #[inline]
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Sets the default `Secure` attribute to `secure`.
+    #[inline]
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = Some(secure);
+        self
+    }
+
+    /// Sets the default `HttpOnly` attribute to `http_only`.
+    #[inline]
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = Some(http_only);
+        self
+    }
+
+    /// Sets the default `Path` attribute to `path`.
+    #[inline]
+    pub fn path<P: Into<String>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the default `Domain` attribute to `domain`.
+    #[inline]
+    pub fn domain<D: Into<String>>(mut self, domain: D) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the default `Max-Age` attribute to `max_age`.
+    #[inline]
+    pub fn max_age(mut self, max_age: time::Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns `true` if `self` sets no attributes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::CookieDefaults;
+    ///
+    /// assert!(CookieDefaults::new().is_empty());
+    /// assert!(!CookieDefaults::new().secure(true).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        *self == CookieDefaults::default()
+    }
+
+    /// Sets every attribute of `cookie` that is unset to its default, if any,
+    /// and to the value required by the cookie's prefix, if it has one.
+    fn apply(&self, cookie: &mut Cookie<'static>) {
+        CookiePrefix::fill(cookie);
+
+        if let (None, Some(same_site)) = (cookie.same_site(), self.same_site) {
+            cookie.set_same_site(same_site);
+        }
+
+        if let (None, Some(secure)) = (cookie.secure(), self.secure) {
+            cookie.set_secure(secure);
+        }
+
+        if let (None, Some(http_only)) = (cookie.http_only(), self.http_only) {
+            cookie.set_http_only(http_only);
+        }
+
+        if let (None, Some(max_age)) = (cookie.max_age(), self.max_age) {
+            cookie.set_max_age(max_age);
+        }
+
+        self.apply_location(cookie);
+    }
+
+    /// Sets the `Path` and `Domain` of `cookie`, if unset, to their defaults.
+    /// These are the attributes that identify a cookie on removal.
+    fn apply_location(&self, cookie: &mut Cookie<'static>) {
+        if CookiePrefix::of(cookie.name()) == Some(CookiePrefix::Host) {
+            return;
+        }
+
+        if let (None, Some(path)) = (cookie.path(), self.path.as_ref()) {
+            cookie.set_path(path.clone());
+        }
+
+        if let (None, Some(domain)) = (cookie.domain(), self.domain.as_ref()) {
+            cookie.set_domain(domain.clone());
+        }
+    }
+}
+
+impl fmt::Display for CookieDefaults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut attrs = vec![];
+        if let Some(same_site) = self.same_site {
+            attrs.push(format!("SameSite={}", same_site));
+        }
+
+        if let Some(secure) = self.secure {
+            attrs.push(format!("Secure={}", secure));
+        }
+
+        if let Some(http_only) = self.http_only {
+            attrs.push(format!("HttpOnly={}", http_only));
+        }
+
+        if let Some(ref path) = self.path {
+            attrs.push(format!("Path={}", path));
+        }
+
+        if let Some(ref domain) = self.domain {
+            attrs.push(format!("Domain={}", domain));
+        }
+
+        if let Some(max_age) = self.max_age {
+            attrs.push(format!("Max-Age={}", max_age.whole_seconds()));
+        }
+
+        if attrs.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", attrs.join(", "))
+        }
+    }
+}
+
+/// A cookie name prefix with special meaning to user agents.
+///
+/// User agents only accept a cookie whose name begins with one of these
+/// prefixes if the cookie satisfies the prefix's rules:
+///
+///   * `__Secure-`: the cookie must have the `Secure` attribute.
+///   * `__Host-`: the cookie must have the `Secure` attribute, a `Path` of
+///     `"/"`, and no `Domain` attribute.
+///
+/// When a prefixed cookie is added to [`Cookies`] with an attribute unset,
+/// the attribute is set as the prefix requires. Before a response is sent,
+/// Rocket additionally enforces the rules on every cookie being set,
+/// correcting and logging a warning for any violation.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::{Cookie, CookiePrefix};
+///
+/// assert_eq!(CookiePrefix::of("__Host-id"), Some(CookiePrefix::Host));
+/// assert_eq!(CookiePrefix::of("__Secure-id"), Some(CookiePrefix::Secure));
+/// assert_eq!(CookiePrefix::of("id"), None);
+///
+/// let mut cookie = Cookie::build("__Host-id", "1").domain("rocket.rs").finish();
+/// let violations = CookiePrefix::Host.enforce(&mut cookie);
+/// assert_eq!(violations.len(), 3);
+/// assert_eq!(cookie.secure(), Some(true));
+/// assert_eq!(cookie.path(), Some("/"));
+/// assert_eq!(cookie.domain(), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CookiePrefix {
+    /// The `__Secure-` prefix.
+    Secure,
+    /// The `__Host-` prefix.
+    Host,
+}
+
+impl CookiePrefix {
+    /// Returns the prefix that `name` begins with, if any. Prefixes are matched
+    /// case-insensitively.
+    pub fn of(name: &str) -> Option<CookiePrefix> {
+        let has_prefix = |prefix: CookiePrefix| {
+            let (name, prefix) = (name.as_bytes(), prefix.as_str().as_bytes());
+            name.len() >= prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+        };
+
+        if has_prefix(CookiePrefix::Secure) {
+            Some(CookiePrefix::Secure)
+        } else if has_prefix(CookiePrefix::Host) {
+            Some(CookiePrefix::Host)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the prefix as a string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::CookiePrefix;
+    ///
+    /// assert_eq!(CookiePrefix::Host.as_str(), "__Host-");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CookiePrefix::Secure => "__Secure-",
+            CookiePrefix::Host => "__Host-",
+        }
+    }
+
+    /// Modifies `cookie` to satisfy the rules of `self`, returning a
+    /// description of each rule that `cookie` violated before modification.
+    pub fn enforce(self, cookie: &mut Cookie<'_>) -> Vec<&'static str> {
+        let mut violations = vec![];
+        if cookie.secure() != Some(true) {
+            cookie.set_secure(true);
+            violations.push("the `Secure` attribute must be set");
+        }
+
+        if self == CookiePrefix::Host {
+            if cookie.path() != Some("/") {
+                cookie.set_path("/");
+                violations.push("the `Path` attribute must be \"/\"");
+            }
+
+            if cookie.domain().is_some() {
+                cookie.unset_domain();
+                violations.push("the `Domain` attribute must not be set");
+            }
+        }
+
+        violations
+    }
+
+    /// Sets the attributes of `cookie` required by its prefix, if it has one,
+    /// that are unset.
+    fn fill(cookie: &mut Cookie<'_>) {
+        let prefix = match CookiePrefix::of(cookie.name()) {
+            Some(prefix) => prefix,
+            None => return
+        };
+
+        if cookie.secure().is_none() {
+            cookie.set_secure(true);
+        }
+
+        if prefix == CookiePrefix::Host && cookie.path().is_none() {
+            cookie.set_path("/");
+        }
+    }
+}
+
+impl fmt::Display for CookiePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 impl fmt::Debug for Cookies<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            Cookies::Jarred(ref jar, _) => jar.fmt(f),
+            Cookies::Jarred(ref jar, ..) => jar.fmt(f),
             Cookies::Empty(ref jar) => jar.fmt(f)
         }
     }
@@ -0,0 +1,399 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::fmt;
+
+use crate::Header;
+
+/// An HTTP entity tag, as found in the `ETag`, `If-Match`, and `If-None-Match`
+/// headers.
+///
+/// An entity tag is an opaque string, the _tag_, that identifies a particular
+/// representation of a resource, along with a flag indicating whether the tag
+/// is _weak_. A strong tag changes whenever the representation's bytes do; a
+/// weak tag may be shared by semantically equivalent representations. As per
+/// [RFC 7232 2.3.2], tags can be compared in two ways:
+///
+///   * **strong comparison**: both tags are strong and their tags are equal.
+///     Used by `If-Match` and `If-Range`.
+///   * **weak comparison**: the tags are equal, whether or not either is weak.
+///     Used by `If-None-Match`.
+///
+/// [RFC 7232 2.3.2]: https://tools.ietf.org/html/rfc7232#section-2.3.2
+///
+/// # Usage
+///
+/// An `EntityTag` is parsed from or rendered to its HTTP form, `"tag"` or
+/// `W/"tag"`, via [`FromStr`] and [`Display`](fmt::Display). It converts into
+/// an `ETag` [`Header`]:
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::EntityTag;
+///
+/// let strong = EntityTag::strong("v1");
+/// let weak: EntityTag = "W/\"v1\"".parse().unwrap();
+/// assert_eq!(strong.to_string(), "\"v1\"");
+/// assert_eq!(weak.to_string(), "W/\"v1\"");
+///
+/// assert!(strong.weak_eq(&weak));
+/// assert!(!strong.strong_eq(&weak));
+/// assert!(strong.strong_eq(&EntityTag::strong("v1")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityTag {
+    weak: bool,
+    tag: Cow<'static, str>,
+}
+
+/// Returns `true` if `c` is allowed in the tag of an entity tag.
+#[inline]
+fn is_etag_char(c: char) -> bool {
+    c == '!' || (c >= '#' && c <= '~') || (c as u32) >= 0x80
+}
+
+impl EntityTag {
+    /// Constructs a strong entity tag with the opaque tag `tag`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` contains a `"`, whitespace, or an ASCII control
+    /// character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::EntityTag;
+    ///
+    /// let tag = EntityTag::strong("abc");
+    /// assert!(!tag.is_weak());
+    /// assert_eq!(tag.tag(), "abc");
+    /// ```
+    #[inline]
+    pub fn strong<T: Into<Cow<'static, str>>>(tag: T) -> EntityTag {
+        EntityTag::new(false, tag)
+    }
+
+    /// Constructs a weak entity tag with the opaque tag `tag`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` contains a `"`, whitespace, or an ASCII control
+    /// character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::EntityTag;
+    ///
+    /// let tag = EntityTag::weak("abc");
+    /// assert!(tag.is_weak());
+    /// assert_eq!(tag.tag(), "abc");
+    /// ```
+    #[inline]
+    pub fn weak<T: Into<Cow<'static, str>>>(tag: T) -> EntityTag {
+        EntityTag::new(true, tag)
+    }
+
+    fn new<T: Into<Cow<'static, str>>>(weak: bool, tag: T) -> EntityTag {
+        let tag = tag.into();
+        if !tag.chars().all(is_etag_char) {
+            panic!("invalid entity tag: {:?}", tag);
+        }
+
+        EntityTag { weak, tag }
+    }
+
+    /// Returns `true` if `self` is a weak entity tag.
+    #[inline(always)]
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Returns the opaque tag of `self`, without quotes or weakness indicator.
+    #[inline(always)]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns `true` if `self` and `other` are equal under the strong
+    /// comparison function: both are strong and their tags are equal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::EntityTag;
+    ///
+    /// assert!(EntityTag::strong("a").strong_eq(&EntityTag::strong("a")));
+    /// assert!(!EntityTag::strong("a").strong_eq(&EntityTag::weak("a")));
+    /// assert!(!EntityTag::weak("a").strong_eq(&EntityTag::weak("a")));
+    /// ```
+    #[inline]
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Returns `true` if `self` and `other` are equal under the weak
+    /// comparison function: their tags are equal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::EntityTag;
+    ///
+    /// assert!(EntityTag::strong("a").weak_eq(&EntityTag::weak("a")));
+    /// assert!(!EntityTag::weak("a").weak_eq(&EntityTag::weak("b")));
+    /// ```
+    #[inline]
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.weak {
+            true => write!(f, "W/\"{}\"", self.tag),
+            false => write!(f, "\"{}\"", self.tag),
+        }
+    }
+}
+
+impl FromStr for EntityTag {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<EntityTag, String> {
+        let raw = raw.trim();
+        let (weak, quoted) = match raw.starts_with("W/") {
+            true => (true, &raw[2..]),
+            false => (false, raw),
+        };
+
+        if quoted.len() < 2 || !quoted.starts_with('"') || !quoted.ends_with('"') {
+            return Err(format!("entity tag '{}' is not quoted", raw));
+        }
+
+        let tag = &quoted[1..(quoted.len() - 1)];
+        if !tag.chars().all(is_etag_char) {
+            return Err(format!("invalid entity tag '{}'", raw));
+        }
+
+        Ok(EntityTag { weak, tag: Cow::Owned(tag.to_string()) })
+    }
+}
+
+/// Creates a new `ETag` header with the value set to the HTTP rendering of
+/// this entity tag.
+impl Into<Header<'static>> for EntityTag {
+    #[inline(always)]
+    fn into(self) -> Header<'static> {
+        Header::new("ETag", self.to_string())
+    }
+}
+
+/// Parses the value of an `If-Match` or `If-None-Match` header: either `*`,
+/// returned as `None`, or a comma-separated list of entity tags.
+fn parse_condition(raw: &str) -> Result<Option<Vec<EntityTag>>, String> {
+    if raw.trim() == "*" {
+        return Ok(None);
+    }
+
+    let tags = raw.split(',')
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.parse())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match tags.is_empty() {
+        true => Err("expected '*' or at least one entity tag".into()),
+        false => Ok(Some(tags))
+    }
+}
+
+macro_rules! condition_header {
+    ($(#[$attr:meta])* $name:ident, $header:expr, $eq:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $name(Option<Vec<EntityTag>>);
+
+        impl $name {
+            /// Constructs a header that matches any current representation:
+            /// `*`.
+            #[inline(always)]
+            pub fn any() -> $name {
+                $name(None)
+            }
+
+            /// Constructs a header listing the entity tags `tags`.
+            #[inline(always)]
+            pub fn new(tags: Vec<EntityTag>) -> $name {
+                $name(Some(tags))
+            }
+
+            /// Returns `true` if `self` is `*`.
+            #[inline(always)]
+            pub fn is_any(&self) -> bool {
+                self.0.is_none()
+            }
+
+            /// Returns an iterator over the entity tags in `self` in the order
+            /// in which they appear in the header. If `self` is `*`, the
+            /// iterator is empty.
+            #[inline(always)]
+            pub fn tags<'a>(&'a self) -> impl Iterator<Item=&'a EntityTag> + 'a {
+                self.0.iter().flat_map(|tags| tags.iter())
+            }
+
+            /// Returns `true` if the header matches the current representation
+            /// of the resource, whose entity tag is `current`. `None` indicates
+            /// that there is no current representation, which `*` doesn't
+            /// match.
+            pub fn matches(&self, current: Option<&EntityTag>) -> bool {
+                match (&self.0, current) {
+                    (_, None) => false,
+                    (None, Some(_)) => true,
+                    (Some(tags), Some(current)) => tags.iter().any(|tag| tag.$eq(current)),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    None => write!(f, "*"),
+                    Some(ref tags) => {
+                        for (i, tag) in tags.iter().enumerate() {
+                            if i >= 1 {
+                                write!(f, ", {}", tag)?;
+                            } else {
+                                write!(f, "{}", tag)?;
+                            }
+                        }
+
+                        Ok(())
+                    }
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            #[inline]
+            fn from_str(raw: &str) -> Result<$name, String> {
+                parse_condition(raw).map($name)
+            }
+        }
+
+        /// Creates a new `Header` with the name of this header and the value
+        /// set to the HTTP rendering of this header.
+        impl Into<Header<'static>> for $name {
+            #[inline(always)]
+            fn into(self) -> Header<'static> {
+                Header::new($header, self.to_string())
+            }
+        }
+    }
+}
+
+condition_header! {
+    /// The HTTP If-Match header.
+    ///
+    /// An `If-Match` header is either `*` or a list of entity tags. It makes a
+    /// request conditional on the current representation of the resource
+    /// matching one of the tags under the _strong_ comparison function or, for
+    /// `*`, on any current representation existing. It is typically used to
+    /// prevent lost updates: a `PUT`, `PATCH`, or `DELETE` whose `If-Match`
+    /// doesn't match should fail with a `412 Precondition Failed`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{EntityTag, IfMatch};
+    ///
+    /// let current = EntityTag::strong("v2");
+    ///
+    /// let if_match: IfMatch = "\"v1\", \"v2\"".parse().unwrap();
+    /// assert!(if_match.matches(Some(&current)));
+    ///
+    /// let if_match: IfMatch = "W/\"v2\"".parse().unwrap();
+    /// assert!(!if_match.matches(Some(&current)));
+    ///
+    /// let if_match: IfMatch = "*".parse().unwrap();
+    /// assert!(if_match.matches(Some(&current)));
+    /// assert!(!if_match.matches(None));
+    /// ```
+    IfMatch, "If-Match", strong_eq
+}
+
+condition_header! {
+    /// The HTTP If-None-Match header.
+    ///
+    /// An `If-None-Match` header is either `*` or a list of entity tags. It
+    /// makes a request conditional on the current representation of the
+    /// resource matching _none_ of the tags under the _weak_ comparison
+    /// function or, for `*`, on no current representation existing. A `GET` or
+    /// `HEAD` whose `If-None-Match` matches is answered with a `304 Not
+    /// Modified`; any other method fails with a `412 Precondition Failed`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use rocket::http::{EntityTag, IfNoneMatch};
+    ///
+    /// let current = EntityTag::strong("v2");
+    ///
+    /// let if_none_match: IfNoneMatch = "W/\"v2\"".parse().unwrap();
+    /// assert!(if_none_match.matches(Some(&current)));
+    ///
+    /// let if_none_match: IfNoneMatch = "\"v1\"".parse().unwrap();
+    /// assert!(!if_none_match.matches(Some(&current)));
+    /// ```
+    IfNoneMatch, "If-None-Match", weak_eq
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntityTag, IfMatch, IfNoneMatch};
+
+    #[test]
+    fn test_parse_entity_tag() {
+        assert_eq!("\"abc\"".parse(), Ok(EntityTag::strong("abc")));
+        assert_eq!(" W/\"abc\" ".parse(), Ok(EntityTag::weak("abc")));
+        assert_eq!("\"\"".parse(), Ok(EntityTag::strong("")));
+
+        assert!("abc".parse::<EntityTag>().is_err());
+        assert!("\"abc".parse::<EntityTag>().is_err());
+        assert!("w/\"abc\"".parse::<EntityTag>().is_err());
+        assert!("\"a\"b\"".parse::<EntityTag>().is_err());
+        assert!("\"a b\"".parse::<EntityTag>().is_err());
+        assert!("\"".parse::<EntityTag>().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_entity_tag() {
+        EntityTag::strong("a\"b");
+    }
+
+    #[test]
+    fn test_parse_conditions() {
+        assert_eq!("*".parse(), Ok(IfMatch::any()));
+        assert_eq!("\"a\", W/\"b\"".parse(),
+            Ok(IfNoneMatch::new(vec![EntityTag::strong("a"), EntityTag::weak("b")])));
+        assert_eq!("\"a\",, \"b\"".parse::<IfMatch>().map(|h| h.tags().count()), Ok(2));
+
+        assert!("".parse::<IfMatch>().is_err());
+        assert!("*, \"a\"".parse::<IfMatch>().is_err());
+        assert!("a, b".parse::<IfNoneMatch>().is_err());
+
+        let header = IfNoneMatch::new(vec![EntityTag::strong("a"), EntityTag::weak("b")]);
+        assert_eq!(header.to_string(), "\"a\", W/\"b\"");
+        assert_eq!(IfMatch::any().to_string(), "*");
+    }
+}
@@ -16,6 +16,7 @@
 pub mod hyper;
 pub mod uri;
 pub mod ext;
+pub mod size;
 
 #[doc(hidden)]
 #[cfg(feature = "tls")]
@@ -35,6 +36,9 @@ mod content_type;
 mod status;
 mod header;
 mod accept;
+mod accept_token;
+mod entity_tag;
+mod cache_control;
 mod raw_str;
 mod parse;
 
@@ -56,9 +60,12 @@ pub mod private {
 pub use crate::method::Method;
 pub use crate::content_type::ContentType;
 pub use crate::accept::{Accept, QMediaType};
+pub use crate::accept_token::{QToken, AcceptLanguage, AcceptEncoding, AcceptCharset};
+pub use crate::entity_tag::{EntityTag, IfMatch, IfNoneMatch};
+pub use crate::cache_control::CacheControl;
 pub use crate::status::{Status, StatusClass};
 pub use crate::header::{Header, HeaderMap};
 pub use crate::raw_str::RawStr;
 
 pub use crate::media_type::MediaType;
-pub use crate::cookies::{Cookie, SameSite, Cookies};
+pub use crate::cookies::{Cookie, SameSite, Cookies, CookieDefaults, CookiePrefix};
@@ -0,0 +1,103 @@
+//! Parsing of human-readable byte sizes.
+
+/// Parses a human-readable byte size such as `"64 KiB"`, `"2MiB"`, `"1.5 MB"`,
+/// or `"1024"` into a number of bytes.
+///
+/// A size is a non-negative number, optionally with a fractional part,
+/// followed by an optional unit. Whitespace is allowed around the number and
+/// the unit. Units are case-insensitive. Decimal units (`kB`, `MB`, `GB`, `TB`)
+/// are powers of 1000 while binary units (`KiB`, `MiB`, `GiB`, `TiB`) are
+/// powers of 1024. `B` and the absence of a unit both mean bytes. Fractional
+/// byte counts are rounded down.
+///
+/// Returns an error describing the problem if `string` is not a valid size
+/// or if the size does not fit in a `u64`.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::size::parse_size;
+///
+/// assert_eq!(parse_size("512"), Ok(512));
+/// assert_eq!(parse_size("64 KiB"), Ok(64 * 1024));
+/// assert_eq!(parse_size("2mib"), Ok(2 * 1024 * 1024));
+/// assert_eq!(parse_size("1.5 kB"), Ok(1500));
+/// assert!(parse_size("2 MiBs").is_err());
+/// ```
+pub fn parse_size(string: &str) -> Result<u64, &'static str> {
+    let string = string.trim();
+    let split = string.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(string.len());
+
+    let (number, unit) = (&string[..split], string[split..].trim_start());
+    let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "kib" => 1 << 10,
+        "mb" => 1_000_000,
+        "mib" => 1 << 20,
+        "gb" => 1_000_000_000,
+        "gib" => 1 << 30,
+        "tb" => 1_000_000_000_000,
+        "tib" => 1 << 40,
+        _ => return Err("unknown unit: expected one of B, kB, KiB, MB, MiB, GB, GiB, TB, TiB"),
+    };
+
+    let (whole, fraction) = match number.find('.') {
+        Some(i) => (&number[..i], &number[(i + 1)..]),
+        None => (number, ""),
+    };
+
+    if whole.is_empty() || fraction.contains('.') || number.ends_with('.') {
+        return Err("expected a number of bytes, optionally followed by a unit");
+    }
+
+    let overflow = "size is too large";
+    let whole: u64 = whole.parse().map_err(|_| overflow)?;
+    let mut bytes = whole.checked_mul(multiplier).ok_or(overflow)?;
+
+    // Each fractional digit contributes `digit * multiplier / 10^n` bytes.
+    let mut scale = multiplier;
+    for digit in fraction.bytes().map(|b| u64::from(b - b'0')) {
+        scale /= 10;
+        if scale == 0 {
+            break;
+        }
+
+        bytes = bytes.checked_add(digit * scale).ok_or(overflow)?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_size;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size(" 17 "), Ok(17));
+        assert_eq!(parse_size("17B"), Ok(17));
+        assert_eq!(parse_size("1 kB"), Ok(1000));
+        assert_eq!(parse_size("1 KB"), Ok(1000));
+        assert_eq!(parse_size("64 KiB"), Ok(64 * 1024));
+        assert_eq!(parse_size("2 MiB"), Ok(2 << 20));
+        assert_eq!(parse_size("3gib"), Ok(3 << 30));
+        assert_eq!(parse_size("1 TiB"), Ok(1 << 40));
+        assert_eq!(parse_size("1.5 KiB"), Ok(1536));
+        assert_eq!(parse_size("0.5 MB"), Ok(500_000));
+        assert_eq!(parse_size("1.25 B"), Ok(1));
+
+        assert!(parse_size("").is_err());
+        assert!(parse_size("KiB").is_err());
+        assert!(parse_size(".5 KiB").is_err());
+        assert!(parse_size("5. KiB").is_err());
+        assert!(parse_size("1.2.3").is_err());
+        assert!(parse_size("-1").is_err());
+        assert!(parse_size("10 kibibytes").is_err());
+        assert!(parse_size("99999999999 TiB").is_err());
+        assert!(parse_size("99999999999999999999").is_err());
+    }
+}
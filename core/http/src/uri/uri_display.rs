@@ -1,5 +1,6 @@
 use std::{fmt, path};
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 use crate::RawStr;
 use crate::uri::{Uri, UriPart, Path, Query, Formatter};
@@ -424,6 +425,35 @@ impl<T: UriDisplay<Query>, E> UriDisplay<Query> for Result<T, E> {
     }
 }
 
+/// Writes each element as a named value whose name is the element's index.
+///
+/// For example, the elements of `vec!["a", "b"]` in a field named `tags` are
+/// written as `tags.0=a&tags.1=b`, which parses back into an equal vector.
+impl<T: UriDisplay<Query>> UriDisplay<Query> for Vec<T> {
+    fn fmt(&self, f: &mut Formatter<'_, Query>) -> fmt::Result {
+        for (i, value) in self.iter().enumerate() {
+            f.write_named_value(&i.to_string(), value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes each value as a named value whose name is the entry's key.
+///
+/// Keys are percent-encoded, including any `.`, so that they are not mistaken
+/// for nested names.
+impl<K: UriDisplay<Query>, V: UriDisplay<Query>> UriDisplay<Query> for HashMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_, Query>) -> fmt::Result {
+        for (key, value) in self.iter() {
+            let key = format!("{}", key as &dyn UriDisplay<Query>).replace('.', "%2E");
+            f.write_named_value(&key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
 // And finally, the `Ignorable` trait, which has sugar of `_` in the `uri!`
 // macro, which expands to a typecheck.
 
@@ -19,6 +19,8 @@ pub struct StaticRouteInfo {
     pub handler: StaticHandler,
     /// The route's rank, if any.
     pub rank: Option<isize>,
+    /// The route's request body limit in bytes, if any.
+    pub limit: Option<u64>,
 }
 
 /// Information generated by the `catch` attribute during codegen.
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use crate::config::{Result, Config, Value, Environment, Limits, LoggingLevel};
+use crate::config::{Result, Config, Value, Environment, LoggingLevel};
+use crate::config::{Limits, Timeouts, Validators, CacheDefaults};
+use crate::http::CookieDefaults;
 
 /// Structure following the builder pattern for building `Config` structures.
 #[derive(Clone)]
@@ -16,14 +18,26 @@ pub struct ConfigBuilder {
     pub workers: u16,
     /// Keep-alive timeout in seconds or disabled if 0.
     pub keep_alive: u32,
+    /// The maximum number of concurrent event streams, if set.
+    pub event_streams: Option<u16>,
     /// How much information to log.
     pub log_level: LoggingLevel,
     /// The secret key.
     pub secret_key: Option<String>,
+    /// The retired secret keys.
+    pub retired_secret_keys: Vec<String>,
     /// TLS configuration (path to certificates file, path to private key file).
     pub tls: Option<(String, String)>,
     /// Size limits.
     pub limits: Limits,
+    /// Client read timeouts.
+    pub timeouts: Timeouts,
+    /// Validators attached to file responses.
+    pub validators: Validators,
+    /// Default `Cache-Control` policies by path prefix.
+    pub cache: CacheDefaults,
+    /// Default cookie attributes.
+    pub cookies: CookieDefaults,
     /// Any extra parameters that aren't part of Rocket's config.
     pub extras: HashMap<String, Value>,
     /// The root directory of this config, if any.
@@ -57,10 +71,16 @@ impl ConfigBuilder {
             port: config.port,
             workers: config.workers,
             keep_alive: config.keep_alive.unwrap_or(0),
+            event_streams: config.event_streams,
             log_level: config.log_level,
             secret_key: None,
+            retired_secret_keys: vec![],
             tls: None,
             limits: config.limits,
+            timeouts: config.timeouts,
+            validators: config.validators,
+            cache: config.cache,
+            cookies: config.cookies,
             extras: config.extras,
             root: None,
         }
@@ -148,6 +168,26 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the maximum number of concurrent event streams in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .event_streams(8)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.event_streams, Some(8));
+    /// ```
+    #[inline]
+    pub fn event_streams(mut self, streams: u16) -> Self {
+        self.event_streams = Some(streams);
+        self
+    }
+
     /// Sets the `log_level` in the configuration being built.
     ///
     /// # Example
@@ -184,6 +224,29 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the `secret_key` in the configuration being built to `primary` and
+    /// the retired secret keys to `retired`. See
+    /// [`Config::set_secret_keys()`] for details on key rotation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let new = "8Xui8SN4mI+7egV/9dlfYYLGQJeEx4+DwmSQLwDVXJg=";
+    /// let old = "fe4c5b09a9ac372156e44ce133bc940685ef5e0394d6e9274aadacc21e4f2643";
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .secret_keys(new, vec![old])
+    ///     .unwrap();
+    /// ```
+    pub fn secret_keys<K, R, I>(mut self, primary: K, retired: I) -> Self
+        where K: Into<String>, R: Into<String>, I: IntoIterator<Item = R>
+    {
+        self.secret_key = Some(primary.into());
+        self.retired_secret_keys = retired.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Sets the `limits` in the configuration being built.
     ///
     /// # Example
@@ -200,6 +263,74 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the client read `timeouts` in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, Timeouts};
+    ///
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .timeouts(Timeouts { min_rate: Some(512), ..Timeouts::default() })
+    ///     .unwrap();
+    /// ```
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sets the `validators` attached to file responses in the configuration
+    /// being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, Validators};
+    ///
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .validators(Validators { etag: false, last_modified: true })
+    ///     .unwrap();
+    /// ```
+    pub fn validators(mut self, validators: Validators) -> Self {
+        self.validators = validators;
+        self
+    }
+
+    /// Sets the default `Cache-Control` policies, `cache`, in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, CacheDefaults};
+    /// use rocket::http::CacheControl;
+    ///
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .cache(CacheDefaults::new().policy("/api", CacheControl::new().no_store()))
+    ///     .unwrap();
+    /// ```
+    pub fn cache(mut self, cache: CacheDefaults) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Sets the default `cookies` attributes in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    /// use rocket::http::{CookieDefaults, SameSite};
+    ///
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .cookies(CookieDefaults::new().same_site(SameSite::Lax))
+    ///     .unwrap();
+    /// ```
+    pub fn cookies(mut self, cookies: CookieDefaults) -> Self {
+        self.cookies = cookies;
+        self
+    }
+
     /// Sets the TLS configuration in the configuration being built.
     ///
     /// Certificates are read from `certs_path`. The certificate chain must be
@@ -321,6 +452,14 @@ impl ConfigBuilder {
         config.set_log_level(self.log_level);
         config.set_extras(self.extras);
         config.set_limits(self.limits);
+        config.set_timeouts(self.timeouts);
+        config.set_validators(self.validators);
+        config.set_cache(self.cache);
+        config.set_cookies(self.cookies);
+
+        if let Some(streams) = self.event_streams {
+            config.set_event_streams(streams);
+        }
 
         if let Some(root) = self.root {
             config.set_root(root);
@@ -331,7 +470,11 @@ impl ConfigBuilder {
         }
 
         if let Some(key) = self.secret_key {
-            config.set_secret_key(key)?;
+            if self.retired_secret_keys.is_empty() {
+                config.set_secret_key(key)?;
+            } else {
+                config.set_secret_keys(key, self.retired_secret_keys)?;
+            }
         }
 
         Ok(config)
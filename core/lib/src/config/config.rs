@@ -8,6 +8,7 @@ use crate::config::Environment::*;
 use crate::config::{Result, ConfigBuilder, Environment, ConfigError, LoggingLevel};
 use crate::config::{Table, Value, Array, Datetime};
 use crate::http::private::Key;
+use crate::http::CookieDefaults;
 
 use super::custom_values::*;
 
@@ -45,14 +46,27 @@ pub struct Config {
     pub workers: u16,
     /// Keep-alive timeout in seconds or None if disabled.
     pub keep_alive: Option<u32>,
+    /// The maximum number of concurrent event streams or None for half of the
+    /// workers.
+    pub event_streams: Option<u16>,
     /// How much information to log.
     pub log_level: LoggingLevel,
     /// The secret key.
     pub(crate) secret_key: SecretKey,
+    /// Retired secret keys, still accepted when reading cookies.
+    pub(crate) retired_secret_keys: Vec<Key>,
     /// TLS configuration.
     pub(crate) tls: Option<TlsConfig>,
     /// Streaming read size limits.
     pub limits: Limits,
+    /// Timeouts and rates for reading requests from clients.
+    pub timeouts: Timeouts,
+    /// The validators attached to file responses.
+    pub validators: Validators,
+    /// Default `Cache-Control` policies by path prefix.
+    pub cache: CacheDefaults,
+    /// Default attributes for cookies added by the application.
+    pub cookies: CookieDefaults,
     /// Extra parameters that aren't part of Rocket's core config.
     pub extras: HashMap<String, Value>,
     /// The path to the configuration file this config was loaded from, if any.
@@ -228,10 +242,16 @@ impl Config {
                     port: 8000,
                     workers: default_workers,
                     keep_alive: Some(5),
+                    event_streams: None,
                     log_level: LoggingLevel::Normal,
                     secret_key: key,
+                    retired_secret_keys: vec![],
                     tls: None,
                     limits: Limits::default(),
+                    timeouts: Timeouts::default(),
+                    validators: Validators::default(),
+                    cache: CacheDefaults::default(),
+                    cookies: CookieDefaults::default(),
                     extras: HashMap::new(),
                     config_file_path: None,
                     root_path: None,
@@ -244,10 +264,16 @@ impl Config {
                     port: 8000,
                     workers: default_workers,
                     keep_alive: Some(5),
+                    event_streams: None,
                     log_level: LoggingLevel::Normal,
                     secret_key: key,
+                    retired_secret_keys: vec![],
                     tls: None,
                     limits: Limits::default(),
+                    timeouts: Timeouts::default(),
+                    validators: Validators::default(),
+                    cache: CacheDefaults::default(),
+                    cookies: CookieDefaults::default(),
                     extras: HashMap::new(),
                     config_file_path: None,
                     root_path: None,
@@ -260,10 +286,16 @@ impl Config {
                     port: 8000,
                     workers: default_workers,
                     keep_alive: Some(5),
+                    event_streams: None,
                     log_level: LoggingLevel::Critical,
                     secret_key: key,
+                    retired_secret_keys: vec![],
                     tls: None,
                     limits: Limits::default(),
+                    timeouts: Timeouts::default(),
+                    validators: Validators::default(),
+                    cache: CacheDefaults::default(),
+                    cookies: CookieDefaults::default(),
                     extras: HashMap::new(),
                     config_file_path: None,
                     root_path: None,
@@ -296,9 +328,20 @@ impl Config {
     ///   * **port**: Integer (16-bit unsigned)
     ///   * **workers**: Integer (16-bit unsigned)
     ///   * **keep_alive**: Integer
+    ///   * **event_streams**: Integer (16-bit unsigned)
     ///   * **log**: String
     ///   * **secret_key**: String (256-bit base64 or base16)
+    ///   * **secret_keys**: Table (`primary` (String), `retired` (Array of
+    ///     String))
     ///   * **tls**: Table (`certs` (path as String), `key` (path as String))
+    ///   * **cookies**: Table (`same_site` (String), `secure` (Boolean),
+    ///     `http_only` (Boolean), `path` (String), `domain` (String), `max_age`
+    ///     (Integer, seconds))
+    ///   * **limits**: Table (data type to Integer bytes or String size)
+    ///   * **timeouts**: Table (`header`, `read`, `body` (Integer, seconds),
+    ///     `min_rate` (Integer bytes or String size))
+    ///   * **validators**: Table (`etag` (Boolean), `last_modified` (Boolean))
+    ///   * **cache**: Table (path prefix to String `Cache-Control` value)
     pub(crate) fn set_raw(&mut self, name: &str, val: &Value) -> Result<()> {
         let (id, ok) = (|val| val, |_| Ok(()));
         config_from_raw!(self, name, val,
@@ -306,10 +349,16 @@ impl Config {
             port => (u16, set_port, ok),
             workers => (u16, set_workers, ok),
             keep_alive => (u32, set_keep_alive, ok),
+            event_streams => (u16, set_event_streams, ok),
             log => (log_level, set_log_level, ok),
             secret_key => (str, set_secret_key, id),
+            secret_keys => (secret_keys, set_raw_secret_keys, id),
             tls => (tls_config, set_raw_tls, id),
             limits => (limits, set_limits, ok),
+            timeouts => (timeouts, set_timeouts, ok),
+            validators => (validators, set_validators, ok),
+            cache => (cache_defaults, set_cache, ok),
+            cookies => (cookie_defaults, set_cookies, ok),
             | _ => {
                 self.extras.insert(name.into(), val.clone());
                 Ok(())
@@ -421,6 +470,32 @@ impl Config {
         }
     }
 
+    /// Sets the maximum number of concurrent event streams to `streams`. If
+    /// `streams` is `0`, every event stream is refused.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// let mut config = Config::development();
+    /// config.set_event_streams(4);
+    /// assert_eq!(config.event_streams, Some(4));
+    /// ```
+    #[inline]
+    pub fn set_event_streams(&mut self, streams: u16) {
+        self.event_streams = Some(streams);
+    }
+
+    /// Returns the maximum number of concurrent event streams: the configured
+    /// `event_streams` or, if unset, half of the workers.
+    pub(crate) fn max_event_streams(&self) -> usize {
+        match self.event_streams {
+            Some(streams) => streams as usize,
+            None => std::cmp::max(1, self.workers as usize / 2),
+        }
+    }
+
     /// Sets the `secret_key` in `self` to `key` which must be a 256-bit base64
     /// or base16 (hex) encoded string.
     ///
@@ -448,8 +523,62 @@ impl Config {
     /// assert!(config.set_secret_key("hello? anyone there?").is_err());
     /// ```
     pub fn set_secret_key<K: Into<String>>(&mut self, key: K) -> Result<()> {
-        let key = key.into();
-        let e = self.bad_type("secret_key", "string", "a 256-bit base64 or hex encoded string");
+        let key = self.decode_secret_key("secret_key", &key.into())?;
+        self.secret_key = SecretKey::Provided(key);
+        Ok(())
+    }
+
+    /// Sets the `secret_key` in `self` to `primary` and the retired secret keys
+    /// to `retired`. Each key must be a 256-bit base64 or base16 (hex) encoded
+    /// string.
+    ///
+    /// The primary key is used to sign and encrypt all new cookies. Retired
+    /// keys are only used to read private and signed cookies that were created
+    /// with them; such cookies are transparently reissued under the primary
+    /// key. This allows a secret key to be rotated without invalidating every
+    /// existing cookie at once.
+    ///
+    /// # Errors
+    ///
+    /// If any key is not a valid 256-bit encoded string, returns a `BadType`
+    /// error and leaves `self` unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let mut config = Config::new(Environment::Staging);
+    ///
+    /// let new = "8Xui8SN4mI+7egV/9dlfYYLGQJeEx4+DwmSQLwDVXJg=";
+    /// let old = "fe4c5b09a9ac372156e44ce133bc940685ef5e0394d6e9274aadacc21e4f2643";
+    /// assert!(config.set_secret_keys(new, vec![old]).is_ok());
+    ///
+    /// // An invalid retired key.
+    /// assert!(config.set_secret_keys(new, vec!["hello? anyone there?"]).is_err());
+    /// ```
+    pub fn set_secret_keys<K, R, I>(&mut self, primary: K, retired: I) -> Result<()>
+        where K: Into<String>, R: Into<String>, I: IntoIterator<Item = R>
+    {
+        let primary = self.decode_secret_key("secret_keys.primary", &primary.into())?;
+        let retired = retired.into_iter()
+            .map(|key| self.decode_secret_key("secret_keys.retired", &key.into()))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.secret_key = SecretKey::Provided(primary);
+        self.retired_secret_keys = retired;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn set_raw_secret_keys(&mut self, keys: (&str, Vec<&str>)) -> Result<()> {
+        self.set_secret_keys(keys.0, keys.1)
+    }
+
+    /// Decodes the 256-bit base64 or base16 (hex) encoded `key` for the entry
+    /// `name` into a `Key`.
+    fn decode_secret_key(&self, name: &str, key: &str) -> Result<Key> {
+        let e = self.bad_type(name, "string", "a 256-bit base64 or hex encoded string");
 
         // `binascii` requires a bit more space than actual output for padding
         let mut bytes = [0u8; 36];
@@ -459,8 +588,7 @@ impl Config {
             _ => return Err(e)
         };
 
-        self.secret_key = SecretKey::Provided(Key::from_master(&bytes));
-        Ok(())
+        Ok(Key::from_master(&bytes))
     }
 
     /// Sets the logging level for `self` to `log_level`.
@@ -494,6 +622,69 @@ impl Config {
         self.limits = limits;
     }
 
+    /// Sets the client read timeouts in `self` to `timeouts`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Timeouts};
+    ///
+    /// let mut config = Config::development();
+    /// config.set_timeouts(Timeouts { body: Some(30), ..Timeouts::default() });
+    /// ```
+    #[inline]
+    pub fn set_timeouts(&mut self, timeouts: Timeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// Sets the validators attached to file responses in `self` to
+    /// `validators`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Validators};
+    ///
+    /// let mut config = Config::development();
+    /// config.set_validators(Validators { etag: false, last_modified: true });
+    /// ```
+    #[inline]
+    pub fn set_validators(&mut self, validators: Validators) {
+        self.validators = validators;
+    }
+
+    /// Sets the default `Cache-Control` policies in `self` to `cache`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, CacheDefaults};
+    /// use rocket::http::CacheControl;
+    ///
+    /// let mut config = Config::development();
+    /// config.set_cache(CacheDefaults::new().policy("/api", CacheControl::new().no_store()));
+    /// ```
+    #[inline]
+    pub fn set_cache(&mut self, cache: CacheDefaults) {
+        self.cache = cache;
+    }
+
+    /// Sets the default cookie attributes in `self` to `cookies`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    /// use rocket::http::{CookieDefaults, SameSite};
+    ///
+    /// let mut config = Config::development();
+    /// config.set_cookies(CookieDefaults::new().same_site(SameSite::Lax).secure(true));
+    /// ```
+    #[inline]
+    pub fn set_cookies(&mut self, cookies: CookieDefaults) {
+        self.cookies = cookies;
+    }
+
     /// Sets the TLS configuration in `self`.
     ///
     /// Certificates are read from `certs_path`. The certificate chain must be
@@ -625,6 +816,18 @@ impl Config {
         self.secret_key.inner()
     }
 
+    /// Retrieves the retired secret keys from `self`.
+    #[inline]
+    pub(crate) fn retired_secret_keys(&self) -> &[Key] {
+        &self.retired_secret_keys
+    }
+
+    /// Returns the fingerprints of the retired secret keys in `self`.
+    #[cfg(feature = "private-cookies")]
+    pub(crate) fn retired_key_fingerprints(&self) -> Vec<String> {
+        self.retired_secret_keys.iter().map(key_fingerprint).collect()
+    }
+
     /// Attempts to retrieve the extra named `name` as a raw value.
     ///
     /// # Errors
@@ -927,6 +1130,7 @@ impl fmt::Debug for Config {
         s.field("port", &self.port);
         s.field("workers", &self.workers);
         s.field("keep_alive", &self.keep_alive);
+        s.field("event_streams", &self.event_streams);
         s.field("log_level", &self.log_level);
 
         for (key, value) in self.extras() {
@@ -945,6 +1149,7 @@ impl PartialEq for Config {
             && self.workers == other.workers
             && self.log_level == other.log_level
             && self.keep_alive == other.keep_alive
+            && self.event_streams == other.event_streams
             && self.environment == other.environment
             && self.extras == other.extras
     }
@@ -3,6 +3,8 @@ use std::fmt;
 #[cfg(feature = "tls")] use crate::http::tls::{Certificate, PrivateKey};
 
 use crate::http::private::Key;
+use crate::http::{CookieDefaults, SameSite, CacheControl};
+use crate::http::size::parse_size;
 use crate::config::{Result, Config, Value, ConfigError, LoggingLevel};
 
 #[derive(Clone)]
@@ -34,7 +36,9 @@ impl fmt::Display for SecretKey {
         #[cfg(feature = "private-cookies")]
         match *self {
             SecretKey::Generated(_) => write!(f, "generated"),
-            SecretKey::Provided(_) => write!(f, "provided"),
+            SecretKey::Provided(ref key) => {
+                write!(f, "provided (fingerprint {})", key_fingerprint(key))
+            }
         }
 
         #[cfg(not(feature = "private-cookies"))]
@@ -42,6 +46,20 @@ impl fmt::Display for SecretKey {
     }
 }
 
+/// Returns a short fingerprint that identifies `key` without revealing it.
+///
+/// The fingerprint is a prefix of the base64 encoded HMAC-SHA256 signature of a
+/// fixed value, computed with the signing half of `key`.
+#[cfg(feature = "private-cookies")]
+pub(crate) fn key_fingerprint(key: &Key) -> String {
+    use crate::http::{Cookie, private::CookieJar};
+
+    let mut jar = CookieJar::new();
+    jar.signed(key).add(Cookie::new("rocket", "fingerprint"));
+    let cookie = jar.get("rocket").expect("signed cookie was just added");
+    cookie.value()[..12].to_string()
+}
+
 #[cfg(feature = "tls")]
 #[derive(Clone)]
 pub struct TlsConfig {
@@ -79,6 +97,45 @@ pub struct TlsConfig;
 ///     .limit("forms", 64 * 1024)
 ///     .limit("json", 3 * 1024 * 1024);
 /// ```
+///
+/// In `Rocket.toml`, a limit is either an integer number of bytes or a
+/// human-readable size string as accepted by
+/// [`parse_size()`](crate::http::size::parse_size):
+///
+/// ```toml
+/// [global.limits]
+/// forms = 65536
+/// json = "3 MiB"
+/// data = "10 MiB"
+/// ```
+///
+/// # Request Body Limits
+///
+/// Before a route's guards and handler run, Rocket compares the request's
+/// `Content-Length` against the limit that applies to the route and, if the
+/// declared length exceeds it, responds with `413 Payload Too Large` without
+/// reading the body. The applicable limit is the route's own limit, declared
+/// with the `limit` route attribute argument, or, if the route doesn't declare
+/// one, the `data` limit, if it is set. The same limit caps the body as it is
+/// read, which catches bodies without a `Content-Length`, such as chunked
+/// bodies: reading past the limit fails, and Rocket responds with a `413
+/// Payload Too Large` regardless of the handler's response.
+///
+/// # Decompressed Bodies
+///
+/// When one of the `decompression`, `gzip_decompression`, or
+/// `brotli_decompression` features is enabled, request bodies with a
+/// `Content-Encoding` of `gzip`, `deflate`, or `br` are decoded before routing.
+/// The `decompressed` limit, 8MiB by default, bounds the size of a decoded
+/// body; reading past it fails and the request is answered with `413 Payload
+/// Too Large`. Requests with an encoding Rocket can't decode are answered with
+/// `415 Unsupported Media Type`.
+///
+/// # WebSocket Messages
+///
+/// The `websocket` limit, 1MiB by default, bounds the size of a message
+/// received over a [WebSocket](crate::websocket) connection, including all of
+/// its fragments. A larger message closes the connection with status `1009`.
 #[derive(Debug, Clone)]
 pub struct Limits {
     // We cache this internally but don't share that fact in the API.
@@ -201,6 +258,278 @@ impl fmt::Display for Limits {
     }
 }
 
+/// Timeouts and rates that protect workers from slow clients.
+///
+/// Each worker serves one connection at a time, so a client that sends its
+/// request slowly occupies a worker for as long as it takes. A `Timeouts`
+/// structure bounds how long that can be:
+///
+///   * **header**: the maximum number of seconds a single read of the
+///     request's headers may block. Only the first request on a connection
+///     is bound by `header`: while a kept-alive connection waits for and reads
+///     the headers of later requests, each read is bound by `keep_alive`
+///     instead.
+///   * **read**: the maximum number of seconds a single read of the request's
+///     body may block.
+///   * **body**: the maximum number of seconds Rocket waits on the client, in
+///     total, while reading the request's body. Time spent in handlers between
+///     reads isn't counted.
+///   * **min_rate**: the minimum rate, in bytes per second, at which the client
+///     must send the request's body, measured after the first second of
+///     waiting.
+///
+/// A value of `None` disables the corresponding check. When a request's body
+/// violates the `read`, `body`, or `min_rate` bound, the read fails, Rocket
+/// responds with a `408 Request Timeout` and closes the connection, and the
+/// client's address is logged. A connection whose headers time out is closed
+/// without a response since no request exists yet.
+///
+/// # Defaults
+///
+/// As documented in [`config`](crate::config), the defaults are as follows:
+///
+///   * **header**: 10 seconds
+///   * **read**: 5 seconds
+///   * **body**: disabled
+///   * **min_rate**: disabled
+///
+/// # Example
+///
+/// In `Rocket.toml`, durations are given in seconds, with `0` disabling the
+/// check, and the rate as a size as accepted by
+/// [`parse_size()`](crate::http::size::parse_size):
+///
+/// ```toml
+/// [global.timeouts]
+/// header = 5
+/// read = 5
+/// body = 30
+/// min_rate = "1 KiB"
+/// ```
+///
+/// Or, equivalently, in code:
+///
+/// ```rust
+/// use rocket::config::{Config, Environment, Timeouts};
+///
+/// let timeouts = Timeouts {
+///     header: Some(5),
+///     body: Some(30),
+///     min_rate: Some(1024),
+///     ..Timeouts::default()
+/// };
+///
+/// let config = Config::build(Environment::Staging)
+///     .timeouts(timeouts)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Per-read timeout, in seconds, while reading the request's headers.
+    pub header: Option<u32>,
+    /// Per-read timeout, in seconds, while reading the request's body.
+    pub read: Option<u32>,
+    /// Total time, in seconds, allowed to receive the request's body.
+    pub body: Option<u32>,
+    /// Minimum transfer rate, in bytes per second, for the request's body.
+    pub min_rate: Option<u64>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Timeouts {
+        Timeouts { header: Some(10), read: Some(5), body: None, min_rate: None }
+    }
+}
+
+impl fmt::Display for Timeouts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn fmt_secs(name: &str, n: Option<u32>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match n {
+                Some(n) => write!(f, "{} = {}s", name, n),
+                None => write!(f, "{} = disabled", name),
+            }
+        }
+
+        fmt_secs("header", self.header, f)?;
+        fmt_secs(", read", self.read, f)?;
+        fmt_secs(", body", self.body, f)?;
+        match self.min_rate {
+            Some(rate) => write!(f, ", min rate = {}B/s", rate),
+            None => write!(f, ", min rate = disabled"),
+        }
+    }
+}
+
+/// The validators Rocket attaches to file responses.
+///
+/// When enabled, [`NamedFile`](crate::response::NamedFile) responses carry a
+/// `Last-Modified` header with the file's modification time and an `ETag`
+/// derived from the file's inode, size, and modification time, and requests
+/// with a matching `If-None-Match` or `If-Modified-Since` header are answered
+/// with a `304 Not Modified` without a body.
+///
+/// # Defaults
+///
+/// As documented in [`config`](crate::config), both validators are enabled by
+/// default.
+///
+/// # Example
+///
+/// In `Rocket.toml`:
+///
+/// ```toml
+/// [global.validators]
+/// etag = false
+/// last_modified = true
+/// ```
+///
+/// Or, equivalently, in code:
+///
+/// ```rust
+/// use rocket::config::{Config, Environment, Validators};
+///
+/// let config = Config::build(Environment::Staging)
+///     .validators(Validators { etag: false, last_modified: true })
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validators {
+    /// Whether file responses carry an `ETag`.
+    pub etag: bool,
+    /// Whether file responses carry a `Last-Modified` date.
+    pub last_modified: bool,
+}
+
+impl Default for Validators {
+    fn default() -> Validators {
+        Validators { etag: true, last_modified: true }
+    }
+}
+
+impl fmt::Display for Validators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "etag = {}, last_modified = {}", self.etag, self.last_modified)
+    }
+}
+
+/// Default `Cache-Control` policies by path prefix.
+///
+/// A `CacheDefaults` maps path prefixes, such as the paths routes are mounted
+/// at, to the [`CacheControl`] that successful (`2xx`) and `304 Not Modified`
+/// responses to requests under that prefix receive if they don't set a
+/// `Cache-Control` header themselves, for instance via
+/// [`Cached`](crate::response::Cached). A prefix matches a request path that
+/// is equal to it or continues it with a `/`; the longest matching prefix
+/// wins. Error responses and responses that set a cookie via `Set-Cookie` never
+/// receive a default policy.
+///
+/// # Defaults
+///
+/// As documented in [`config`](crate::config), there are no policies by
+/// default.
+///
+/// # Example
+///
+/// In `Rocket.toml`, each key is a path prefix and each value a
+/// `Cache-Control` header value:
+///
+/// ```toml
+/// [global.cache]
+/// "/static" = "public, max-age=31536000, immutable"
+/// "/api" = "private, no-store"
+/// ```
+///
+/// Or, equivalently, in code:
+///
+/// ```rust
+/// use rocket::config::{Config, Environment, CacheDefaults};
+/// use rocket::http::CacheControl;
+///
+/// let cache = CacheDefaults::new()
+///     .policy("/static", CacheControl::new().public().max_age(31536000).immutable())
+///     .policy("/api", CacheControl::new().private().no_store());
+///
+/// let config = Config::build(Environment::Staging)
+///     .cache(cache)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheDefaults {
+    policies: Vec<(String, CacheControl)>,
+}
+
+impl CacheDefaults {
+    /// Returns a new `CacheDefaults` with no policies.
+    #[inline(always)]
+    pub fn new() -> CacheDefaults {
+        CacheDefaults::default()
+    }
+
+    /// Sets the policy for requests whose path is `path` or begins with `path`
+    /// followed by a `/` to `cache_control`, replacing any existing policy for
+    /// `path`.
+    pub fn policy<P: Into<String>>(mut self, path: P, cache_control: CacheControl) -> Self {
+        let mut path = path.into();
+        while path.len() > 1 && path.ends_with('/') {
+            path.pop();
+        }
+
+        self.policies.retain(|(prefix, _)| *prefix != path);
+        self.policies.push((path, cache_control));
+        self
+    }
+
+    /// Returns the policy for a request to `path`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::CacheDefaults;
+    /// use rocket::http::CacheControl;
+    ///
+    /// let cache = CacheDefaults::new()
+    ///     .policy("/", CacheControl::new().no_cache())
+    ///     .policy("/static", CacheControl::new().public().max_age(3600));
+    ///
+    /// assert_eq!(cache.get("/static/app.js"), Some(&CacheControl::new().public().max_age(3600)));
+    /// assert_eq!(cache.get("/staticfoo"), Some(&CacheControl::new().no_cache()));
+    /// assert_eq!(CacheDefaults::new().get("/"), None);
+    /// ```
+    pub fn get(&self, path: &str) -> Option<&CacheControl> {
+        self.policies.iter()
+            .filter(|(prefix, _)| {
+                prefix == "/" || path == prefix
+                    || (path.starts_with(prefix.as_str()) && path[prefix.len()..].starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, cache_control)| cache_control)
+    }
+
+    /// Returns `true` if there are no policies.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+}
+
+impl fmt::Display for CacheDefaults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.policies.is_empty() {
+            return write!(f, "none");
+        }
+
+        for (i, (prefix, cache_control)) in self.policies.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{} = {}", prefix, cache_control)?;
+        }
+
+        Ok(())
+    }
+}
+
 pub fn str<'a>(conf: &Config, name: &str, v: &'a Value) -> Result<&'a str> {
     v.as_str().ok_or_else(|| conf.bad_type(name, v.type_str(), "a string"))
 }
@@ -212,6 +541,17 @@ pub fn u64(conf: &Config, name: &str, value: &Value) -> Result<u64> {
     }
 }
 
+pub fn size(conf: &Config, name: &str, value: &Value) -> Result<u64> {
+    match value.as_str() {
+        Some(string) => parse_size(string).map_err(|_| {
+            conf.bad_type(name, "an invalid size string", "a size such as \"64 KiB\"")
+        }),
+        None => u64(conf, name, value).map_err(|_| {
+            conf.bad_type(name, value.type_str(), "an unsigned integer or a size string")
+        })
+    }
+}
+
 pub fn u16(conf: &Config, name: &str, value: &Value) -> Result<u16> {
     match value.as_integer() {
         Some(x) if x >= 0 && x <= (u16::max_value() as i64) => Ok(x as u16),
@@ -259,15 +599,152 @@ pub fn tls_config<'v>(conf: &Config,
     }
 }
 
+pub fn secret_keys<'v>(conf: &Config,
+                       name: &str,
+                       value: &'v Value,
+                       ) -> Result<(&'v str, Vec<&'v str>)> {
+    let (mut primary, mut retired) = (None, vec![]);
+    let table = value.as_table()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
+
+    let env = conf.environment;
+    for (key, value) in table {
+        match key.as_str() {
+            "primary" => primary = Some(str(conf, "secret_keys.primary", value)?),
+            "retired" => {
+                let array = value.as_array().ok_or_else(|| {
+                    conf.bad_type("secret_keys.retired", value.type_str(), "an array")
+                })?;
+
+                for key in array {
+                    retired.push(str(conf, "secret_keys.retired", key)?);
+                }
+            }
+            _ => return Err(ConfigError::UnknownKey(format!("{}.secret_keys.{}", env, key)))
+        }
+    }
+
+    match primary {
+        Some(primary) => Ok((primary, retired)),
+        None => Err(conf.bad_type(name, "a table with missing entries",
+                                  "a table with a `primary` entry"))
+    }
+}
+
 pub fn limits(conf: &Config, name: &str, value: &Value) -> Result<Limits> {
     let table = value.as_table()
         .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
 
     let mut limits = Limits::default();
     for (key, val) in table {
-        let val = u64(conf, &format!("limits.{}", key), val)?;
+        let val = size(conf, &format!("limits.{}", key), val)?;
         limits = limits.limit(key.as_str(), val);
     }
 
     Ok(limits)
 }
+
+pub fn timeouts(conf: &Config, name: &str, value: &Value) -> Result<Timeouts> {
+    let table = value.as_table()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
+
+    let nonzero_u32 = |name: &str, value: &Value| u32(conf, name, value)
+        .map(|n| match n { 0 => None, n => Some(n) });
+
+    let env = conf.environment;
+    let mut timeouts = Timeouts::default();
+    for (key, value) in table {
+        match key.as_str() {
+            "header" => timeouts.header = nonzero_u32("timeouts.header", value)?,
+            "read" => timeouts.read = nonzero_u32("timeouts.read", value)?,
+            "body" => timeouts.body = nonzero_u32("timeouts.body", value)?,
+            "min_rate" => {
+                timeouts.min_rate = match size(conf, "timeouts.min_rate", value)? {
+                    0 => None,
+                    n => Some(n)
+                };
+            }
+            _ => return Err(ConfigError::UnknownKey(format!("{}.timeouts.{}", env, key)))
+        }
+    }
+
+    Ok(timeouts)
+}
+
+pub fn validators(conf: &Config, name: &str, value: &Value) -> Result<Validators> {
+    let table = value.as_table()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
+
+    let boolean = |name: &str, value: &Value| value.as_bool()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a boolean"));
+
+    let env = conf.environment;
+    let mut validators = Validators::default();
+    for (key, value) in table {
+        match key.as_str() {
+            "etag" => validators.etag = boolean("validators.etag", value)?,
+            "last_modified" => {
+                validators.last_modified = boolean("validators.last_modified", value)?;
+            }
+            _ => return Err(ConfigError::UnknownKey(format!("{}.validators.{}", env, key)))
+        }
+    }
+
+    Ok(validators)
+}
+
+pub fn cache_defaults(conf: &Config, name: &str, value: &Value) -> Result<CacheDefaults> {
+    let table = value.as_table()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
+
+    let mut defaults = CacheDefaults::new();
+    for (path, value) in table {
+        let name = format!("cache.{}", path);
+        if !path.starts_with('/') {
+            return Err(conf.bad_type(&name, "a relative path", "an absolute path prefix"));
+        }
+
+        let cache_control = str(conf, &name, value)?.parse::<CacheControl>()
+            .map_err(|_| conf.bad_type(&name, "an invalid policy", "a Cache-Control value"))?;
+
+        defaults = defaults.policy(path.as_str(), cache_control);
+    }
+
+    Ok(defaults)
+}
+
+pub fn cookie_defaults(conf: &Config, name: &str, value: &Value) -> Result<CookieDefaults> {
+    let table = value.as_table()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a table"))?;
+
+    let boolean = |name: &str, value: &Value| value.as_bool()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "a boolean"));
+
+    let env = conf.environment;
+    let mut defaults = CookieDefaults::new();
+    for (key, value) in table {
+        defaults = match key.as_str() {
+            "same_site" => {
+                let same_site = str(conf, "cookies.same_site", value)?;
+                match same_site.to_ascii_lowercase().as_str() {
+                    "strict" => defaults.same_site(SameSite::Strict),
+                    "lax" => defaults.same_site(SameSite::Lax),
+                    "none" => defaults.same_site(SameSite::None),
+                    _ => return Err(conf.bad_type("cookies.same_site", value.type_str(),
+                                                  "one of \"strict\", \"lax\", or \"none\""))
+                }
+            }
+            "secure" => defaults.secure(boolean("cookies.secure", value)?),
+            "http_only" => defaults.http_only(boolean("cookies.http_only", value)?),
+            "path" => defaults.path(str(conf, "cookies.path", value)?),
+            "domain" => defaults.domain(str(conf, "cookies.domain", value)?),
+            "max_age" => {
+                let secs = u32(conf, "cookies.max_age", value)?;
+                defaults.max_age(time::Duration::seconds(secs.into()))
+            }
+            _ => return Err(ConfigError::UnknownKey(format!("{}.cookies.{}", env, key)))
+        };
+    }
+
+    Ok(defaults)
+}
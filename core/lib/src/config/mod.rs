@@ -37,12 +37,18 @@
 //! | port       | integer        | port number to listen on                                    | `8000`, `80`               |
 //! | keep_alive | integer        | keep-alive timeout in seconds                               | `0` (disable), `10`        |
 //! | workers    | integer        | number of concurrent thread workers                         | `36`, `512`                |
+//! | event_streams | integer     | max concurrent event streams (default: half the workers)    | `0` (disable), `16`        |
 //! | log        | string         | max log level: `"off"`, `"normal"`, `"debug"`, `"critical"` | `"off"`, `"normal"`        |
 //! | secret_key | 256-bit base64 | secret key for private cookies                              | `"8Xui8SI..."` (44 chars)  |
+//! | secret_keys | table         | primary and retired secret keys (`primary`, `retired`)      | _see below_                |
 //! | tls        | table          | tls config table with two keys (`certs`, `key`)             | _see below_                |
 //! | tls.certs  | string         | path to certificate chain in PEM format                     | `"private/cert.pem"`       |
 //! | tls.key    | string         | path to private key for `tls.certs` in PEM format           | `"private/key.pem"`        |
-//! | limits     | table          | map from data type (string) to data limit (integer: bytes)  | `{ forms = 65536 }`        |
+//! | limits     | table          | map from data type (string) to data limit (bytes or size)   | `{ forms = "64 KiB" }`     |
+//! | timeouts   | table          | client read timeouts and minimum body rate                  | _see below_                |
+//! | validators | table          | validators attached to file responses                       | _see below_                |
+//! | cache      | table          | default `Cache-Control` policy by path prefix               | _see below_                |
+//! | cookies    | table          | default attributes for cookies added by the application     | _see below_                |
 //!
 //! ### Rocket.toml
 //!
@@ -94,6 +100,19 @@
 //! base64 encoded string. Such a string can be generated with the `openssl`
 //! command line tool: `openssl rand -base64 32`.
 //!
+//! To rotate the secret key without invalidating existing private or signed
+//! cookies, use `secret_keys` in place of `secret_key`. The `primary` key is
+//! used for all new cookies while the keys in `retired` are only used to read
+//! cookies created with them; such cookies are reissued under the primary key:
+//!
+//! ```toml
+//! [production]
+//! secret_keys = { primary = "8Xui8SI...", retired = ["jTyprDb...", "TpUiXK2..."] }
+//! ```
+//!
+//! Only fingerprints of the keys, never the keys themselves, are logged at
+//! launch.
+//!
 //! The "global" pseudo-environment can be used to set and/or override
 //! configuration parameters globally. A parameter defined in a `[global]` table
 //! sets, or overrides if already present, that parameter in every environment.
@@ -124,6 +143,85 @@
 //! key = "/path/to/key.pem"
 //! ```
 //!
+//! ### Timeouts
+//!
+//! The `timeouts` parameter protects workers from slow clients. `header` and
+//! `read` bound, in seconds, how long a single read of the request's headers
+//! or body may block, `body` bounds the total time spent waiting on the body,
+//! and `min_rate` is the minimum rate at which the body must arrive. A `0`
+//! disables a bound. A client violating a body bound receives a `408 Request
+//! Timeout`. `header` only applies to the first request on a connection; reads
+//! of the headers of later requests on a kept-alive connection are bounded by
+//! `keep_alive` instead. All keys are optional; see [`Timeouts`] for the
+//! defaults:
+//!
+//! ```toml
+//! [global.timeouts]
+//! header = 10
+//! read = 5
+//! body = 60
+//! min_rate = "1 KiB"     # per second
+//! ```
+//!
+//! ### Event Streams
+//!
+//! Each [`EventStream`](crate::response::EventStream) occupies a worker for as
+//! long as the client stays connected. To keep long-lived streams from starving
+//! ordinary requests, `event_streams` bounds how many may be open at once; a
+//! request for a stream beyond the bound receives a `503 Service Unavailable`.
+//! The bound defaults to half of the `workers`:
+//!
+//! ```toml
+//! [global]
+//! workers = 64
+//! event_streams = 48
+//! ```
+//!
+//! ### File Validators
+//!
+//! The `validators` parameter controls the validators attached to file
+//! responses, allowing clients to revalidate cached files with a conditional
+//! request answered by a `304 Not Modified`. Both are enabled by default; see
+//! [`Validators`]:
+//!
+//! ```toml
+//! [global.validators]
+//! etag = true             # derived from the file's inode, size, and mtime
+//! last_modified = true
+//! ```
+//!
+//! ### Cache Defaults
+//!
+//! The `cache` parameter maps path prefixes to the `Cache-Control` header that
+//! successful responses under the prefix receive unless they set their own or
+//! set a cookie. The longest matching prefix wins. There are no policies by default; see
+//! [`CacheDefaults`]:
+//!
+//! ```toml
+//! [global.cache]
+//! "/static" = "public, max-age=31536000, immutable"
+//! "/api" = "private, no-store"
+//! ```
+//!
+//! ### Cookie Defaults
+//!
+//! The `cookies` parameter sets default attributes for cookies added via
+//! [`Cookies`](crate::http::Cookies). A default is only applied to a cookie
+//! that doesn't set the attribute itself. All keys are optional:
+//!
+//! ```toml
+//! [global.cookies]
+//! same_site = "lax"       # "strict", "lax", or "none"
+//! secure = true
+//! http_only = true
+//! path = "/"
+//! domain = "rocket.rs"
+//! max_age = 86400         # in seconds
+//! ```
+//!
+//! Cookies named with the `__Host-` or `__Secure-` prefixes always have their
+//! prefix's rules enforced; see [`CookiePrefix`](crate::http::CookiePrefix).
+//!
 //! ### Environment Variables
 //!
 //! All configuration parameters, including extras, can be overridden through
@@ -197,7 +295,7 @@ use std::env;
 
 use toml;
 
-pub use self::custom_values::Limits;
+pub use self::custom_values::{Limits, Timeouts, Validators, CacheDefaults};
 pub use toml::value::{Array, Table, Value, Datetime};
 pub use self::error::ConfigError;
 pub use self::environment::Environment;
@@ -473,6 +571,7 @@ mod test {
     use std::sync::Mutex;
 
     use super::{RocketConfig, Config, ConfigError, ConfigBuilder};
+    use super::{Timeouts, Validators, CacheDefaults};
     use super::{Environment, GLOBAL_ENV_NAME};
     use super::environment::CONFIG_ENV;
     use super::Environment::*;
@@ -863,6 +962,38 @@ mod test {
         "#.to_string(), TEST_CONFIG_FILENAME).is_err());
     }
 
+    #[test]
+    fn test_event_streams() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            workers = 9
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        assert_eq!(config.active().event_streams, None);
+        assert_eq!(config.active().max_event_streams(), 4);
+
+        check_config!(RocketConfig::parse(r#"
+                          [stage]
+                          event_streams = 32
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Staging).event_streams(32)
+                      });
+
+        assert!(RocketConfig::parse(r#"
+            [stage]
+            event_streams = -1
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [stage]
+            event_streams = "many"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
     #[test]
     fn test_good_keep_alives() {
         // Take the lock so changing the environment doesn't cause races.
@@ -1030,6 +1161,274 @@ mod test {
         "#.to_string(), TEST_CONFIG_FILENAME).is_err());
     }
 
+    #[test]
+    fn test_good_secret_keys() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            secret_keys = { primary = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=" }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert!(!config.active().secret_key.is_generated());
+        assert!(config.active().retired_secret_keys().is_empty());
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            secret_keys = { primary = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=", retired = [
+                "jTyprDberFUiUFsJ3vcb1XKsYHWNBRvWAnXTlbTgGFU=",
+                "fe4c5b09a9ac372156e44ce133bc940685ef5e0394d6e9274aadacc21e4f2643"
+            ] }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().retired_secret_keys().len(), 2);
+
+        let config = default_config(Staging)
+            .secret_keys("TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=",
+                         vec!["jTyprDberFUiUFsJ3vcb1XKsYHWNBRvWAnXTlbTgGFU="])
+            .unwrap();
+        assert_eq!(config.retired_secret_keys().len(), 1);
+    }
+
+    #[test]
+    fn test_bad_secret_keys() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            secret_keys = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0="
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            secret_keys = { retired = ["TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0="] }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            secret_keys = { primary = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=", retired = ["abcv"] }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            secret_keys = { primary = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=", other = 1 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_good_cookie_defaults() {
+        use crate::http::{CookieDefaults, SameSite};
+
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "dev");
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            cookies = { same_site = "Lax", secure = true, path = "/app", max_age = 60 }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = CookieDefaults::new()
+            .same_site(SameSite::Lax)
+            .secure(true)
+            .path("/app")
+            .max_age(time::Duration::seconds(60));
+        assert_eq!(config.active().cookies, expected);
+
+        let config = RocketConfig::parse(r#"
+            [dev.cookies]
+            http_only = false
+            domain = "rocket.rs"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = CookieDefaults::new().http_only(false).domain("rocket.rs");
+        assert_eq!(config.active().cookies, expected);
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            cookies = {}
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert!(config.active().cookies.is_empty());
+    }
+
+    #[test]
+    fn test_bad_cookie_defaults() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cookies = { same_site = "sometimes" }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cookies = { secure = "yes" }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cookies = { max_age = -1 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cookies = { expires = 10 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cookies = "strict"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "dev");
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            limits = { forms = 1024, json = "64 KiB", data = "2MiB", file = "1.5 MB" }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let limits = &config.active().limits;
+        assert_eq!(limits.get("forms"), Some(1024));
+        assert_eq!(limits.get("json"), Some(64 * 1024));
+        assert_eq!(limits.get("data"), Some(2 * 1024 * 1024));
+        assert_eq!(limits.get("file"), Some(1_500_000));
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            limits = { forms = "64 KB!" }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            limits = { forms = -1 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            limits = { forms = true }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_timeouts() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "dev");
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            timeouts = { header = 3, body = 60, min_rate = "1 KiB" }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = Timeouts { header: Some(3), read: Some(5), body: Some(60), min_rate: Some(1024) };
+        assert_eq!(config.active().timeouts, expected);
+
+        let config = RocketConfig::parse(r#"
+            [dev.timeouts]
+            header = 0
+            read = 0
+            min_rate = 0
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = Timeouts { header: None, read: None, body: None, min_rate: None };
+        assert_eq!(config.active().timeouts, expected);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            timeouts = { header = -1 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            timeouts = { min_rate = "fast" }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            timeouts = { write = 10 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_validators() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "dev");
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            address = "localhost"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = Validators { etag: true, last_modified: true };
+        assert_eq!(config.active().validators, expected);
+
+        let config = RocketConfig::parse(r#"
+            [dev]
+            validators = { etag = false }
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = Validators { etag: false, last_modified: true };
+        assert_eq!(config.active().validators, expected);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            validators = { last_modified = "no" }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            validators = { content_md5 = true }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_cache_defaults() {
+        use crate::http::CacheControl;
+
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "dev");
+
+        let config = RocketConfig::parse(r#"
+            [dev.cache]
+            "/static/" = "public, max-age=60"
+            "/api" = "private, no-store"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let expected = CacheDefaults::new()
+            .policy("/api", CacheControl::new().private().no_store())
+            .policy("/static", CacheControl::new().public().max_age(60));
+
+        assert_eq!(config.active().cache.get("/api/users"), expected.get("/api/users"));
+        assert_eq!(config.active().cache.get("/static"), expected.get("/static"));
+        assert_eq!(config.active().cache.get("/other"), None);
+
+        assert!(RocketConfig::parse(r#"
+            [dev.cache]
+            "static" = "public"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev.cache]
+            "/" = "forever"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            cache = "public"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
     #[test]
     fn test_bad_toml() {
         // Take the lock so changing the environment doesn't cause races.
@@ -1,24 +1,104 @@
 use std::io::{self, Read, Write, Cursor, Chain};
 use std::path::Path;
 use std::fs::File;
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, AtomicU64, Ordering};
 
-#[cfg(feature = "tls")] use super::net_stream::HttpsStream;
-
-use super::data_stream::{DataStream, kill_stream};
-use super::net_stream::NetStream;
+use super::data_stream::{DataStream, Limiter, kill_stream};
+use super::net_stream::{NetStream, TimedStream};
 use crate::ext::ReadExt;
+use crate::config::Timeouts;
+use crate::http::Status;
 
 use crate::http::hyper;
 use crate::http::hyper::h1::HttpReader;
 use crate::http::hyper::h1::HttpReader::*;
-use crate::http::hyper::net::{HttpStream, NetworkStream};
+use crate::http::hyper::net::NetworkStream;
 
 pub type HyperBodyReader<'a, 'b> =
     self::HttpReader<&'a mut hyper::buffer::BufReader<&'b mut dyn NetworkStream>>;
 
 //                              |---- from hyper ----|
-pub type BodyReader = HttpReader<Chain<Cursor<Vec<u8>>, NetStream>>;
+pub type BodyReader = HttpReader<Chain<Cursor<Vec<u8>>, TimedStream>>;
+
+/// The source of a request's body: either the raw HTTP body or a decoder
+/// reading from the `DataStream` of a raw body.
+pub enum Body {
+    Raw(BodyReader),
+    #[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+    Decoded(Box<dyn Read>),
+}
+
+impl Body {
+    #[inline]
+    fn empty() -> Body {
+        let empty_stream = Cursor::new(vec![]).chain(TimedStream::from(NetStream::Empty));
+        Body::Raw(HttpReader::SizedReader(empty_stream, 0))
+    }
+}
+
+impl Read for Body {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            Body::Raw(ref mut stream) => stream.read(buf),
+            #[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+            Body::Decoded(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+/// A record of a violation, while reading a request's body, that replaces the
+/// handler's response with an error response of the recorded status.
+#[derive(Clone, Default)]
+pub(crate) struct Abort(Arc<AtomicU16>);
+
+impl Abort {
+    /// Records `status` unless a violation was already recorded. Returns
+    /// `true` if `status` was recorded.
+    #[inline]
+    pub fn set(&self, status: Status) -> bool {
+        self.0.compare_exchange(0, status.code, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+    }
+
+    /// Returns the recorded status, if any.
+    #[inline]
+    pub fn status(&self) -> Option<Status> {
+        match self.0.load(Ordering::SeqCst) {
+            0 => None,
+            code => Status::from_code(code),
+        }
+    }
+}
+
+/// The limit on the size of a request's body, set by the dispatcher to the
+/// limit of each route before the route is tried. Reading the body past the
+/// limit fails and records a `413` in the body's `Abort`.
+#[derive(Clone)]
+pub(crate) struct BodyLimit(Arc<AtomicU64>);
+
+impl BodyLimit {
+    /// Sets the limit to `limit`, removing it if `limit` is `None`.
+    #[inline]
+    pub fn set(&self, limit: Option<u64>) {
+        self.0.store(limit.unwrap_or(u64::max_value()), Ordering::SeqCst);
+    }
+
+    /// Returns the limit, if any.
+    #[inline]
+    pub fn get(&self) -> Option<u64> {
+        match self.0.load(Ordering::SeqCst) {
+            n if n == u64::max_value() => None,
+            n => Some(n),
+        }
+    }
+}
+
+impl Default for BodyLimit {
+    fn default() -> BodyLimit {
+        BodyLimit(Arc::new(AtomicU64::new(u64::max_value())))
+    }
+}
 
 /// The number of bytes to read into the "peek" buffer.
 const PEEK_BYTES: usize = 512;
@@ -58,7 +138,9 @@ const PEEK_BYTES: usize = 512;
 pub struct Data {
     buffer: Vec<u8>,
     is_complete: bool,
-    stream: BodyReader,
+    stream: Body,
+    abort: Abort,
+    limit: BodyLimit,
 }
 
 impl Data {
@@ -80,44 +162,37 @@ impl Data {
     /// ```
     pub fn open(mut self) -> DataStream {
         let buffer = std::mem::replace(&mut self.buffer, vec![]);
-        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
 
         // FIXME: Insert a `BufReader` in front of the `NetStream` with capacity
         // 4096. We need the new `Chain` methods to get the inner reader to
         // actually do this, however.
-        let empty_http_stream = HttpReader::SizedReader(empty_stream, 0);
-        let stream = std::mem::replace(&mut self.stream, empty_http_stream);
-        DataStream(Cursor::new(buffer).chain(stream))
-    }
+        let stream = std::mem::replace(&mut self.stream, Body::empty());
 
-    // FIXME: This is absolutely terrible (downcasting!), thanks to Hyper.
-    pub(crate) fn from_hyp(mut body: HyperBodyReader<'_, '_>) -> Result<Data, &'static str> {
-        #[inline(always)]
-        #[cfg(feature = "tls")]
-        fn concrete_stream(stream: &mut dyn NetworkStream) -> Option<NetStream> {
-            stream.downcast_ref::<HttpsStream>()
-                .map(|s| NetStream::Https(s.clone()))
-                .or_else(|| {
-                    stream.downcast_ref::<HttpStream>()
-                        .map(|s| NetStream::Http(s.clone()))
-                })
-        }
+        // The limit applies to the raw body. A decoded body reads from the
+        // raw body's stream, which enforces the limit itself.
+        let limiter = match stream {
+            Body::Raw(_) => Some(Limiter::new(self.limit.clone(), self.abort.clone())),
+            #[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+            Body::Decoded(_) => None,
+        };
 
-        #[inline(always)]
-        #[cfg(not(feature = "tls"))]
-        fn concrete_stream(stream: &mut dyn NetworkStream) -> Option<NetStream> {
-            stream.downcast_ref::<HttpStream>()
-                .map(|s| NetStream::Http(s.clone()))
-        }
+        DataStream(Cursor::new(buffer).chain(stream), limiter)
+    }
 
+    // FIXME: This is absolutely terrible (downcasting!), thanks to Hyper.
+    pub(crate) fn from_hyp(
+        mut body: HyperBodyReader<'_, '_>,
+        timeouts: Timeouts
+    ) -> Result<Data, &'static str> {
         // Retrieve the underlying Http(s)Stream from Hyper.
-        let net_stream = match concrete_stream(*body.get_mut().get_mut()) {
+        let net_stream = match NetStream::from_hyp(*body.get_mut().get_mut()) {
             Some(net_stream) => net_stream,
             None => return Err("Stream is not an HTTP(s) stream!")
         };
 
-        // Set the read timeout to 5 seconds.
-        let _ = net_stream.set_read_timeout(Some(Duration::from_secs(5)));
+        // Bound reads from the stream by the configured timeouts.
+        let abort = Abort::default();
+        let net_stream = TimedStream::new(net_stream, timeouts, abort.clone());
 
         // Steal the internal, undecoded data buffer from Hyper.
         let (mut hyper_buf, pos, cap) = body.get_mut().take_buf();
@@ -134,7 +209,7 @@ impl Data {
             ChunkedReader(_, n) => ChunkedReader(inner_data, n)
         };
 
-        Ok(Data::new(http_stream))
+        Ok(Data::new(Body::Raw(http_stream), abort, BodyLimit::default()))
     }
 
     /// Retrieve the `peek` buffer.
@@ -230,8 +305,7 @@ impl Data {
     // bytes `vec[pos..cap]` are buffered and unread. The remainder of the data
     // bytes can be read from `stream`.
     #[inline(always)]
-    pub(crate) fn new(mut stream: BodyReader) -> Data {
-        trace_!("Data::new({:?})", stream);
+    pub(crate) fn new(mut stream: Body, abort: Abort, limit: BodyLimit) -> Data {
         let mut peek_buf: Vec<u8> = vec![0; PEEK_BYTES];
 
         // Fill the buffer with as many bytes as possible. If we read less than
@@ -255,18 +329,31 @@ impl Data {
         };
 
         trace_!("Peek bytes: {}/{} bytes.", peek_buf.len(), PEEK_BYTES);
-        Data { buffer: peek_buf, stream, is_complete: eof }
+        Data { buffer: peek_buf, stream, is_complete: eof, abort, limit }
+    }
+
+    /// Returns the record of violations, such as a timeout, that occur while
+    /// reading the body.
+    #[inline]
+    pub(crate) fn abort(&self) -> Abort {
+        self.abort.clone()
+    }
+
+    /// Returns the limit on the size of the body.
+    #[inline]
+    pub(crate) fn limit(&self) -> BodyLimit {
+        self.limit.clone()
     }
 
     /// This creates a `data` object from a local data source `data`.
     #[inline]
     pub(crate) fn local(data: Vec<u8>) -> Data {
-        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
-
         Data {
             buffer: data,
-            stream: HttpReader::SizedReader(empty_stream, 0),
+            stream: Body::empty(),
             is_complete: true,
+            abort: Abort::default(),
+            limit: BodyLimit::default(),
         }
     }
 }
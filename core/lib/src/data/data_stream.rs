@@ -1,12 +1,14 @@
+use std::cmp::min;
 use std::io::{self, Read, Cursor, Chain};
 use std::net::Shutdown;
 
-use super::data::BodyReader;
+use super::data::{Body, BodyLimit, Abort};
+use crate::http::Status;
 use crate::http::hyper::net::NetworkStream;
 use crate::http::hyper::h1::HttpReader;
 
 //                          |-- peek buf --|
-pub type InnerStream = Chain<Cursor<Vec<u8>>, BodyReader>;
+pub type InnerStream = Chain<Cursor<Vec<u8>>, Body>;
 
 /// Raw data stream of a request body.
 ///
@@ -14,7 +16,10 @@ pub type InnerStream = Chain<Cursor<Vec<u8>>, BodyReader>;
 /// [`Data::open()`](crate::data::Data::open()). The stream contains all of the data
 /// in the body of the request. It exposes no methods directly. Instead, it must
 /// be used as an opaque [`Read`] structure.
-pub struct DataStream(pub(crate) InnerStream);
+///
+/// Reading past the body limit of the route handling the request fails, and
+/// Rocket responds to the request with a `413 Payload Too Large`.
+pub struct DataStream(pub(crate) InnerStream, pub(crate) Option<Limiter>);
 
 // TODO: Have a `BufRead` impl for `DataStream`. At the moment, this isn't
 // possible since Hyper's `HttpReader` doesn't implement `BufRead`.
@@ -22,11 +27,66 @@ impl Read for DataStream {
     #[inline(always)]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         trace_!("DataStream::read()");
-        self.0.read(buf)
+        match self.1 {
+            Some(ref mut limiter) => limiter.read(&mut self.0, buf),
+            None => self.0.read(buf),
+        }
+    }
+}
+
+/// Enforces a `BodyLimit` on the bytes read from a body, recording a `413` in
+/// `abort` if the body is read past it.
+pub(crate) struct Limiter {
+    limit: BodyLimit,
+    abort: Abort,
+    read: u64,
+}
+
+impl Limiter {
+    pub(crate) fn new(limit: BodyLimit, abort: Abort) -> Limiter {
+        Limiter { limit, abort, read: 0 }
+    }
+
+    fn read<R: Read>(&mut self, reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+        let limit = match self.limit.get() {
+            Some(limit) if !buf.is_empty() => limit,
+            _ => {
+                let n = reader.read(buf)?;
+                self.read += n as u64;
+                return Ok(n);
+            }
+        };
+
+        if self.read >= limit {
+            // The limit has been reached: anything more is a violation.
+            return match reader.read(&mut [0])? {
+                0 => Ok(0),
+                _ => {
+                    if self.abort.set(Status::PayloadTooLarge) {
+                        warn_!("Request body exceeds its limit ({} bytes).", limit);
+                    }
+
+                    let msg = "request body exceeds its limit";
+                    Err(io::Error::new(io::ErrorKind::Other, msg))
+                }
+            };
+        }
+
+        let max = min(buf.len() as u64, limit - self.read) as usize;
+        let n = reader.read(&mut buf[..max])?;
+        self.read += n as u64;
+        Ok(n)
     }
 }
 
-pub fn kill_stream(stream: &mut BodyReader) {
+pub fn kill_stream(stream: &mut Body) {
+    // A decoded body owns the raw `DataStream`, which is killed when dropped.
+    let stream = match *stream {
+        Body::Raw(ref mut stream) => stream,
+        #[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+        Body::Decoded(_) => return,
+    };
+
     // Only do the expensive reading if we're not sure we're done.
     use self::HttpReader::*;
     match *stream {
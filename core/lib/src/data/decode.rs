@@ -0,0 +1,108 @@
+use std::cmp::min;
+use std::io::{self, Read};
+
+use crate::http::Status;
+use crate::request::Request;
+use super::data::{Data, Body, Abort};
+
+/// The default limit, 8MiB, on the size of a decompressed request body.
+pub const DEFAULT_DECOMPRESSED_LIMIT: u64 = 8 << 20;
+
+/// A content coding that Rocket can decode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Coding {
+    #[cfg(feature = "gzip_decompression")]
+    Gzip,
+    #[cfg(feature = "gzip_decompression")]
+    Deflate,
+    #[cfg(feature = "brotli_decompression")]
+    Brotli,
+}
+
+/// Returns the codings in the request's `Content-Encoding` headers, in the
+/// order they were applied, ignoring `identity`. If a coding can't be decoded,
+/// returns it as an `Err`.
+fn codings(request: &Request<'_>) -> Result<Vec<Coding>, String> {
+    let mut codings = vec![];
+    for value in request.headers().get("Content-Encoding") {
+        for coding in value.split(',').map(|c| c.trim()).filter(|c| !c.is_empty()) {
+            match coding.to_ascii_lowercase().as_str() {
+                "identity" => continue,
+                #[cfg(feature = "gzip_decompression")]
+                "gzip" | "x-gzip" => codings.push(Coding::Gzip),
+                #[cfg(feature = "gzip_decompression")]
+                "deflate" => codings.push(Coding::Deflate),
+                #[cfg(feature = "brotli_decompression")]
+                "br" => codings.push(Coding::Brotli),
+                _ => return Err(coding.to_string()),
+            }
+        }
+    }
+
+    Ok(codings)
+}
+
+/// Replaces `data` with its decoded contents according to the request's
+/// `Content-Encoding`. The decoded body is limited to the `decompressed` limit;
+/// reading past it fails and records a `413` in the body's `Abort`. If the
+/// body's encoding can't be decoded, returns the offending coding as an `Err`.
+pub(crate) fn decode(request: &Request<'_>, data: Data) -> Result<Data, String> {
+    let codings = codings(request)?;
+    if codings.is_empty() {
+        return Ok(data);
+    }
+
+    let limit = request.limits().get("decompressed").unwrap_or(DEFAULT_DECOMPRESSED_LIMIT);
+    let (abort, body_limit) = (data.abort(), data.limit());
+    let mut reader: Box<dyn Read> = Box::new(data.open());
+    for coding in codings.into_iter().rev() {
+        reader = match coding {
+            #[cfg(feature = "gzip_decompression")]
+            Coding::Gzip => Box::new(flate2::read::GzDecoder::new(reader)),
+            // HTTP's `deflate` is the zlib format, not raw deflate.
+            #[cfg(feature = "gzip_decompression")]
+            Coding::Deflate => Box::new(flate2::read::ZlibDecoder::new(reader)),
+            #[cfg(feature = "brotli_decompression")]
+            Coding::Brotli => Box::new(brotli::Decompressor::new(reader, 4096)),
+        };
+    }
+
+    let limited = Limited { reader, remaining: limit, abort: abort.clone() };
+    Ok(Data::new(Body::Decoded(Box::new(limited)), abort, body_limit))
+}
+
+/// A reader that fails, recording a `413` in `abort`, if `reader` produces
+/// more than `remaining` bytes.
+struct Limited<R> {
+    reader: R,
+    remaining: u64,
+    abort: Abort,
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            // The limit has been reached: anything more is a violation.
+            return match self.reader.read(&mut [0])? {
+                0 => Ok(0),
+                _ => {
+                    if self.abort.set(Status::PayloadTooLarge) {
+                        warn_!("Decompressed request body exceeds its limit.");
+                    }
+
+                    let msg = "decompressed request body exceeds its limit";
+                    Err(io::Error::new(io::ErrorKind::Other, msg))
+                }
+            };
+        }
+
+        let max = min(buf.len() as u64, self.remaining) as usize;
+        let n = self.reader.read(&mut buf[..max])?;
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
@@ -4,7 +4,10 @@ mod data;
 mod data_stream;
 mod net_stream;
 mod from_data;
+#[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+pub(crate) mod decode;
 
 pub use self::data::Data;
 pub use self::data_stream::DataStream;
 pub use self::from_data::{FromData, FromDataSimple, Outcome, Transform, Transformed};
+pub(crate) use self::net_stream::NetStream;
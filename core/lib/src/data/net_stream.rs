@@ -1,9 +1,12 @@
 use std::io;
 use std::net::{SocketAddr, Shutdown};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "tls")] use crate::http::tls::{WrappedStream, ServerSession};
 use crate::http::hyper::net::{HttpStream, NetworkStream};
+use crate::config::Timeouts;
+use crate::http::Status;
+use super::data::Abort;
 
 use self::NetStream::*;
 
@@ -19,6 +22,24 @@ pub enum NetStream {
     Empty,
 }
 
+impl NetStream {
+    /// Returns a handle to the concrete stream underlying Hyper's `stream`, if
+    /// it's an HTTP(S) stream.
+    #[cfg(feature = "tls")]
+    pub(crate) fn from_hyp(stream: &mut dyn NetworkStream) -> Option<NetStream> {
+        stream.downcast_ref::<HttpsStream>()
+            .map(|s| Https(s.clone()))
+            .or_else(|| stream.downcast_ref::<HttpStream>().map(|s| Http(s.clone())))
+    }
+
+    /// Returns a handle to the concrete stream underlying Hyper's `stream`, if
+    /// it's an HTTP stream.
+    #[cfg(not(feature = "tls"))]
+    pub(crate) fn from_hyp(stream: &mut dyn NetworkStream) -> Option<NetStream> {
+        stream.downcast_ref::<HttpStream>().map(|s| Http(s.clone()))
+    }
+}
+
 impl io::Read for NetStream {
     #[inline(always)]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -92,3 +113,215 @@ impl NetworkStream for NetStream {
         }
     }
 }
+
+/// A `NetStream` whose reads are bounded by the configured `Timeouts`.
+///
+/// Only the time spent blocked in `read` counts toward the body deadline and
+/// the minimum rate, so a handler doing work between reads isn't penalized.
+/// Once a bound is violated, every subsequent read fails and a `408` is
+/// recorded in the shared `Abort` so that the dispatcher responds with it.
+pub struct TimedStream {
+    stream: NetStream,
+    timeouts: Option<Timeouts>,
+    remote: Option<SocketAddr>,
+    waited: Duration,
+    received: u64,
+    expired: bool,
+    abort: Abort,
+}
+
+impl TimedStream {
+    pub fn new(mut stream: NetStream, timeouts: Timeouts, abort: Abort) -> TimedStream {
+        let remote = stream.peer_addr().ok();
+        let read_timeout = timeouts.read.map(|s| Duration::from_secs(s as u64));
+        if let Err(e) = stream.set_read_timeout(read_timeout) {
+            warn_!("Failed to set the body read timeout: {:?}", e);
+        }
+
+        TimedStream {
+            stream, remote, abort,
+            timeouts: Some(timeouts),
+            waited: Duration::from_secs(0),
+            received: 0,
+            expired: false,
+        }
+    }
+
+    #[inline]
+    pub fn close(&mut self, how: Shutdown) -> io::Result<()> {
+        self.stream.close(how)
+    }
+
+    fn expire<T>(&mut self, reason: &'static str) -> io::Result<T> {
+        self.expired = true;
+        if self.abort.set(Status::RequestTimeout) {
+            match self.remote {
+                Some(addr) => warn_!("Client {} was too slow: {}.", addr, reason),
+                None => warn_!("Client was too slow: {}.", reason),
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::TimedOut, reason))
+    }
+}
+
+impl From<NetStream> for TimedStream {
+    #[inline]
+    fn from(stream: NetStream) -> TimedStream {
+        TimedStream {
+            stream,
+            timeouts: None,
+            remote: None,
+            waited: Duration::from_secs(0),
+            received: 0,
+            expired: false,
+            abort: Abort::default(),
+        }
+    }
+}
+
+impl io::Read for TimedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let timeouts = match self.timeouts {
+            Some(timeouts) => timeouts,
+            None => return self.stream.read(buf),
+        };
+
+        if self.expired {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request timed out"));
+        }
+
+        // Never block past the body's deadline.
+        if let Some(deadline) = timeouts.body.map(|s| Duration::from_secs(s as u64)) {
+            let remaining = match deadline.checked_sub(self.waited) {
+                Some(remaining) if remaining > Duration::from_millis(1) => remaining,
+                _ => return self.expire("request body deadline exceeded"),
+            };
+
+            let read_timeout = timeouts.read.map(|s| Duration::from_secs(s as u64));
+            let timeout = read_timeout.map_or(remaining, |t| ::std::cmp::min(t, remaining));
+            self.stream.set_read_timeout(Some(timeout))?;
+        }
+
+        let start = Instant::now();
+        let result = self.stream.read(buf);
+        self.waited += start.elapsed();
+
+        match result {
+            Ok(n) => {
+                self.received += n as u64;
+                if let Some(rate) = timeouts.min_rate {
+                    let waited = self.waited.as_secs_f64();
+                    if n > 0 && waited > 1.0 && (self.received as f64) < (rate as f64) * waited {
+                        return self.expire("request body sent below the minimum rate");
+                    }
+                }
+
+                Ok(n)
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock
+                || e.kind() == io::ErrorKind::TimedOut => {
+                let deadline = timeouts.body.map(|s| Duration::from_secs(s as u64));
+                match deadline.map_or(false, |deadline| self.waited >= deadline) {
+                    true => self.expire("request body deadline exceeded"),
+                    false => self.expire("request body read timed out"),
+                }
+            }
+            Err(e) => Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{NetStream, TimedStream};
+    use crate::config::Timeouts;
+    use crate::data::data::Abort;
+    use crate::http::Status;
+    use crate::http::hyper::net::HttpStream;
+
+    // Connects a client to a local server, running `client` in a thread on the
+    // client's end, and returns the server's end of the connection.
+    fn connect<F: FnOnce(TcpStream) + Send + 'static>(client: F) -> NetStream {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || client(TcpStream::connect(addr).unwrap()));
+        NetStream::Http(HttpStream(listener.accept().unwrap().0))
+    }
+
+    fn timeouts(read: Option<u32>, body: Option<u32>, min_rate: Option<u64>) -> Timeouts {
+        Timeouts { header: None, read, body, min_rate }
+    }
+
+    #[test]
+    fn read_timeout() {
+        let stream = connect(|_client| thread::sleep(Duration::from_secs(3)));
+        let abort = Abort::default();
+        let timeouts = timeouts(Some(1), None, None);
+        let mut stream = TimedStream::new(stream, timeouts, abort.clone());
+        assert!(stream.read(&mut [0; 8]).is_err());
+        assert_eq!(abort.status(), Some(Status::RequestTimeout));
+    }
+
+    #[test]
+    fn body_deadline() {
+        let stream = connect(|mut client| {
+            for _ in 0..30 {
+                if client.write_all(b"a").is_err() { break; }
+                thread::sleep(Duration::from_millis(100));
+            }
+        });
+
+        let abort = Abort::default();
+        let timeouts = timeouts(Some(5), Some(1), None);
+        let mut stream = TimedStream::new(stream, timeouts, abort.clone());
+        let mut buf = [0; 8];
+        let error = loop {
+            match stream.read(&mut buf) {
+                Ok(n) => assert!(n > 0),
+                Err(e) => break e,
+            }
+        };
+
+        assert_eq!(error.kind(), ::std::io::ErrorKind::TimedOut);
+        assert_eq!(abort.status(), Some(Status::RequestTimeout));
+        assert!(stream.read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn min_rate() {
+        let stream = connect(|mut client| {
+            for _ in 0..30 {
+                if client.write_all(b"a").is_err() { break; }
+                thread::sleep(Duration::from_millis(100));
+            }
+        });
+
+        let abort = Abort::default();
+        let timeouts = timeouts(Some(5), None, Some(100));
+        let mut stream = TimedStream::new(stream, timeouts, abort.clone());
+        let mut buf = [0; 8];
+        while let Ok(n) = stream.read(&mut buf) {
+            assert!(n > 0);
+        }
+
+        assert_eq!(abort.status(), Some(Status::RequestTimeout));
+    }
+
+    #[test]
+    fn fast_client() {
+        let stream = connect(|mut client| client.write_all(&[1; 4096]).unwrap());
+        let abort = Abort::default();
+        let timeouts = timeouts(Some(1), Some(1), Some(1024));
+        let mut stream = TimedStream::new(stream, timeouts, abort.clone());
+        let mut body = vec![];
+        stream.read_to_end(&mut body).unwrap();
+        assert_eq!(body.len(), 4096);
+        assert_eq!(abort.status(), None);
+    }
+}
@@ -108,6 +108,7 @@ pub mod data;
 pub mod handler;
 pub mod fairing;
 pub mod error;
+pub mod websocket;
 
 // Reexport of HTTP everything.
 pub mod http {
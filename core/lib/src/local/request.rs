@@ -291,6 +291,32 @@ impl<'c> LocalRequest<'c> {
         self
     }
 
+    /// Add a [signed cookie] to this request.
+    ///
+    /// This method is only available when the `private-cookies` feature is
+    /// enabled.
+    ///
+    /// [signed cookie]: crate::http::Cookies::add_signed()
+    ///
+    /// # Examples
+    ///
+    /// Add `theme` as a signed cookie:
+    ///
+    /// ```rust
+    /// use rocket::local::Client;
+    /// use rocket::http::Cookie;
+    ///
+    /// let client = Client::new(rocket::ignite()).unwrap();
+    /// # #[allow(unused_variables)]
+    /// let req = client.get("/").signed_cookie(Cookie::new("theme", "dark"));
+    /// ```
+    #[inline]
+    #[cfg(feature = "private-cookies")]
+    pub fn signed_cookie(self, cookie: Cookie<'static>) -> Self {
+        self.request.cookies().add_original_signed(cookie);
+        self
+    }
+
     // TODO: For CGI, we want to be able to set the body to be stdin without
     // actually reading everything into a vector. Can we allow that here while
     // keeping the simplicity? Looks like it would require us to reintroduce a
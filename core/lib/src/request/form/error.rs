@@ -1,14 +1,13 @@
-use std::io;
+use std::{fmt, io};
+
 use crate::http::RawStr;
+use crate::request::form::{FormItem, from_form_field::normalize_path};
 
-/// Error returned by the [`FromForm`](crate::request::FromForm) derive on form
-/// parsing errors.
-///
-/// If multiple errors occur while parsing a form, the first error in the
-/// following precedence, from highest to lowest, is returned:
+/// Error returned by the [`FromFormField`](crate::request::FromFormField)
+/// implementations of individual fields while form items are pushed.
 ///
-///   * `BadValue` or `Unknown` in incoming form string field order
-///   * `Missing` in lexical field order
+/// The [`FromForm`](crate::request::FromForm) derive collects these errors,
+/// along with any others, into [`FormErrors`].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum FormParseError<'f> {
     /// The field named `.0` with value `.1` failed to parse or validate.
@@ -22,6 +21,236 @@ pub enum FormParseError<'f> {
     Missing(&'f RawStr),
 }
 
+/// The kind of a [`FieldError`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FieldErrorKind {
+    /// The value of the field failed to parse.
+    BadValue,
+    /// The parse was strict and the field was unexpected.
+    Unknown,
+    /// The field was expected but is missing.
+    Missing,
+    /// The value of the field parsed but failed a validation.
+    Invalid,
+}
+
+/// An error in a single field of a form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldError {
+    /// The path to the field, with components separated by `.`, as in
+    /// `address.city` or `items.0.qty`.
+    pub path: String,
+    /// The kind of error.
+    pub kind: FieldErrorKind,
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+impl FieldError {
+    /// Creates a new `FieldError` for the field at `path`, which may use
+    /// bracket syntax and percent-encoding, as in `items%5B0%5D[qty]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::{FieldError, FieldErrorKind};
+    ///
+    /// let error = FieldError::new("items[0][qty]", FieldErrorKind::Invalid, "too many");
+    /// assert_eq!(error.path, "items.0.qty");
+    /// ```
+    pub fn new<M: Into<String>>(path: &str, kind: FieldErrorKind, message: M) -> FieldError {
+        FieldError { path: normalize_path(path), kind, message: message.into() }
+    }
+}
+
+impl<'f> From<FormParseError<'f>> for FieldError {
+    fn from(error: FormParseError<'f>) -> FieldError {
+        match error {
+            FormParseError::BadValue(key, _) => {
+                FieldError::new(key, FieldErrorKind::BadValue, "invalid value")
+            }
+            FormParseError::Unknown(key, _) => {
+                FieldError::new(key, FieldErrorKind::Unknown, "unexpected field")
+            }
+            FormParseError::Missing(key) => {
+                FieldError::new(key, FieldErrorKind::Missing, "missing field")
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Error returned by the [`FromForm`](crate::request::FromForm) derive: every
+/// error in every field of a form, along with the values that were submitted.
+///
+/// Errors are ordered as follows:
+///
+///   * errors that occur while parsing values, and unexpected fields in strict
+///     parses, in incoming form string field order
+///   * missing fields and failed validations in lexical field order
+///
+/// Paths use `.` to separate components regardless of the syntax used in the
+/// submitted form, so the error for a form field named `items[0][qty]` has the
+/// path `items.0.qty`.
+///
+/// # Re-rendering Forms
+///
+/// The values that were submitted are available via [`value()`], so a form
+/// that failed to parse or validate can be displayed again with its values
+/// and errors:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use rocket::request::{Form, FormError, FormDataError};
+///
+/// #[derive(FromForm)]
+/// struct Signup {
+///     #[form(validate = len(1..64))]
+///     name: String,
+///     #[form(validate = email)]
+///     email: String,
+/// }
+///
+/// #[post("/signup", data = "<form>")]
+/// fn signup(form: Result<Form<Signup>, FormError<'_>>) -> String {
+///     match form {
+///         Ok(signup) => format!("Welcome, {}!", signup.name),
+///         Err(FormDataError::Parse(errors, _)) => format!(
+///             "name: {:?} ({}), email: {:?} ({})",
+///             errors.value("name"), errors.get("name").unwrap_or("ok"),
+///             errors.value("email"), errors.get("email").unwrap_or("ok"),
+///         ),
+///         Err(_) => "invalid form".into(),
+///     }
+/// }
+/// # fn main() { }
+/// ```
+///
+/// [`value()`]: FormErrors::value()
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FormErrors {
+    errors: Vec<FieldError>,
+    values: Vec<(String, String)>,
+}
+
+impl FormErrors {
+    /// Returns an empty collection of errors.
+    #[inline(always)]
+    pub fn new() -> FormErrors {
+        FormErrors::default()
+    }
+
+    /// Adds `error` to the collection.
+    #[inline]
+    pub fn push<E: Into<FieldError>>(&mut self, error: E) {
+        self.errors.push(error.into());
+    }
+
+    /// Returns `true` if there are no errors.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of errors.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Returns an iterator over all of the errors.
+    #[inline(always)]
+    pub fn iter(&self) -> std::slice::Iter<'_, FieldError> {
+        self.errors.iter()
+    }
+
+    /// Returns `true` if there is at least one error for the field at `path`.
+    #[inline]
+    pub fn contains(&self, path: &str) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Returns the message of the first error for the field at `path`, if
+    /// there is one.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.get_all(path).next()
+    }
+
+    /// Returns an iterator over the messages of all of the errors for the
+    /// field at `path`.
+    pub fn get_all<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.errors.iter()
+            .filter(move |error| error.path == path)
+            .map(|error| error.message.as_str())
+    }
+
+    /// Returns the decoded value that was last submitted for the field at
+    /// `path`, if any.
+    pub fn value(&self, path: &str) -> Option<&str> {
+        self.values.iter().rev()
+            .find(|(p, _)| p == path)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the paths and decoded values of all of the
+    /// fields that were submitted, in the order they were submitted.
+    pub fn values(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(path, value)| (path.as_str(), value.as_str()))
+    }
+
+    /// Records the submitted `items` as the values of the form.
+    #[doc(hidden)]
+    pub fn set_values(&mut self, items: &[FormItem<'_>]) {
+        self.values = items.iter()
+            .map(|item| (normalize_path(item.key), item.value.url_decode_lossy()))
+            .collect();
+    }
+}
+
+impl Extend<FieldError> for FormErrors {
+    fn extend<I: IntoIterator<Item = FieldError>>(&mut self, iter: I) {
+        self.errors.extend(iter)
+    }
+}
+
+impl IntoIterator for FormErrors {
+    type Item = FieldError;
+    type IntoIter = std::vec::IntoIter<FieldError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FormErrors {
+    type Item = &'a FieldError;
+    type IntoIter = std::slice::Iter<'a, FieldError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl fmt::Display for FormErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i != 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Error returned by the [`FromData`](crate::data::FromData) implementations of
 /// [`Form`](crate::request::Form) and [`LenientForm`](crate::request::LenientForm).
 #[derive(Debug)]
@@ -71,4 +300,4 @@ pub enum FormDataError<'f, E> {
 /// }
 /// # fn main() {}
 /// ```
-pub type FormError<'f> = FormDataError<'f, FormParseError<'f>>;
+pub type FormError<'f> = FormDataError<'f, FormErrors>;
@@ -1,7 +1,9 @@
+use std::io;
 use std::ops::Deref;
 
 use crate::outcome::Outcome::*;
 use crate::request::{Request, form::{FromForm, FormItems, FormDataError}};
+use crate::request::form::multipart;
 use crate::data::{Outcome, Transform, Transformed, Data, FromData};
 use crate::http::{Status, uri::{Query, FromUriParam}};
 
@@ -110,6 +112,30 @@ use crate::http::{Status, uri::{Query, FromUriParam}};
 /// [global.limits]
 /// forms = 524288
 /// ```
+///
+/// ## Multipart Forms
+///
+/// A `Form` also parses `multipart/form-data` forms, the encoding browsers use
+/// for forms with file inputs. Text fields are handled exactly as they are in
+/// URL-encoded forms, so a `FromForm` type can be parsed from either encoding.
+/// Files are streamed to temporary files on disk and can be retrieved with
+/// fields of type [`TempFile`](crate::request::TempFile).
+///
+/// Multipart forms are subject to three limits:
+///
+///   * `data-form`: the size of the entire form, which defaults to 2MiB.
+///   * `file`: the size of each file, which defaults to 1MiB.
+///   * `forms`: the total size of all text fields, as for URL-encoded forms.
+///
+/// A form exceeding any of these limits fails with a `413 Payload Too Large`,
+/// while a malformed form fails with a `400 Bad Request`. For example, to allow
+/// files of up to 64MiB in forms of up to 65MiB:
+///
+/// ```toml
+/// [global.limits]
+/// file = 67108864
+/// data-form = 68157440
+/// ```
 #[derive(Debug)]
 pub struct Form<T>(pub T);
 
@@ -147,14 +173,79 @@ impl<T> Deref for Form<T> {
     }
 }
 
+/// The buffered contents of an incoming form: a URL-encoded form string and,
+/// for multipart forms, the parts of the form, which carry its files.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct FormBuffer {
+    string: String,
+    parts: Option<Vec<multipart::Part>>,
+}
+
+impl From<String> for FormBuffer {
+    fn from(string: String) -> FormBuffer {
+        FormBuffer { string, parts: None }
+    }
+}
+
+impl From<multipart::MultipartForm> for FormBuffer {
+    fn from(form: multipart::MultipartForm) -> FormBuffer {
+        FormBuffer { string: form.string, parts: Some(form.parts) }
+    }
+}
+
+impl FormBuffer {
+    /// Returns an iterator over the items of the form. The items of the file
+    /// parts of a multipart form carry the part's file.
+    fn items(&self) -> FormItems<'_> {
+        match self.parts {
+            Some(ref parts) => {
+                let items: Vec<_> = parts.iter().map(|part| part.item(&self.string)).collect();
+                FormItems::from(items)
+            }
+            None => FormItems::from(self.string.as_str())
+        }
+    }
+
+    fn read_multipart(
+        request: &Request<'_>,
+        data: Data
+    ) -> Result<FormBuffer, (Status, io::Error)> {
+        use self::multipart::{MultipartError, MultipartLimits};
+
+        let boundary = request.content_type()
+            .and_then(|ct| ct.params().find(|&(k, _)| k.eq_ignore_ascii_case("boundary")))
+            .map(|(_, v)| v.to_string())
+            .ok_or_else(|| {
+                let error = MultipartError::Malformed("missing multipart boundary");
+                (Status::BadRequest, error.into_io())
+            })?;
+
+        let request_limits = request.limits();
+        let limits = MultipartLimits {
+            data: request_limits.get("data-form").unwrap_or(multipart::DEFAULT_DATA_FORM_LIMIT),
+            file: request_limits.get("file").unwrap_or(multipart::DEFAULT_FILE_LIMIT),
+            text: request_limits.forms,
+        };
+
+        multipart::read(data.open(), &boundary, &limits)
+            .map(FormBuffer::from)
+            .map_err(|e| {
+                error_!("The request's multipart form could not be read: {:?}", e);
+                (e.status(), e.into_io())
+            })
+    }
+}
+
 impl<'f, T: FromForm<'f>> Form<T> {
     pub(crate) fn from_data(
-        form_str: &'f str,
+        form: &'f FormBuffer,
         strict: bool
     ) -> Outcome<T, FormDataError<'f, T::Error>> {
         use self::FormDataError::*;
 
-        let mut items = FormItems::from(form_str);
+        let form_str = form.string.as_str();
+        let mut items = form.items();
         let result = T::from_form(&mut items, strict);
         if !items.exhaust() {
             error_!("The request's form string was malformed.");
@@ -173,21 +264,23 @@ impl<'f, T: FromForm<'f>> Form<T> {
 
 /// Parses a `Form` from incoming form data.
 ///
-/// If the content type of the request data is not
-/// `application/x-www-form-urlencoded`, `Forward`s the request. If the form
-/// data cannot be parsed into a `T`, a `Failure` with status code
-/// `UnprocessableEntity` is returned. If the form string is malformed, a
-/// `Failure` with status code `BadRequest` is returned. Finally, if reading the
-/// incoming stream fails, returns a `Failure` with status code
-/// `InternalServerError`. In all failure cases, the raw form string is returned
-/// if it was able to be retrieved from the incoming stream.
+/// If the content type of the request data is neither
+/// `application/x-www-form-urlencoded` nor `multipart/form-data`, `Forward`s the
+/// request. If the form data cannot be parsed into a `T`, a `Failure` with
+/// status code `UnprocessableEntity` is returned. If the form string or
+/// multipart form is malformed, a `Failure` with status code `BadRequest` is
+/// returned. If a multipart form exceeds its limits, a `Failure` with status
+/// code `PayloadTooLarge` is returned. Finally, if reading the incoming stream
+/// fails, returns a `Failure` with status code `InternalServerError`. In all
+/// failure cases, the raw form string is returned if it was able to be
+/// retrieved from the incoming stream.
 ///
 /// All relevant warnings and errors are written to the console in Rocket
 /// logging format.
 impl<'f, T: FromForm<'f>> FromData<'f> for Form<T> {
     type Error = FormDataError<'f, T::Error>;
-    type Owned = String;
-    type Borrowed = str;
+    type Owned = FormBuffer;
+    type Borrowed = FormBuffer;
 
     fn transform(
         request: &Request<'_>,
@@ -195,6 +288,13 @@ impl<'f, T: FromForm<'f>> FromData<'f> for Form<T> {
     ) -> Transform<Outcome<Self::Owned, Self::Error>> {
         use std::{cmp::min, io::Read};
 
+        if request.content_type().map_or(false, |ct| ct.is_form_data()) {
+            return match FormBuffer::read_multipart(request, data) {
+                Ok(form) => Transform::Borrowed(Success(form)),
+                Err((status, e)) => Transform::Borrowed(Failure((status, FormDataError::Io(e))))
+            };
+        }
+
         if !request.content_type().map_or(false, |ct| ct.is_form()) {
             warn_!("Form data does not have form content type.");
             return Transform::Borrowed(Forward(data))
@@ -207,7 +307,7 @@ impl<'f, T: FromForm<'f>> FromData<'f> for Form<T> {
             return Transform::Borrowed(Failure((Status::InternalServerError, FormDataError::Io(e))))
         }
 
-        Transform::Borrowed(Success(form_string))
+        Transform::Borrowed(Success(FormBuffer::from(form_string)))
     }
 
     fn from_data(_: &Request<'_>, o: Transformed<'f, Self>) -> Outcome<Self, Self::Error> {
@@ -1,6 +1,7 @@
 use memchr::memchr2;
 
 use crate::http::RawStr;
+use crate::request::form::FormFile;
 
 /// Iterator over the key/value pairs of a given HTTP form string.
 ///
@@ -113,6 +114,10 @@ pub enum FormItems<'f> {
     Cooked {
         items: &'f [FormItem<'f>],
         next_index: usize
+    },
+    #[doc(hidden)]
+    Owned {
+        items: std::vec::IntoIter<FormItem<'f>>
     }
 }
 
@@ -132,7 +137,11 @@ pub struct FormItem<'f> {
     /// **Note:** The value is _not_ URL decoded. To URL decode the raw strings,
     /// use the [`RawStr::url_decode()`] method or access key-value pairs with
     /// [`key_value_decoded()`](FormItem::key_value_decoded()).
-    pub value: &'f RawStr
+    pub value: &'f RawStr,
+    /// The uploaded file, if the item is a file part of a `multipart/form-data`
+    /// form. The value of such an item is the file's name as sent by the
+    /// client, if any.
+    pub file: Option<&'f FormFile>,
 }
 
 impl<'f> FormItem<'f> {
@@ -149,6 +158,7 @@ impl<'f> FormItem<'f> {
     ///     raw: "hello=%2C+world%21".into(),
     ///     key: "hello".into(),
     ///     value: "%2C+world%21".into(),
+    ///     file: None,
     /// };
     ///
     /// let (key, value) = item.key_value();
@@ -174,6 +184,7 @@ impl<'f> FormItem<'f> {
     ///     raw: "hello=%2C+world%21".into(),
     ///     key: "hello".into(),
     ///     value: "%2C+world%21".into(),
+    ///     file: None,
     /// };
     ///
     /// let (key, value) = item.key_value_decoded();
@@ -198,6 +209,7 @@ impl<'f> FormItem<'f> {
     ///     raw: "hello=%2C+world%21".into(),
     ///     key: "hello".into(),
     ///     value: "%2C+world%21".into(),
+    ///     file: None,
     /// };
     ///
     /// let (raw, key, value) = item.explode();
@@ -246,6 +258,7 @@ impl FormItems<'_> {
         match self {
             FormItems::Raw { string, next_index } => *next_index >= string.len(),
             FormItems::Cooked { items, next_index } => *next_index >= items.len(),
+            FormItems::Owned { items } => items.as_slice().is_empty(),
         }
     }
 
@@ -293,6 +306,7 @@ impl FormItems<'_> {
         match self {
             FormItems::Raw { string, ref mut next_index } => *next_index = string.len(),
             FormItems::Cooked { items, ref mut next_index } => *next_index = items.len(),
+            FormItems::Owned { ref mut items } => *items = vec![].into_iter(),
         }
     }
 }
@@ -318,6 +332,13 @@ impl<'f> From<&'f [FormItem<'f>]> for FormItems<'f> {
     }
 }
 
+impl<'f> From<Vec<FormItem<'f>>> for FormItems<'f> {
+    #[inline(always)]
+    fn from(items: Vec<FormItem<'f>>) -> FormItems<'f> {
+        FormItems::Owned { items: items.into_iter() }
+    }
+}
+
 fn raw<'f>(string: &mut &'f RawStr, index: &mut usize) -> Option<FormItem<'f>> {
     loop {
         let start = *index;
@@ -345,7 +366,8 @@ fn raw<'f>(string: &mut &'f RawStr, index: &mut usize) -> Option<FormItem<'f>> {
             _ => return Some(FormItem {
                 raw: raw.into(),
                 key: key.into(),
-                value: value.into()
+                value: value.into(),
+                file: None
             })
         }
     }
@@ -368,6 +390,7 @@ impl<'f> Iterator for FormItems<'f> {
                     None
                 }
             }
+            FormItems::Owned { ref mut items } => items.next(),
         }
     }
 }
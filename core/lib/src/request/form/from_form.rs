@@ -7,10 +7,16 @@ use crate::request::FormItems;
 ///
 /// This trait can be automatically derived. When deriving `FromForm`, every
 /// field in the structure must implement
-/// [`FromFormValue`](crate::request::FromFormValue). Rocket validates each field in
-/// the structure by calling its `FromFormValue` implementation. You may wish to
-/// implement `FromFormValue` for your own types for custom, automatic
-/// validation.
+/// [`FromFormField`](crate::request::FromFormField), which is implemented for
+/// all types that implement [`FromFormValue`](crate::request::FromFormValue).
+/// Rocket validates each such field in the structure by calling its
+/// `FromFormValue` implementation. You may wish to implement `FromFormValue`
+/// for your own types for custom, automatic validation.
+///
+/// Fields may also be structures that derive `FromForm`, as well as `Vec` and
+/// `HashMap` collections of fields, which parse from keys such as
+/// `address.city`, `tags[]`, or `items[0].qty`. See
+/// [`FromFormField`](crate::request::FromFormField) for details.
 ///
 /// ```rust
 /// # #![feature(proc_macro_hygiene)]
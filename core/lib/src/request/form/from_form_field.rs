@@ -0,0 +1,396 @@
+use std::hash::Hash;
+use std::collections::HashMap;
+
+use crate::http::RawStr;
+use crate::request::form::{FormItem, FromFormValue, FormParseError};
+use crate::request::form::{FormErrors, FieldError, FieldErrorKind};
+
+/// Trait implemented by types that can be fields of a structure deriving
+/// [`FromForm`](crate::request::FromForm).
+///
+/// A field parses from all of the form items whose keys begin with the field's
+/// name. Keys are paths: the name of a field may be followed by further
+/// components, each either separated by a dot or enclosed in brackets. The
+/// following keys are all equivalent and refer to the `city` field of the
+/// `address` field:
+///
+/// ```text
+/// address.city=Paris
+/// address[city]=Paris
+/// ```
+///
+/// Brackets may also be percent-encoded as `%5B` and `%5D`, as they are by
+/// browsers. `FromFormField` should never need to be implemented directly:
+/// Rocket implements it for the following types.
+///
+///   * **T** _where_ **T: FromFormValue**
+///
+///     The field parses from the value of the last item whose key is exactly
+///     the field's name. If there is no such item, the value is
+///     [`FromFormValue::default()`]. Items with a longer key are unexpected.
+///
+///   * Structures deriving `FromForm`
+///
+///     Each field of the structure parses from the items whose keys continue
+///     with the field's name. For instance, `address.city` is a field named
+///     `city` within the field named `address`.
+///
+///   * **Vec&lt;T>** _where_ **T: FromFormField**
+///
+///     The component following the field's name is an index. Items with the
+///     same index belong to the same element. Items without an index, such as
+///     `tags=a` or `tags[]=a`, each belong to a new element. Elements appear in
+///     the order their first item appears in, or ordered by index if every
+///     index is a number. The vector is empty if there are no items.
+///
+///   * **HashMap&lt;K, V>** _where_ **K: FromFormValue, V: FromFormField**
+///
+///     The component following the field's name is the key of an entry, which
+///     is parsed with `K`'s `FromFormValue` implementation. Items with the same
+///     key belong to the same entry. The map is empty if there are no items.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::collections::HashMap;
+///
+/// use rocket::request::{FormItems, FromForm};
+///
+/// #[derive(FromForm)]
+/// struct Item {
+///     name: String,
+///     qty: usize,
+/// }
+///
+/// #[derive(FromForm)]
+/// struct Order {
+///     tags: Vec<String>,
+///     items: Vec<Item>,
+///     notes: HashMap<String, String>,
+/// }
+///
+/// let form = "tags=a&tags[]=b&items[0].name=Apple&items[0].qty=3&\
+///             items[1][name]=Pear&items[1][qty]=1&notes.gift=yes";
+///
+/// let order = Order::from_form(&mut FormItems::from(form), true).unwrap();
+/// assert_eq!(order.tags, vec!["a", "b"]);
+/// assert_eq!(order.items[0].name, "Apple");
+/// assert_eq!(order.items[1].qty, 1);
+/// assert_eq!(order.notes["gift"], "yes");
+/// ```
+pub trait FromFormField<'f>: Sized {
+    /// The state accumulated while parsing.
+    type Context;
+
+    /// Returns the state before any items have been pushed.
+    fn init() -> Self::Context;
+
+    /// Adds `item` to the state in `ctxt`. `key` is the part of `item.key` that
+    /// follows the field's name and any separating `.`.
+    ///
+    /// Items that are unexpected are an error when `strict` is `true` and are
+    /// ignored otherwise. An item that results in an error is not added.
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>>;
+
+    /// Returns the parsed value, or `None` if the field is missing. Returns
+    /// every error that occurs if the value can't be produced.
+    fn finalize(ctxt: Self::Context) -> Result<Option<Self>, FormErrors>;
+
+    /// Returns the value of the field when it is missing, if there is one. A
+    /// `#[form(default)]` attribute on the field takes precedence.
+    ///
+    /// The default implementation returns `None`.
+    #[inline(always)]
+    fn default() -> Option<Self> {
+        None
+    }
+}
+
+// Returns the index of a bracket in `key` if it begins with one, accepting
+// percent-encoded brackets, along with the length of the bracket.
+fn bracket(key: &str, open: bool) -> Option<usize> {
+    let (raw, encoded) = match open {
+        true => ("[", "%5b"),
+        false => ("]", "%5d"),
+    };
+
+    if key.starts_with(raw) {
+        Some(1)
+    } else if key.len() >= 3 && key.as_bytes()[..3].eq_ignore_ascii_case(encoded.as_bytes()) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+fn find_bracket(key: &str, open: bool) -> Option<(usize, usize)> {
+    key.char_indices()
+        .filter(|&(_, c)| c == '[' || c == ']' || c == '%')
+        .filter_map(|(i, _)| bracket(&key[i..], open).map(|len| (i, len)))
+        .next()
+}
+
+/// If `key` is `name`, optionally followed by a dot or bracketed component,
+/// returns the remainder of `key` following `name` and any separating `.`.
+/// The name may itself be enclosed in brackets, as in `[name].rest`.
+#[doc(hidden)]
+pub fn strip_field_name<'f>(key: &'f RawStr, name: &str) -> Option<&'f RawStr> {
+    let key = key.as_str();
+    let rest = match bracket(key, true) {
+        Some(open) if key[open..].starts_with(name) => {
+            let close = bracket(&key[(open + name.len())..], false)?;
+            &key[(open + name.len() + close)..]
+        }
+        _ if key.starts_with(name) => &key[name.len()..],
+        _ => return None
+    };
+
+    if rest.is_empty() || bracket(rest, true).is_some() {
+        Some(rest.into())
+    } else if rest.starts_with('.') {
+        Some(rest[1..].into())
+    } else {
+        None
+    }
+}
+
+/// Splits `key` into its first component and the remainder, without any
+/// separating `.`. The first component of `[a].b` and `a.b` is `a`.
+#[doc(hidden)]
+pub fn split_form_key(key: &RawStr) -> (&RawStr, &RawStr) {
+    let key = key.as_str();
+    let (head, rest) = match bracket(key, true) {
+        Some(open) => match find_bracket(&key[open..], false) {
+            Some((i, close)) => (&key[open..(open + i)], &key[(open + i + close)..]),
+            None => (&key[open..], "")
+        },
+        None => {
+            let dot = key.find('.');
+            let open = find_bracket(key, true).map(|(i, _)| i);
+            match (dot, open) {
+                (Some(d), Some(o)) if o < d => (&key[..o], &key[o..]),
+                (Some(d), _) => (&key[..d], &key[(d + 1)..]),
+                (None, Some(o)) => (&key[..o], &key[o..]),
+                (None, None) => (key, "")
+            }
+        }
+    };
+
+    let rest = match rest.starts_with('.') {
+        true => &rest[1..],
+        false => rest
+    };
+
+    (head.into(), rest.into())
+}
+
+/// Returns the percent-decoded components of `key` separated by `.`, so that
+/// `items%5B0%5D[name]` and `items.0.name` are both `items.0.name`.
+pub(crate) fn normalize_path(key: &str) -> String {
+    let mut path = String::new();
+    let mut key = RawStr::from_str(key);
+    while !key.is_empty() {
+        let (head, rest) = split_form_key(key);
+        if !head.is_empty() {
+            if !path.is_empty() {
+                path.push('.');
+            }
+
+            path.push_str(&head.url_decode_lossy());
+        }
+
+        key = rest;
+    }
+
+    path
+}
+
+/// Returns the path to the field `name` of a structure whose own path, in the
+/// form's syntax, is `prefix`.
+#[doc(hidden)]
+pub fn field_path(prefix: &str, name: &str) -> String {
+    match prefix.is_empty() {
+        true => name.to_string(),
+        false => format!("{}.{}", prefix, name),
+    }
+}
+
+impl<'f, T: FromFormValue<'f>> FromFormField<'f> for T {
+    type Context = Option<T>;
+
+    #[inline(always)]
+    fn init() -> Self::Context {
+        None
+    }
+
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>> {
+        if !key.is_empty() {
+            return match strict {
+                true => Err(FormParseError::Unknown(item.key, item.value)),
+                false => Ok(())
+            };
+        }
+
+        let value = T::from_form_value(item.value)
+            .map_err(|_| FormParseError::BadValue(item.key, item.value))?;
+
+        *ctxt = Some(value);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn finalize(ctxt: Self::Context) -> Result<Option<Self>, FormErrors> {
+        Ok(ctxt)
+    }
+
+    #[inline(always)]
+    fn default() -> Option<Self> {
+        <T as FromFormValue<'f>>::default()
+    }
+}
+
+impl<'f, T: FromFormField<'f>> FromFormField<'f> for Vec<T> {
+    type Context = Vec<(Option<&'f RawStr>, T::Context)>;
+
+    #[inline(always)]
+    fn init() -> Self::Context {
+        vec![]
+    }
+
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>> {
+        let (index, rest) = split_form_key(key);
+        let element = match index.is_empty() {
+            true => None,
+            false => ctxt.iter().position(|(i, _)| *i == Some(index))
+        };
+
+        let element = match element {
+            Some(element) => element,
+            None => {
+                let index = Some(index).filter(|i| !i.is_empty());
+                ctxt.push((index, T::init()));
+                ctxt.len() - 1
+            }
+        };
+
+        T::push(&mut ctxt[element].1, rest, item, strict)
+    }
+
+    fn finalize(mut ctxt: Self::Context) -> Result<Option<Self>, FormErrors> {
+        if ctxt.is_empty() {
+            return Ok(None);
+        }
+
+        let numeric = |i: &Option<&RawStr>| i.and_then(|i| i.as_str().parse::<usize>().ok());
+        if ctxt.iter().all(|(i, _)| numeric(i).is_some()) {
+            ctxt.sort_by_key(|(i, _)| numeric(i));
+        }
+
+        let mut values = Vec::with_capacity(ctxt.len());
+        let mut errors = FormErrors::new();
+        for (_, element) in ctxt {
+            match T::finalize(element).map(|v| v.or_else(<T as FromFormField<'f>>::default)) {
+                Ok(Some(value)) => values.push(value),
+                Ok(None) => { /* an element without a value is skipped */ }
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(Some(values)),
+            false => Err(errors)
+        }
+    }
+
+    #[inline(always)]
+    fn default() -> Option<Self> {
+        Some(vec![])
+    }
+}
+
+impl<'f, K, V> FromFormField<'f> for HashMap<K, V>
+    where K: FromFormValue<'f> + Eq + Hash, V: FromFormField<'f>
+{
+    type Context = Vec<(&'f str, &'f RawStr, V::Context)>;
+
+    #[inline(always)]
+    fn init() -> Self::Context {
+        vec![]
+    }
+
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>> {
+        let (map_key, rest) = split_form_key(key);
+        if map_key.is_empty() {
+            return match strict {
+                true => Err(FormParseError::Unknown(item.key, item.value)),
+                false => Ok(())
+            };
+        }
+
+        let entry = match ctxt.iter().position(|(_, k, _)| *k == map_key) {
+            Some(entry) => entry,
+            None => {
+                let path = &item.key.as_str()[..(item.key.len() - rest.len())];
+                ctxt.push((path, map_key, V::init()));
+                ctxt.len() - 1
+            }
+        };
+
+        V::push(&mut ctxt[entry].2, rest, item, strict)
+    }
+
+    fn finalize(ctxt: Self::Context) -> Result<Option<Self>, FormErrors> {
+        if ctxt.is_empty() {
+            return Ok(None);
+        }
+
+        let mut map = HashMap::with_capacity(ctxt.len());
+        let mut errors = FormErrors::new();
+        for (path, key, entry) in ctxt {
+            let key = match K::from_form_value(key) {
+                Ok(key) => key,
+                Err(_) => {
+                    errors.push(FieldError::new(path, FieldErrorKind::BadValue, "invalid key"));
+                    continue;
+                }
+            };
+
+            match V::finalize(entry).map(|v| v.or_else(<V as FromFormField<'f>>::default)) {
+                Ok(Some(value)) => { map.insert(key, value); }
+                Ok(None) => { /* an entry without a value is skipped */ }
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(Some(map)),
+            false => Err(errors)
+        }
+    }
+
+    #[inline(always)]
+    fn default() -> Option<Self> {
+        Some(HashMap::new())
+    }
+}
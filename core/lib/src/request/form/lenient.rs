@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use crate::request::{Request, form::{Form, FormDataError, FromForm}};
+use crate::request::{Request, form::{Form, FormBuffer, FormDataError, FromForm}};
 use crate::data::{Data, Transform, Transformed, FromData, Outcome};
 use crate::http::uri::{Query, FromUriParam};
 
@@ -97,8 +97,8 @@ impl<T> Deref for LenientForm<T> {
 
 impl<'f, T: FromForm<'f>> FromData<'f> for LenientForm<T> {
     type Error = FormDataError<'f, T::Error>;
-    type Owned = String;
-    type Borrowed = str;
+    type Owned = FormBuffer;
+    type Borrowed = FormBuffer;
 
     fn transform(r: &Request<'_>, d: Data) -> Transform<Outcome<Self::Owned, Self::Error>> {
         <Form<T>>::transform(r, d)
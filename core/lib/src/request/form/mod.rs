@@ -3,13 +3,21 @@
 mod form_items;
 mod from_form;
 mod from_form_value;
+mod from_form_field;
 mod lenient;
 mod error;
 mod form;
+mod multipart;
+pub mod validate;
+mod temp_file;
 
 pub use self::form_items::{FormItems, FormItem};
 pub use self::from_form::FromForm;
 pub use self::from_form_value::FromFormValue;
-pub use self::form::Form;
+pub use self::from_form_field::FromFormField;
+#[doc(hidden)] pub use self::from_form_field::{strip_field_name, split_form_key, field_path};
+pub use self::form::{Form, FormBuffer};
+pub use self::temp_file::{TempFile, FormFile};
 pub use self::lenient::LenientForm;
 pub use self::error::{FormError, FormParseError, FormDataError};
+pub use self::error::{FormErrors, FieldError, FieldErrorKind};
@@ -0,0 +1,385 @@
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+
+use memchr::memchr;
+
+use crate::http::{ContentType, RawStr, Status};
+use crate::request::form::{FormItem, FormFile, TempFile};
+
+/// Default limit for the total size of a `multipart/form-data` form.
+pub(crate) const DEFAULT_DATA_FORM_LIMIT: u64 = 2 * 1024 * 1024;
+
+/// Default limit for the size of each file in a `multipart/form-data` form.
+pub(crate) const DEFAULT_FILE_LIMIT: u64 = 1024 * 1024;
+
+/// Maximum size of the headers of a single part.
+const MAX_HEADER_BYTES: u64 = 8 * 1024;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// The limits that apply while reading a multipart form.
+pub(crate) struct MultipartLimits {
+    /// Limit on the total size of the form, including files.
+    pub data: u64,
+    /// Limit on the size of each file.
+    pub file: u64,
+    /// Limit on the total size of all non-file fields.
+    pub text: u64,
+}
+
+#[derive(Debug)]
+pub(crate) enum MultipartError {
+    Io(io::Error),
+    Malformed(&'static str),
+    TooLarge(&'static str),
+}
+
+impl MultipartError {
+    pub(crate) fn status(&self) -> Status {
+        match self {
+            MultipartError::Io(_) => Status::InternalServerError,
+            MultipartError::Malformed(_) => Status::BadRequest,
+            MultipartError::TooLarge(_) => Status::PayloadTooLarge,
+        }
+    }
+
+    pub(crate) fn into_io(self) -> io::Error {
+        match self {
+            MultipartError::Io(e) => e,
+            MultipartError::Malformed(msg) => io::Error::new(io::ErrorKind::InvalidData, msg),
+            MultipartError::TooLarge(limit) => {
+                let msg = format!("multipart form exceeds the '{}' limit", limit);
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for MultipartError {
+    fn from(error: io::Error) -> MultipartError {
+        MultipartError::Io(error)
+    }
+}
+
+/// A multipart form converted to a URL-encoded form string, with one `Part`
+/// for each part of the form.
+#[derive(Debug)]
+pub(crate) struct MultipartForm {
+    pub string: String,
+    pub parts: Vec<Part>,
+}
+
+/// A part of a multipart form: the ranges of its key and value in the form
+/// string and, if it is a file part, its file.
+#[derive(Debug)]
+pub(crate) struct Part {
+    key: Range<usize>,
+    value: Range<usize>,
+    file: Option<FormFile>,
+}
+
+impl Part {
+    /// Returns the form item for this part of the form with string `string`.
+    pub(crate) fn item<'f>(&'f self, string: &'f str) -> FormItem<'f> {
+        FormItem {
+            raw: string[self.key.start..self.value.end].into(),
+            key: string[self.key.clone()].into(),
+            value: string[self.value.clone()].into(),
+            file: self.file.as_ref(),
+        }
+    }
+}
+
+/// A reader over the body of a multipart form that tracks the form size limit.
+struct PartReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    read: u64,
+    limit: u64,
+    eof: bool,
+}
+
+impl<R: Read> PartReader<R> {
+    fn new(inner: R, limit: u64) -> PartReader<R> {
+        // The first boundary may not be preceded by a CRLF. Adding one lets us
+        // treat it like every other boundary.
+        PartReader { inner, buf: b"\r\n".to_vec(), pos: 0, read: 0, limit, eof: false }
+    }
+
+    #[inline(always)]
+    fn available(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Reads more data into the buffer. Returns `false` if there is no more.
+    fn fill(&mut self) -> Result<bool, MultipartError> {
+        if self.eof {
+            return Ok(false);
+        }
+
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+
+        let mut chunk = [0u8; CHUNK_SIZE];
+        let n = loop {
+            match self.inner.read(&mut chunk) {
+                Ok(n) => break n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        self.read += n as u64;
+        if self.read > self.limit {
+            return Err(MultipartError::TooLarge("data-form"));
+        }
+
+        self.eof = n == 0;
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(n > 0)
+    }
+
+    /// Ensures at least `n` bytes are available unless the body ends first.
+    fn ensure(&mut self, n: usize) -> Result<bool, MultipartError> {
+        while self.available().len() < n {
+            if !self.fill()? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Consumes `bytes` if the remaining data starts with them.
+    fn consume(&mut self, bytes: &[u8]) -> Result<bool, MultipartError> {
+        if self.ensure(bytes.len())? && self.available().starts_with(bytes) {
+            self.pos += bytes.len();
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Copies all data up to the next `delim` into `sink`, consuming the
+    /// delimiter. Fails with `TooLarge(limit_name)` if more than `limit` bytes
+    /// precede the delimiter. Returns the number of bytes copied.
+    fn copy_until<W: Write>(
+        &mut self,
+        delim: &[u8],
+        sink: &mut W,
+        limit: u64,
+        limit_name: &'static str
+    ) -> Result<u64, MultipartError> {
+        let mut written = 0;
+        loop {
+            let (copy, consumed, done) = match find(self.available(), delim) {
+                Some(i) => (i, i + delim.len(), true),
+                None => {
+                    // Everything but a possible delimiter prefix can be copied.
+                    let safe = self.available().len().saturating_sub(delim.len() - 1);
+                    (safe, safe, false)
+                }
+            };
+
+            written += copy as u64;
+            if written > limit {
+                return Err(MultipartError::TooLarge(limit_name));
+            }
+
+            sink.write_all(&self.available()[..copy])?;
+            self.pos += consumed;
+            if done {
+                return Ok(written);
+            }
+
+            if !self.fill()? {
+                return Err(MultipartError::Malformed("multipart form ended unexpectedly"));
+            }
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let mut start = 0;
+    while let Some(i) = memchr(needle[0], &haystack[start..]) {
+        let i = start + i;
+        if haystack.len() - i < needle.len() {
+            return None;
+        }
+
+        if &haystack[i..(i + needle.len())] == needle {
+            return Some(i);
+        }
+
+        start = i + 1;
+    }
+
+    None
+}
+
+/// Appends `bytes` to `string`, percent-encoding everything but unreserved
+/// characters so that the result is a valid URL-encoded form key or value.
+fn encode_into(string: &mut String, bytes: &[u8]) {
+    for &byte in bytes {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                string.push(byte as char)
+            }
+            _ => { let _ = write!(string, "%{:02X}", byte); }
+        }
+    }
+}
+
+/// A parsed `Content-Disposition: form-data` header.
+struct Disposition {
+    name: String,
+    file_name: Option<String>,
+}
+
+/// Splits `input` at the first unquoted `;`, unquoting the first half.
+fn param_value(input: &str) -> (String, &str) {
+    let input = input.trim_start();
+    if !input.starts_with('"') {
+        return match input.find(';') {
+            Some(i) => (input[..i].trim().to_string(), &input[i..]),
+            None => (input.trim().to_string(), "")
+        };
+    }
+
+    let mut value = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (value, &input[(i + 1)..]),
+            '\\' => if let Some((_, c)) = chars.next() { value.push(c) },
+            c => value.push(c),
+        }
+    }
+
+    (value, "")
+}
+
+fn parse_disposition(header: &str) -> Option<Disposition> {
+    let mut rest = match header.find(';') {
+        Some(i) if header[..i].trim().eq_ignore_ascii_case("form-data") => &header[i..],
+        _ => return None
+    };
+
+    let (mut name, mut file_name, mut ext_file_name) = (None, None, None);
+    while rest.starts_with(';') {
+        let param = &rest[1..];
+        let eq = param.find('=')?;
+        let (value, remaining) = param_value(&param[(eq + 1)..]);
+        match param[..eq].trim().to_ascii_lowercase().as_str() {
+            "name" => name = Some(value),
+            "filename" => file_name = Some(value),
+            "filename*" => {
+                // RFC 5987: charset'language'percent-encoded-value
+                let encoded = value.splitn(3, '\'').nth(2).unwrap_or("");
+                let decoded = RawStr::from_str(encoded).percent_decode_lossy();
+                ext_file_name = Some(decoded.into_owned());
+            }
+            _ => { /* ignore unknown parameters */ }
+        }
+
+        rest = remaining.trim_start();
+    }
+
+    Some(Disposition { name: name?, file_name: ext_file_name.or(file_name) })
+}
+
+/// Returns the final component of `name`, if it is a usable file name.
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(|c| c == '/' || c == '\\').next().unwrap_or("").trim();
+    match name {
+        "" | "." | ".." => None,
+        name => Some(name.to_string())
+    }
+}
+
+/// Reads the `multipart/form-data` form with boundary `boundary` from `stream`,
+/// streaming files to temporary files.
+pub(crate) fn read<R: Read>(
+    stream: R,
+    boundary: &str,
+    limits: &MultipartLimits
+) -> Result<MultipartForm, MultipartError> {
+    let delimiter = format!("\r\n--{}", boundary).into_bytes();
+    let mut reader = PartReader::new(stream, limits.data);
+    let mut form = MultipartForm { string: String::new(), parts: vec![] };
+    let mut text_len = 0;
+
+    // Skip the preamble.
+    reader.copy_until(&delimiter, &mut io::sink(), limits.data, "data-form")?;
+    loop {
+        if reader.consume(b"--")? {
+            break;
+        }
+
+        while reader.consume(b" ")? || reader.consume(b"\t")? {  }
+        if !reader.ensure(2)? || !reader.available().starts_with(b"\r\n") {
+            return Err(MultipartError::Malformed("invalid multipart boundary"));
+        }
+
+        // The CRLF ending the boundary line is left in place so that a part
+        // without any headers is found by the search for a blank line.
+        let mut head = vec![];
+        reader.copy_until(b"\r\n\r\n", &mut head, MAX_HEADER_BYTES, "multipart headers")?;
+        let head = String::from_utf8_lossy(&head);
+
+        let (mut disposition, mut content_type) = (None, None);
+        for line in head.split("\r\n").filter(|line| !line.is_empty()) {
+            let colon = line.find(':')
+                .ok_or(MultipartError::Malformed("invalid multipart header"))?;
+
+            let (name, value) = (line[..colon].trim(), line[(colon + 1)..].trim());
+            if name.eq_ignore_ascii_case("Content-Disposition") {
+                disposition = parse_disposition(value);
+            } else if name.eq_ignore_ascii_case("Content-Type") {
+                content_type = ContentType::parse_flexible(value);
+            }
+        }
+
+        let disposition = disposition
+            .ok_or(MultipartError::Malformed("missing or invalid multipart Content-Disposition"))?;
+
+        if !form.string.is_empty() {
+            form.string.push('&');
+        }
+
+        let key_start = form.string.len();
+        encode_into(&mut form.string, disposition.name.as_bytes());
+        let key = key_start..form.string.len();
+        form.string.push('=');
+
+        let value_start = form.string.len();
+        let file = match disposition.file_name {
+            Some(file_name) => {
+                let file_name = sanitize_file_name(&file_name);
+                let (mut temp_file, mut file) = TempFile::create(file_name.clone(), content_type)?;
+                let len = reader.copy_until(&delimiter, &mut file, limits.file, "file")?;
+                file.flush()?;
+                temp_file.set_len(len);
+
+                encode_into(&mut form.string, file_name.unwrap_or_default().as_bytes());
+                Some(FormFile::new(temp_file))
+            }
+            None => {
+                let mut value = vec![];
+                let limit = limits.text.saturating_sub(text_len);
+                text_len += reader.copy_until(&delimiter, &mut value, limit, "forms")?;
+                encode_into(&mut form.string, &value);
+                None
+            }
+        };
+
+        let value = value_start..form.string.len();
+        form.parts.push(Part { key, value, file });
+    }
+
+    Ok(form)
+}
@@ -0,0 +1,338 @@
+use std::{fmt, fs, io};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::http::{RawStr, ContentType};
+use crate::request::form::{FormItem, FromFormField, FormParseError, FormErrors};
+
+/// A file uploaded in a `multipart/form-data` form.
+///
+/// A `TempFile` is streamed to a temporary file on disk as the form is read,
+/// so uploads are never buffered in memory. The temporary file is deleted when
+/// the `TempFile` is dropped unless it has been moved to a permanent location
+/// with [`persist_to()`](TempFile::persist_to()).
+///
+/// `TempFile` implements [`FromFormField`], so it can be used as the type of a
+/// field in a structure deriving [`FromForm`](crate::request::FromForm). Such a
+/// field only parses from the file parts of a `multipart/form-data` form, whose
+/// items carry a [`FormFile`]; it fails to parse from any other field,
+/// including every field of an `application/x-www-form-urlencoded` form. To
+/// make an upload optional, use `Option<TempFile>`, which is also `None` when a
+/// browser submits a file input with no file selected. A `Vec<TempFile>`
+/// collects the files of a multiple file input.
+///
+/// The size of each file is limited by the `file` limit, which defaults to
+/// 1MiB. See [`Form`](crate::request::Form#multipart-forms) for details.
+///
+/// # Example
+///
+/// The file is stored under the unique name of its temporary file, which the
+/// server chose, rather than under the name sent by the client, which could
+/// collide with or overwrite existing files. The client's name is only kept as
+/// metadata:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use std::io;
+/// use std::path::Path;
+///
+/// use rocket::request::{Form, TempFile};
+///
+/// #[derive(FromForm)]
+/// struct Upload {
+///     description: String,
+///     file: TempFile,
+/// }
+///
+/// #[post("/upload", data = "<upload>")]
+/// fn upload(upload: Form<Upload>) -> io::Result<String> {
+///     let mut upload = upload.into_inner();
+///     let id = upload.file.path().file_name().expect("temporary file name").to_owned();
+///     let client_name = upload.file.file_name().unwrap_or("upload").to_string();
+///     upload.file.persist_to(Path::new("uploads").join(&id))?;
+///     Ok(format!("Stored '{}' ({}, {} bytes) as {:?}.", upload.description,
+///         client_name, upload.file.len(), id))
+/// }
+/// # fn main() { }
+/// ```
+pub struct TempFile {
+    path: PathBuf,
+    file_name: Option<String>,
+    content_type: Option<ContentType>,
+    len: u64,
+    persisted: bool,
+}
+
+impl TempFile {
+    /// Creates a new, empty temporary file in the system's temporary directory.
+    pub(crate) fn create(
+        file_name: Option<String>,
+        content_type: Option<ContentType>
+    ) -> io::Result<(TempFile, File)> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let name = format!("rocket-upload-{}-{}-{}", std::process::id(), n, nanos);
+            let path = std::env::temp_dir().join(name);
+
+            // `create_new` ensures we never write through an existing file.
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    let temp = TempFile { path, file_name, content_type, len: 0, persisted: false };
+                    return Ok((temp, file));
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_len(&mut self, len: u64) {
+        self.len = len;
+    }
+
+    /// Returns the current path to the file: the temporary path until the file
+    /// is persisted and the path it was persisted to afterwards.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name of the file as sent by the client, if any.
+    ///
+    /// Only the final component of the name is returned, so the name never
+    /// contains a `/` or `\`, and names that are empty, `.`, or `..` are
+    /// returned as `None`. The name is still entirely under the client's
+    /// control, so keep it only as metadata: store the file under a name the
+    /// server chooses, such as the name of its temporary file.
+    #[inline(always)]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_ref().map(|name| name.as_str())
+    }
+
+    /// Returns the Content-Type of the file as sent by the client, if any.
+    #[inline(always)]
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    /// Returns the size of the file in bytes.
+    #[inline(always)]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the file is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Opens the file for reading.
+    pub fn open(&self) -> io::Result<File> {
+        File::open(&self.path)
+    }
+
+    /// Moves the file to `path`, replacing any existing file, so that it is no
+    /// longer deleted when `self` is dropped. Afterwards,
+    /// [`path()`](TempFile::path()) returns `path`.
+    ///
+    /// The file is renamed when possible and copied otherwise, for instance when
+    /// `path` is on a different file system than the temporary file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could neither be renamed nor copied. The
+    /// temporary file is left in place.
+    pub fn persist_to<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if fs::rename(&self.path, path).is_err() {
+            fs::copy(&self.path, path)?;
+            let _ = fs::remove_file(&self.path);
+        }
+
+        self.path = path.to_path_buf();
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TempFile")
+            .field("path", &self.path)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// The file of a file part in a `multipart/form-data` form.
+///
+/// The [`FormItem`] of each file part refers to the part's `FormFile` via its
+/// [`file`](FormItem::file) field. The file can be taken from it once, by the
+/// first field that claims it; the [`FromFormField`] implementation of
+/// [`TempFile`] does so automatically. Files that are never taken are deleted
+/// once the form has been parsed.
+///
+/// A manual [`FromForm`](crate::request::FromForm) implementation that wants a
+/// file takes it from the item directly:
+///
+/// ```rust
+/// use rocket::request::{FromForm, FormItems, TempFile};
+///
+/// struct Avatar(TempFile);
+///
+/// impl<'f> FromForm<'f> for Avatar {
+///     type Error = ();
+///
+///     fn from_form(items: &mut FormItems<'f>, _: bool) -> Result<Avatar, ()> {
+///         items.filter(|item| item.key == "avatar")
+///             .filter_map(|item| item.file.and_then(|file| file.take()))
+///             .next()
+///             .map(Avatar)
+///             .ok_or(())
+///     }
+/// }
+/// ```
+pub struct FormFile(Mutex<Option<TempFile>>);
+
+impl FormFile {
+    #[inline(always)]
+    pub(crate) fn new(file: TempFile) -> FormFile {
+        FormFile(Mutex::new(Some(file)))
+    }
+
+    /// Takes the file, leaving nothing in its place. Returns `None` if the
+    /// file has already been taken.
+    pub fn take(&self) -> Option<TempFile> {
+        match self.0.lock() {
+            Ok(mut file) => file.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        }
+    }
+}
+
+/// Two `FormFile`s are equal only if they are the same part's file.
+impl PartialEq for FormFile {
+    #[inline(always)]
+    fn eq(&self, other: &FormFile) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for FormFile {  }
+
+impl fmt::Debug for FormFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.lock() {
+            Ok(file) => f.debug_tuple("FormFile").field(&*file).finish(),
+            Err(_) => f.debug_tuple("FormFile").field(&"<poisoned>").finish(),
+        }
+    }
+}
+
+/// Returns the file of `item` if it is the entire value of a field: an item
+/// whose key is the field's name and that carries a file that was selected.
+fn claim<'f>(
+    key: &'f RawStr,
+    item: FormItem<'f>,
+    strict: bool
+) -> Result<Option<TempFile>, FormParseError<'f>> {
+    if !key.is_empty() {
+        return match strict {
+            true => Err(FormParseError::Unknown(item.key, item.value)),
+            false => Ok(None)
+        };
+    }
+
+    match item.file.and_then(|file| file.take()) {
+        // Browsers send an empty, unnamed file when none is selected.
+        Some(ref file) if file.file_name.is_none() && file.is_empty() => Ok(None),
+        file => Ok(file)
+    }
+}
+
+impl<'f> FromFormField<'f> for TempFile {
+    type Context = Option<TempFile>;
+
+    #[inline(always)]
+    fn init() -> Self::Context {
+        None
+    }
+
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>> {
+        // A later file replaces an earlier one, which is then deleted.
+        match claim(key, item, strict)? {
+            Some(file) => *ctxt = Some(file),
+            None if key.is_empty() => {
+                return Err(FormParseError::BadValue(item.key, item.value));
+            }
+            None => { /* ignored item */ }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn finalize(ctxt: Self::Context) -> Result<Option<Self>, FormErrors> {
+        Ok(ctxt)
+    }
+}
+
+impl<'f> FromFormField<'f> for Option<TempFile> {
+    type Context = Option<TempFile>;
+
+    #[inline(always)]
+    fn init() -> Self::Context {
+        None
+    }
+
+    fn push(
+        ctxt: &mut Self::Context,
+        key: &'f RawStr,
+        item: FormItem<'f>,
+        strict: bool
+    ) -> Result<(), FormParseError<'f>> {
+        let file = claim(key, item, strict)?;
+        if key.is_empty() {
+            *ctxt = file;
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn finalize(ctxt: Self::Context) -> Result<Option<Self>, FormErrors> {
+        Ok(Some(ctxt))
+    }
+
+    #[inline(always)]
+    fn default() -> Option<Self> {
+        Some(None)
+    }
+}
@@ -0,0 +1,261 @@
+//! Validators for use with the `#[form(validate = ..)]` attribute of the
+//! [`FromForm`](crate::request::FromForm) derive.
+//!
+//! The value of the `validate` attribute is a call to a validator, written
+//! without the field's value: the value is passed as the first argument
+//! automatically. A validator that takes no other arguments may also be
+//! written as a path. All of the validators in this module are in scope; any
+//! other function can be used as a validator as long as it is in scope and
+//! has the signature:
+//!
+//! ```rust,ignore
+//! fn validator(value: &T, /* arguments.. */) -> Result<(), E>
+//! ```
+//!
+//! where `T` is the type of the field, or a type it dereferences to, and `E`
+//! implements `Display`. The error is displayed to produce the message of the
+//! [`FieldError`](crate::request::FieldError).
+//!
+//! Validators only run on values that parse successfully. Multiple `validate`
+//! attributes may be applied to one field; each one that fails results in an
+//! error.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! fn not_admin(name: &str) -> Result<(), &'static str> {
+//!     match name {
+//!         "admin" => Err("name is reserved"),
+//!         _ => Ok(())
+//!     }
+//! }
+//!
+//! #[derive(FromForm)]
+//! struct Account {
+//!     #[form(validate = len(1..=32), validate = not_admin)]
+//!     name: String,
+//!     #[form(validate = email)]
+//!     email: String,
+//!     #[form(validate = range(13..))]
+//!     age: u8,
+//!     #[form(validate = one_of(&["free", "pro"]))]
+//!     plan: String,
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::ops::{Bound, RangeBounds};
+use std::collections::HashMap;
+
+use crate::http::RawStr;
+use crate::request::TempFile;
+
+/// Trait implemented by values with a length, for the [`len()`] validator.
+pub trait Len {
+    /// The length of the value: the number of characters in a string, the
+    /// number of elements in a collection, or the number of bytes in a file.
+    fn len(&self) -> usize;
+}
+
+impl Len for str {
+    fn len(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Len for String {
+    fn len(&self) -> usize {
+        self.as_str().chars().count()
+    }
+}
+
+/// The length of the percent-decoded string.
+impl Len for RawStr {
+    fn len(&self) -> usize {
+        self.url_decode_lossy().chars().count()
+    }
+}
+
+impl<T> Len for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+}
+
+impl<T> Len for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl<K, V> Len for HashMap<K, V> {
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+impl Len for TempFile {
+    fn len(&self) -> usize {
+        TempFile::len(self) as usize
+    }
+}
+
+impl<L: Len + ?Sized> Len for &L {
+    fn len(&self) -> usize {
+        L::len(self)
+    }
+}
+
+/// Trait implemented by string values, for the [`email()`] validator.
+pub trait Text {
+    /// The text of the value, or `None` if it isn't valid text. A `RawStr` is
+    /// percent-decoded, and isn't valid text if it doesn't decode to UTF-8.
+    fn text(&self) -> Option<Cow<'_, str>>;
+}
+
+impl Text for str {
+    fn text(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl Text for String {
+    fn text(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.as_str()))
+    }
+}
+
+impl Text for RawStr {
+    fn text(&self) -> Option<Cow<'_, str>> {
+        self.url_decode().ok().map(Cow::Owned)
+    }
+}
+
+impl<T: Text + ?Sized> Text for &T {
+    fn text(&self) -> Option<Cow<'_, str>> {
+        T::text(self)
+    }
+}
+
+fn bounds<T: Display, R: RangeBounds<T>>(range: &R) -> String {
+    let bound = |bound: Bound<&T>, inclusive: &str, exclusive: &str| match bound {
+        Bound::Included(v) => Some(format!("{} {}", inclusive, v)),
+        Bound::Excluded(v) => Some(format!("{} {}", exclusive, v)),
+        Bound::Unbounded => None,
+    };
+
+    let start = bound(range.start_bound(), "at least", "greater than");
+    let end = bound(range.end_bound(), "at most", "less than");
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{} and {}", start, end),
+        (Some(bound), None) | (None, Some(bound)) => bound,
+        (None, None) => "anything".into(),
+    }
+}
+
+/// Succeeds if the [`Len::len()`] of `value` is in `range`.
+///
+/// ```rust
+/// use rocket::request::validate::len;
+///
+/// assert!(len("hi", 1..64).is_ok());
+/// assert!(len("", 1..64).is_err());
+/// assert!(len(&vec![1, 2, 3], ..=2).is_err());
+/// ```
+pub fn len<V, R>(value: &V, range: R) -> Result<(), String>
+    where V: Len + ?Sized, R: RangeBounds<usize>
+{
+    match range.contains(&value.len()) {
+        true => Ok(()),
+        false => Err(format!("length must be {}", bounds(&range))),
+    }
+}
+
+/// Succeeds if `value` is in `range`.
+///
+/// ```rust
+/// use rocket::request::validate::range;
+///
+/// assert!(range(&5, 1..=10).is_ok());
+/// assert!(range(&0, 1..=10).is_err());
+/// assert!(range(&-1.5, ..0.0).is_ok());
+/// ```
+pub fn range<V, T, R>(value: &V, range: R) -> Result<(), String>
+    where V: PartialOrd<T> + ?Sized, T: PartialOrd<V> + Display, R: RangeBounds<T>
+{
+    match range.contains(value) {
+        true => Ok(()),
+        false => Err(format!("value must be {}", bounds(&range))),
+    }
+}
+
+/// Succeeds if `value` is one of `options`.
+///
+/// ```rust
+/// use rocket::request::validate::one_of;
+///
+/// assert!(one_of(&String::from("b"), &["a", "b"]).is_ok());
+/// assert!(one_of(&3, &[1, 2]).is_err());
+/// ```
+pub fn one_of<V, O>(value: &V, options: &[O]) -> Result<(), String>
+    where V: PartialEq<O> + ?Sized, O: Display
+{
+    if options.iter().any(|option| value == option) {
+        return Ok(());
+    }
+
+    let options = options.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+    Err(format!("value must be one of: {}", options.join(", ")))
+}
+
+/// Succeeds if `value` looks like an email address: a local part and a domain
+/// containing a `.`, separated by a single `@`, without any whitespace.
+///
+/// Only the shape of the address is checked. The only way to know whether an
+/// address is valid is to send an email to it.
+///
+/// ```rust
+/// use rocket::request::validate::email;
+///
+/// assert!(email("jane@example.com").is_ok());
+/// assert!(email("jane@localhost").is_err());
+/// assert!(email("jane doe@example.com").is_err());
+/// ```
+///
+/// A [`RawStr`] is percent-decoded before it is checked:
+///
+/// ```rust
+/// use rocket::http::RawStr;
+/// use rocket::request::validate::email;
+///
+/// assert!(email(RawStr::from_str("jane%40example.com")).is_ok());
+/// assert!(email(RawStr::from_str("jane%20doe@example.com")).is_err());
+/// ```
+pub fn email<V: Text + ?Sized>(value: &V) -> Result<(), String> {
+    let value = match value.text() {
+        Some(value) => value,
+        None => return Err("value must be an email address".into()),
+    };
+
+    let value = &*value;
+    let valid = match value.find('@') {
+        Some(at) => {
+            let (local, domain) = (&value[..at], &value[(at + 1)..]);
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !domain.contains("..")
+                && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err("value must be an email address".into()),
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::str::FromStr;
 
 use crate::router::Route;
 use crate::request::Request;
@@ -7,6 +8,7 @@ use crate::outcome::{self, IntoOutcome};
 use crate::outcome::Outcome::*;
 
 use crate::http::{Status, ContentType, Accept, Method, Cookies, uri::Origin};
+use crate::http::{AcceptLanguage, AcceptEncoding, AcceptCharset, IfMatch, IfNoneMatch};
 
 /// Type alias for the `Outcome` of a `FromRequest` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), ()>;
@@ -131,6 +133,21 @@ impl<S, E> IntoOutcome<S, (Status, E), ()> for Result<S, E> {
 ///     Extracts the [`ContentType`] from the incoming request. If the request
 ///     didn't specify a Content-Type, the request is forwarded.
 ///
+///   * **&AcceptLanguage**, **&AcceptEncoding**, **&AcceptCharset**
+///
+///     Extracts the [`AcceptLanguage`], [`AcceptEncoding`], or
+///     [`AcceptCharset`] header from the incoming request. Multiple headers of
+///     the same name are combined. If the request didn't specify the header,
+///     or the header is malformed, the request is forwarded.
+///
+///   * **&IfMatch**, **&IfNoneMatch**
+///
+///     Extracts the [`IfMatch`] or [`IfNoneMatch`] precondition header from
+///     the incoming request. Multiple headers of the same name are combined.
+///     If the request didn't specify the header, the request is forwarded. If
+///     the header is malformed, fails with a `400 Bad Request` status so that
+///     a broken precondition is never mistaken for an absent one.
+///
 ///   * **SocketAddr**
 ///
 ///     Extracts the remote address of the incoming request as a [`SocketAddr`].
@@ -413,6 +430,96 @@ impl<'a> FromRequest<'a, '_> for &'a ContentType {
     }
 }
 
+/// Parses the (combined) values of all headers named `name` as a `T`.
+fn parse_combined_header<T: FromStr>(request: &Request<'_>, name: &str) -> Option<T> {
+    let values: Vec<_> = request.headers().get(name).collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    values.join(", ").parse().ok()
+}
+
+impl<'a> FromRequest<'a, '_> for &'a AcceptLanguage {
+    type Error = std::convert::Infallible;
+
+    fn from_request(request: &'a Request<'_>) -> Outcome<Self, Self::Error> {
+        let header: &Option<AcceptLanguage> = request.local_cache(|| {
+            parse_combined_header(request, "Accept-Language")
+        });
+
+        match header {
+            Some(accept_language) => Success(accept_language),
+            None => Forward(())
+        }
+    }
+}
+
+impl<'a> FromRequest<'a, '_> for &'a AcceptEncoding {
+    type Error = std::convert::Infallible;
+
+    fn from_request(request: &'a Request<'_>) -> Outcome<Self, Self::Error> {
+        let header: &Option<AcceptEncoding> = request.local_cache(|| {
+            parse_combined_header(request, "Accept-Encoding")
+        });
+
+        match header {
+            Some(accept_encoding) => Success(accept_encoding),
+            None => Forward(())
+        }
+    }
+}
+
+impl<'a> FromRequest<'a, '_> for &'a AcceptCharset {
+    type Error = std::convert::Infallible;
+
+    fn from_request(request: &'a Request<'_>) -> Outcome<Self, Self::Error> {
+        let header: &Option<AcceptCharset> = request.local_cache(|| {
+            parse_combined_header(request, "Accept-Charset")
+        });
+
+        match header {
+            Some(accept_charset) => Success(accept_charset),
+            None => Forward(())
+        }
+    }
+}
+
+/// Retrieves the precondition header `name` of type `T` from `request`.
+fn precondition<'a, T>(request: &'a Request<'_>, name: &str) -> Outcome<&'a T, String>
+    where T: FromStr<Err = String> + Send + Sync + 'static
+{
+    let header: &Option<Result<T, String>> = request.local_cache(|| {
+        let values: Vec<_> = request.headers().get(name).collect();
+        match values.is_empty() {
+            true => None,
+            false => Some(values.join(", ").parse())
+        }
+    });
+
+    match header {
+        Some(Ok(header)) => Success(header),
+        Some(Err(e)) => Failure((Status::BadRequest, format!("invalid {}: {}", name, e))),
+        None => Forward(())
+    }
+}
+
+impl<'a> FromRequest<'a, '_> for &'a IfMatch {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'_>) -> Outcome<Self, Self::Error> {
+        precondition(request, "If-Match")
+    }
+}
+
+impl<'a> FromRequest<'a, '_> for &'a IfNoneMatch {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'_>) -> Outcome<Self, Self::Error> {
+        precondition(request, "If-None-Match")
+    }
+}
+
 impl FromRequest<'_, '_> for SocketAddr {
     type Error = std::convert::Infallible;
 
@@ -15,11 +15,15 @@ mod tests;
 pub use self::request::Request;
 pub use self::from_request::{FromRequest, Outcome};
 pub use self::param::{FromParam, FromSegments};
-pub use self::form::{FromForm, FromFormValue};
-pub use self::form::{Form, LenientForm, FormItems, FormItem};
+pub use self::form::{FromForm, FromFormValue, FromFormField};
+pub use self::form::{Form, LenientForm, FormItems, FormItem, TempFile, FormFile};
+#[doc(hidden)] pub use self::form::{FormBuffer, strip_field_name, split_form_key, field_path};
 pub use self::form::{FormError, FormParseError, FormDataError};
+pub use self::form::{FormErrors, FieldError, FieldErrorKind, validate};
 pub use self::state::State;
 pub use self::query::{Query, FromQuery};
 
 #[doc(inline)]
 pub use crate::response::flash::FlashMessage;
+#[doc(inline)]
+pub use crate::response::event_stream::LastEventId;
@@ -290,7 +290,11 @@ impl<'r> Request<'r> {
     pub fn cookies(&self) -> Cookies<'_> {
         // FIXME: Can we do better? This is disappointing.
         match self.state.cookies.try_borrow_mut() {
-            Ok(jar) => Cookies::new(jar, self.state.config.secret_key()),
+            Ok(jar) => {
+                let config = &self.state.config;
+                let (key, retired) = (config.secret_key(), config.retired_secret_keys());
+                Cookies::new(jar, key, retired, &config.cookies)
+            }
             Err(_) => {
                 error_!("Multiple `Cookies` instances are active at once.");
                 info_!("An instance of `Cookies` must be dropped before another \
@@ -396,6 +400,29 @@ impl<'r> Request<'r> {
         }).as_ref()
     }
 
+    /// Returns the length of the request's body, in bytes, as declared by its
+    /// Content-Length header. If the header is not present or is not a valid
+    /// length, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// use rocket::http::Header;
+    ///
+    /// # Request::example(Method::Post, "/uri", |mut request| {
+    /// assert_eq!(request.content_length(), None);
+    ///
+    /// request.add_header(Header::new("Content-Length", "1024"));
+    /// assert_eq!(request.content_length(), Some(1024));
+    /// # });
+    /// ```
+    #[inline]
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers().get_one("Content-Length").and_then(|v| v.trim().parse().ok())
+    }
+
     /// Returns the Accept header of `self`. If the header is not present,
     /// returns `None`. The Accept header is cached after the first call to this
     /// function. As a result, subsequent calls will always return the same
@@ -883,6 +910,7 @@ impl IndexedFormItem {
             raw: source[self.raw.0..self.raw.1].into(),
             key: source[self.key.0..self.key.1].into(),
             value: source[self.value.0..self.value.1].into(),
+            file: None,
         }
     }
 }
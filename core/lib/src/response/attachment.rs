@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::request::Request;
+use crate::response::{self, Responder};
+use crate::http::{Header, ContentType};
+
+/// Prompts the client to save the response as a file named `name`.
+///
+/// The wrapped responder `R` generates the response; `Attachment` then sets
+/// the `Content-Disposition` header to `attachment` with the download name,
+/// encoded as per [RFC 6266] and [RFC 5987]: a name consisting of printable
+/// ASCII is sent as-is in `filename`, while any other name is sent
+/// percent-encoded as UTF-8 in `filename*` along with an ASCII approximation
+/// in `filename` for older clients. Path separators in the name are replaced.
+///
+/// If the extension of the download name is recognized by
+/// [`ContentType::from_extension()`], the `Content-Type` of the response is
+/// set accordingly, overriding any set by `R`.
+///
+/// [RFC 6266]: https://tools.ietf.org/html/rfc6266
+/// [RFC 5987]: https://tools.ietf.org/html/rfc5987
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use rocket::response::{Attachment, NamedFile};
+///
+/// #[get("/report")]
+/// fn report() -> Attachment<String> {
+///     Attachment::new("report.csv", "id,total\n1,20\n".into())
+/// }
+///
+/// #[get("/invoice")]
+/// fn invoice() -> Option<Attachment<NamedFile>> {
+///     let file = NamedFile::open("data/invoice-42.pdf").ok()?;
+///     Some(file.attachment("Rechnung Nr. 42.pdf"))
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment<R> {
+    name: Cow<'static, str>,
+    responder: R,
+}
+
+impl<R> Attachment<R> {
+    /// Wraps `responder` so that its response is downloaded as `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Attachment;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let attachment = Attachment::new("hello.txt", "Hello, world!");
+    /// ```
+    pub fn new<N: Into<Cow<'static, str>>>(name: N, responder: R) -> Attachment<R> {
+        Attachment { name: name.into(), responder }
+    }
+
+    /// Returns the download name.
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Renders the `Content-Disposition` header value for an attachment named
+/// `name`.
+fn content_disposition(name: &str) -> String {
+    let is_plain = |c: char| (c == ' ' || c.is_ascii_graphic()) && !"\"\\/".contains(c);
+    let fallback: String = name.chars()
+        .map(|c| if is_plain(c) { c } else { '_' })
+        .collect();
+
+    let mut value = format!("attachment; filename=\"{}\"", fallback);
+    if fallback != name {
+        // `attr-char` from RFC 5987; everything else is percent-encoded.
+        let is_attr_char = |b: u8| b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b);
+        value.push_str("; filename*=UTF-8''");
+        for byte in name.replace(|c: char| c == '/' || c == '\\', "_").bytes() {
+            match is_attr_char(byte) {
+                true => value.push(byte as char),
+                false => { let _ = write!(value, "%{:02X}", byte); }
+            }
+        }
+    }
+
+    value
+}
+
+/// Sets the `Content-Disposition` header and, if the download name's extension
+/// is recognized, the `Content-Type` header of the wrapped responder's
+/// response.
+impl<'r, R: Responder<'r>> Responder<'r> for Attachment<R> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let mut response = self.responder.respond_to(req)?;
+        let content_type = Path::new(&*self.name).extension()
+            .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()));
+
+        if let Some(content_type) = content_type {
+            response.set_header(content_type);
+        }
+
+        response.set_header(Header::new("Content-Disposition", content_disposition(&self.name)));
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::content_disposition;
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(content_disposition("report.csv"), "attachment; filename=\"report.csv\"");
+        assert_eq!(content_disposition("my report.csv"), "attachment; filename=\"my report.csv\"");
+        assert_eq!(content_disposition("naïve.txt"),
+            "attachment; filename=\"na_ve.txt\"; filename*=UTF-8''na%C3%AFve.txt");
+        assert_eq!(content_disposition("€ rates.pdf"),
+            "attachment; filename=\"_ rates.pdf\"; filename*=UTF-8''%E2%82%AC%20rates.pdf");
+        assert_eq!(content_disposition("a\"b.txt"),
+            "attachment; filename=\"a_b.txt\"; filename*=UTF-8''a%22b.txt");
+        assert_eq!(content_disposition("../etc/passwd"),
+            "attachment; filename=\".._etc_passwd\"; filename*=UTF-8''.._etc_passwd");
+    }
+}
@@ -0,0 +1,43 @@
+use crate::request::Request;
+use crate::response::{self, Responder};
+use crate::http::CacheControl;
+
+/// Sets the `Cache-Control` header of a response.
+///
+/// The wrapped responder `R` generates the response; the `Cache-Control`
+/// header is then set to the [`CacheControl`], overriding any set by `R` and
+/// any default policy from the [`cache`](crate::config::CacheDefaults)
+/// configuration parameter.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::CacheControl;
+/// use rocket::response::Cached;
+///
+/// #[get("/logo.svg")]
+/// fn logo() -> Cached<&'static str> {
+///     let policy = CacheControl::new().public().max_age(86400).stale_while_revalidate(60);
+///     Cached(policy, "<svg/>")
+/// }
+///
+/// #[get("/me")]
+/// fn me() -> Cached<String> {
+///     Cached(CacheControl::new().private().no_store(), "personal".into())
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cached<R>(pub CacheControl, pub R);
+
+/// Sets the `Cache-Control` header on the response generated by the wrapped
+/// responder, overriding any existing value.
+impl<'r, R: Responder<'r>> Responder<'r> for Cached<R> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let mut response = self.1.respond_to(req)?;
+        response.set_header(self.0);
+        Ok(response)
+    }
+}
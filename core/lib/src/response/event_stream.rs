@@ -0,0 +1,411 @@
+use std::{cmp, fmt};
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use crate::outcome::Outcome::*;
+use crate::request::{self, Request, FromRequest};
+use crate::response::{self, Response, Responder};
+use crate::http::{ContentType, Status, CacheControl};
+
+/// The default interval between heartbeats: 15 seconds.
+const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// A heartbeat: an empty comment, ignored by clients.
+const HEARTBEAT: &[u8] = b":\n\n";
+
+/// A single server-sent event.
+///
+/// An `Event` is built from its `data`, the payload delivered to the client's
+/// `message` handler, and optionally carries:
+///
+///   * an **id**, which the client sends back in the `Last-Event-ID` header
+///     when it reconnects; see [`LastEventId`](crate::request::LastEventId),
+///   * an **event** name, which dispatches the event to listeners for that
+///     name instead of `message`,
+///   * a **retry** duration, which sets how long the client waits before
+///     reconnecting after the stream ends.
+///
+/// Newlines in `data` are preserved by splitting it across several `data`
+/// lines. Carriage returns, newlines, and NUL characters are removed from the
+/// id and event name since they can't be represented.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::response::Event;
+///
+/// let event = Event::data("hello\nworld").id("7").event("greeting");
+/// assert_eq!(event.to_string(), "id: 7\nevent: greeting\ndata: hello\ndata: world\n\n");
+///
+/// let event = Event::empty().retry(Duration::from_secs(5));
+/// assert_eq!(event.to_string(), "retry: 5000\n\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl Event {
+    /// Returns an event carrying `data`.
+    #[inline]
+    pub fn data<S: Into<String>>(data: S) -> Event {
+        Event { data: Some(data.into()), ..Event::default() }
+    }
+
+    /// Returns an event with no fields. Clients don't dispatch an event without
+    /// data, but still apply its `id` and `retry`.
+    #[inline]
+    pub fn empty() -> Event {
+        Event::default()
+    }
+
+    /// Sets the id of `self` to `id`.
+    #[inline]
+    pub fn id<S: Into<String>>(mut self, id: S) -> Event {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the event name of `self` to `event`.
+    #[inline]
+    pub fn event<S: Into<String>>(mut self, event: S) -> Event {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the client's reconnection delay to `retry`.
+    #[inline]
+    pub fn retry(mut self, retry: Duration) -> Event {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+/// Renders the event in the `text/event-stream` format, including the blank
+/// line that terminates it.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let single_line = |s: &str| s.replace(|c: char| c == '\r' || c == '\n' || c == '\0', "");
+        if let Some(ref id) = self.id {
+            writeln!(f, "id: {}", single_line(id))?;
+        }
+
+        if let Some(ref event) = self.event {
+            writeln!(f, "event: {}", single_line(event))?;
+        }
+
+        if let Some(ref data) = self.data {
+            for line in data.replace("\r\n", "\n").split(|c: char| c == '\r' || c == '\n') {
+                writeln!(f, "data: {}", line)?;
+            }
+        }
+
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+
+        writeln!(f)
+    }
+}
+
+/// Where the events of an `EventStream` come from.
+enum Source<'r> {
+    Iter(Box<dyn Iterator<Item = Event> + 'r>),
+    Channel(Receiver<Event>),
+}
+
+/// Streams [server-sent events] to a client.
+///
+/// An `EventStream` responds with a `text/event-stream` body that is written
+/// and flushed to the client one [`Event`] at a time as the events become
+/// available. The events come either from an iterator, via
+/// [`EventStream::new()`], or from a channel, via [`EventStream::from()`]. The
+/// stream ends when the iterator is exhausted or every sender of the channel
+/// has been dropped; it also ends, with an error logged, when the client
+/// disconnects.
+///
+/// [server-sent events]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+///
+/// # Heartbeats
+///
+/// While a channel-backed stream waits for its next event, it sends an empty
+/// comment every 15 seconds by default; see [`EventStream::heartbeat()`].
+/// Heartbeats keep proxies from closing an idle connection and let the server
+/// notice a client that has gone away. An iterator is polled directly, so no
+/// heartbeats are sent while it blocks; feed a channel from a separate thread
+/// when events arrive slowly.
+///
+/// # Concurrency
+///
+/// Rocket's workers are threads, and an event stream keeps its worker busy for
+/// as long as it's open. To ensure that streams can't starve ordinary
+/// requests, at most `event_streams` streams may be open at once, half of the
+/// configured `workers` by default; see the [config
+/// docs](crate::config#event-streams). An `EventStream` beyond the bound fails
+/// with a `503 Service Unavailable`. Setting `event_streams` close to `workers`
+/// leaves little room for other requests.
+///
+/// # Example
+///
+/// Broadcasting events produced elsewhere in the application:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use std::sync::mpsc;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// use rocket::request::LastEventId;
+/// use rocket::response::{Event, EventStream};
+///
+/// #[get("/ticks")]
+/// fn ticks(last: Option<LastEventId<'_>>) -> EventStream<'static> {
+///     let mut n: u64 = last.and_then(|id| id.as_str().parse().ok()).unwrap_or(0);
+///     let (tx, rx) = mpsc::channel();
+///     thread::spawn(move || loop {
+///         n += 1;
+///         let event = Event::data(format!("tick {}", n)).id(n.to_string());
+///         if tx.send(event).is_err() {
+///             break;
+///         }
+///
+///         thread::sleep(Duration::from_secs(1));
+///     });
+///
+///     EventStream::from(rx)
+/// }
+///
+/// #[get("/status")]
+/// fn status() -> EventStream<'static> {
+///     EventStream::new(vec![Event::data("up").event("status")])
+/// }
+/// # fn main() {  }
+/// ```
+pub struct EventStream<'r> {
+    source: Source<'r>,
+    heartbeat: Option<Duration>,
+}
+
+impl<'r> EventStream<'r> {
+    /// Returns a stream of the events produced by `events`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::{Event, EventStream};
+    ///
+    /// let events = (1..=3).map(|i| Event::data(i.to_string()));
+    /// # #[allow(unused_variables)]
+    /// let stream = EventStream::new(events);
+    /// ```
+    pub fn new<I>(events: I) -> EventStream<'r>
+        where I: IntoIterator<Item = Event>, I::IntoIter: 'r
+    {
+        EventStream {
+            source: Source::Iter(Box::new(events.into_iter())),
+            heartbeat: Some(DEFAULT_HEARTBEAT),
+        }
+    }
+
+    /// Sets the interval between heartbeats sent while waiting on a channel to
+    /// `interval`. An `interval` of zero disables heartbeats.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    /// use rocket::response::EventStream;
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// # drop(tx);
+    /// # #[allow(unused_variables)]
+    /// let stream = EventStream::from(rx).heartbeat(Duration::from_secs(30));
+    /// ```
+    pub fn heartbeat(mut self, interval: Duration) -> EventStream<'r> {
+        self.heartbeat = match interval == Duration::from_secs(0) {
+            true => None,
+            false => Some(interval),
+        };
+
+        self
+    }
+}
+
+/// Returns a stream of the events received on `receiver`.
+impl<'r> From<Receiver<Event>> for EventStream<'r> {
+    fn from(receiver: Receiver<Event>) -> EventStream<'r> {
+        EventStream { source: Source::Channel(receiver), heartbeat: Some(DEFAULT_HEARTBEAT) }
+    }
+}
+
+impl fmt::Debug for EventStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream").field("heartbeat", &self.heartbeat).finish()
+    }
+}
+
+/// The number of open event streams, kept in the managed state of each Rocket
+/// instance.
+#[derive(Default)]
+pub(crate) struct OpenStreams(Arc<AtomicUsize>);
+
+/// A reserved slot for an open stream, released on drop.
+struct Slot(Arc<AtomicUsize>);
+
+impl OpenStreams {
+    /// Reserves a slot if fewer than `max` streams are open.
+    fn reserve(&self, max: usize) -> Option<Slot> {
+        let mut open = self.0.load(Ordering::Acquire);
+        loop {
+            if open >= max {
+                return None;
+            }
+
+            match self.0.compare_exchange(open, open + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return Some(Slot(self.0.clone())),
+                Err(actual) => open = actual,
+            }
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The body of an event stream: each read yields at most one rendered event or
+/// heartbeat so that the event is flushed before the next one is awaited.
+struct EventReader<'r> {
+    source: Source<'r>,
+    heartbeat: Option<Duration>,
+    pending: Vec<u8>,
+    position: usize,
+    _slot: Option<Slot>,
+}
+
+impl EventReader<'_> {
+    /// Blocks until the next event or heartbeat is due and returns it, or
+    /// returns `None` when the source is exhausted.
+    fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        match self.source {
+            Source::Iter(ref mut iter) => iter.next().map(|e| e.to_string().into_bytes()),
+            Source::Channel(ref receiver) => match self.heartbeat {
+                Some(interval) => match receiver.recv_timeout(interval) {
+                    Ok(event) => Some(event.to_string().into_bytes()),
+                    Err(RecvTimeoutError::Timeout) => Some(HEARTBEAT.to_vec()),
+                    Err(RecvTimeoutError::Disconnected) => None,
+                },
+                None => receiver.recv().ok().map(|e| e.to_string().into_bytes()),
+            }
+        }
+    }
+}
+
+impl Read for EventReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.pending.len() {
+            match self.next_chunk() {
+                Some(chunk) => self.pending = chunk,
+                None => return Ok(0),
+            }
+
+            self.position = 0;
+        }
+
+        let n = cmp::min(buf.len(), self.pending.len() - self.position);
+        buf[..n].copy_from_slice(&self.pending[self.position..(self.position + n)]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Streams the events with a `text/event-stream` Content-Type and a
+/// `no-cache` Cache-Control. Fails with a `503 Service Unavailable` if the
+/// maximum number of concurrent event streams are already open.
+impl<'r> Responder<'r> for EventStream<'r> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let slot = match req.state.managed.try_get::<OpenStreams>() {
+            Some(open) => {
+                let max = req.state.config.max_event_streams();
+                match open.reserve(max) {
+                    Some(slot) => Some(slot),
+                    None => {
+                        warn_!("Refusing event stream: {} streams are already open.", max);
+                        return Err(Status::ServiceUnavailable);
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let reader = EventReader {
+            source: self.source,
+            heartbeat: self.heartbeat,
+            pending: vec![],
+            position: 0,
+            _slot: slot,
+        };
+
+        Response::build()
+            .header(ContentType::EventStream)
+            .header(CacheControl::new().no_cache())
+            .flushed_body(reader)
+            .ok()
+    }
+}
+
+/// The value of the `Last-Event-ID` header sent by a reconnecting client.
+///
+/// When an [`EventStream`] breaks, the client reconnects and sends the id of
+/// the last event it received, allowing the stream to resume where it left
+/// off. As a request guard, `LastEventId` forwards if the header is absent;
+/// use `Option<LastEventId>` to handle both fresh and resumed streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastEventId<'r>(&'r str);
+
+impl<'r> LastEventId<'r> {
+    /// Returns the id of the last event received by the client.
+    #[inline(always)]
+    pub fn as_str(&self) -> &'r str {
+        self.0
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for LastEventId<'a> {
+    type Error = std::convert::Infallible;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match request.headers().get_one("Last-Event-ID") {
+            Some(id) => Success(LastEventId(id)),
+            None => Forward(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Event;
+
+    #[test]
+    fn test_event_format() {
+        assert_eq!(Event::data("").to_string(), "data: \n\n");
+        assert_eq!(Event::data(" x ").to_string(), "data:  x \n\n");
+        assert_eq!(Event::data("a\r\nb\rc\nd").to_string(),
+            "data: a\ndata: b\ndata: c\ndata: d\n\n");
+        assert_eq!(Event::data("a\n").to_string(), "data: a\ndata: \n\n");
+        assert_eq!(Event::empty().id("a\nb\0c").event("x\r\ny").to_string(),
+            "id: abc\nevent: xy\n\n");
+        assert_eq!(Event::empty().to_string(), "\n");
+    }
+}
@@ -26,11 +26,17 @@
 mod responder;
 mod redirect;
 mod named_file;
+mod ranged;
+mod tagged;
+mod cached;
+mod attachment;
 mod stream;
+mod negotiate;
 mod response;
 mod debug;
 
 pub(crate) mod flash;
+pub(crate) mod event_stream;
 
 pub mod content;
 pub mod status;
@@ -42,7 +48,13 @@ pub use self::responder::Responder;
 pub use self::redirect::Redirect;
 pub use self::flash::Flash;
 pub use self::named_file::NamedFile;
+pub use self::ranged::Ranged;
+pub use self::tagged::Tagged;
+pub use self::cached::Cached;
+pub use self::attachment::Attachment;
+pub use self::event_stream::{EventStream, Event};
 pub use self::stream::Stream;
+pub use self::negotiate::Negotiate;
 pub use self::debug::Debug;
 #[doc(inline)] pub use self::content::Content;
 
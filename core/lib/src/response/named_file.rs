@@ -1,11 +1,13 @@
-use std::fs::File;
+use std::borrow::Cow;
+use std::fs::{File, Metadata};
 use std::path::{Path, PathBuf};
-use std::io;
+use std::io::{self, BufReader};
 use std::ops::{Deref, DerefMut};
+use std::time::UNIX_EPOCH;
 
 use crate::request::Request;
-use crate::response::{self, Responder};
-use crate::http::ContentType;
+use crate::response::{self, Response, Responder, Attachment, ranged};
+use crate::http::{Method, Status, ContentType, EntityTag, IfNoneMatch};
 
 /// A file with an associated name; responds with the Content-Type based on the
 /// file extension.
@@ -71,6 +73,28 @@ impl NamedFile {
     pub fn path(&self) -> &Path {
         self.0.as_path()
     }
+
+    /// Wraps `self` in an [`Attachment`] so that the client saves the file as
+    /// `name`. The `Content-Type` is inferred from the extension of `name` if
+    /// it is recognized and from the file's own extension otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use rocket::response::NamedFile;
+    ///
+    /// # #[allow(dead_code)]
+    /// # fn demo_attachment() -> io::Result<()> {
+    /// let file = NamedFile::open("exports/3f2a.bin")?.attachment("Übersicht.xlsx");
+    /// assert_eq!(file.name(), "Übersicht.xlsx");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn attachment<N: Into<Cow<'static, str>>>(self, name: N) -> Attachment<NamedFile> {
+        Attachment::new(name, self)
+    }
 }
 
 /// Streams the named file to the client. Sets or overrides the Content-Type in
@@ -78,16 +102,96 @@ impl NamedFile {
 /// recognized. See [`ContentType::from_extension()`] for more information. If
 /// you would like to stream a file with a different Content-Type than that
 /// implied by its extension, use a [`File`] directly.
+///
+/// Unless disabled via the [`validators`](crate::config::Validators)
+/// configuration parameter, the response carries a `Last-Modified` header with
+/// the file's modification time and an `ETag` derived from the file's inode,
+/// size, and modification time. A `GET` or `HEAD` request whose `If-None-Match`
+/// or, in its absence, `If-Modified-Since` header shows that the client's copy
+/// is current is answered with a `304 Not Modified` and no body.
+///
+/// Range requests are honored as described in the [`Ranged`] documentation:
+/// the response advertises `Accept-Ranges: bytes`, and a `Range` header is
+/// answered with a `206 Partial Content` or a `416 Range Not Satisfiable`.
+///
+/// [`Ranged`]: crate::response::Ranged
 impl Responder<'_> for NamedFile {
     fn respond_to(self, req: &Request<'_>) -> response::Result<'static> {
-        let mut response = self.1.respond_to(req)?;
+        let mut response = Response::new();
         if let Some(ext) = self.0.extension() {
             if let Some(ct) = ContentType::from_extension(&ext.to_string_lossy()) {
                 response.set_header(ct);
             }
         }
 
-        Ok(response)
+        let validators = req.state.config.validators;
+        let metadata = self.1.metadata().ok();
+        let modified = metadata.as_ref()
+            .and_then(|md| md.modified().ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+
+        if let (true, Some(md), Some(modified)) = (validators.etag, &metadata, modified) {
+            let tag = format!("{:x}-{:x}-{:x}", inode(md), md.len(), modified.as_nanos());
+            response.set_header(EntityTag::strong(tag));
+        }
+
+        if let (true, Some(modified)) = (validators.last_modified, modified) {
+            response.set_raw_header("Last-Modified", http_date(modified.as_secs()));
+        }
+
+        if is_not_modified(req, &response) {
+            response.set_status(Status::NotModified);
+            return Ok(response);
+        }
+
+        ranged::respond(req, response, BufReader::new(self.1))
+    }
+}
+
+#[cfg(unix)]
+fn inode(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn inode(_: &Metadata) -> u64 {
+    0
+}
+
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Formats `secs` seconds since the Unix epoch as an HTTP-date.
+fn http_date(secs: u64) -> String {
+    time::OffsetDateTime::from_unix_timestamp(secs as i64).format(HTTP_DATE_FORMAT)
+}
+
+/// Returns `true` if the validators in `response` show that the client's copy
+/// of the representation, as described by `req`'s conditional headers, is
+/// current. Only `GET` and `HEAD` requests are considered. As required by RFC
+/// 7232, `If-Modified-Since` is ignored when `If-None-Match` is present, and an
+/// `If-None-Match` of `*` matches the file even if it has no entity tag.
+fn is_not_modified(req: &Request<'_>, response: &Response<'_>) -> bool {
+    if req.method() != Method::Get && req.method() != Method::Head {
+        return false;
+    }
+
+    let if_none_match: Vec<_> = req.headers().get("If-None-Match").collect();
+    if !if_none_match.is_empty() {
+        let etag = response.headers().get_one("ETag").and_then(|tag| tag.parse().ok());
+        return if_none_match.join(", ").parse::<IfNoneMatch>()
+            .map_or(false, |header| header.is_any() || header.matches(etag.as_ref()));
+    }
+
+    let since = req.headers().get_one("If-Modified-Since")
+        .and_then(|date| time::PrimitiveDateTime::parse(date.trim(), HTTP_DATE_FORMAT).ok());
+
+    let modified = response.headers().get_one("Last-Modified")
+        .and_then(|date| time::PrimitiveDateTime::parse(date, HTTP_DATE_FORMAT).ok());
+
+    match (since, modified) {
+        (Some(since), Some(modified)) => modified <= since,
+        _ => false
     }
 }
 
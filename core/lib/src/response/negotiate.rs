@@ -0,0 +1,155 @@
+use std::fmt;
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::{Status, MediaType, ContentType};
+
+type Variant<'r> = Box<dyn FnOnce(&Request<'_>) -> response::Result<'r> + 'r>;
+
+/// Responds with the representation most acceptable to the client.
+///
+/// A `Negotiate` responder holds several _variants_, each a `Responder`
+/// associated with the media type it produces. When responding, the variant
+/// whose media type is most preferred by the request's `Accept` header, as
+/// determined by [`Accept::preferred_of()`], is chosen and used to respond.
+/// Among equally acceptable variants, the one added first wins. If the request
+/// has no `Accept` header, the first variant is used.
+///
+/// Variants are only ever used to respond if they are chosen; responders for
+/// variants that aren't chosen are simply dropped.
+///
+/// [`Accept::preferred_of()`]: crate::http::Accept::preferred_of()
+///
+/// # Response
+///
+/// The response of the chosen variant is returned with a `Vary: Accept` header
+/// adjoined to it. If the chosen variant's response does not set a
+/// `Content-Type`, the `Content-Type` is set to the variant's media type when
+/// that media type is fully specified (i.e., is not a range like `text/*`).
+///
+/// If no variant is acceptable to the client, an empty `406 Not Acceptable`
+/// response is returned, also with a `Vary: Accept` header so that caches don't
+/// serve it to clients with a different `Accept` header.
+///
+/// # Example
+///
+/// Respond with JSON, MessagePack, or HTML depending on the client's
+/// preference:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::MediaType;
+/// use rocket::response::{content, Negotiate};
+///
+/// #[get("/user")]
+/// fn user() -> Negotiate<'static> {
+///     Negotiate::new()
+///         .variant(MediaType::JSON, content::Json("{ \"name\": \"Bob\" }"))
+///         .variant(MediaType::MsgPack, content::MsgPack(&b"\x81\xa4name\xa3Bob"[..]))
+///         .variant(MediaType::HTML, content::Html("<b>Bob</b>"))
+/// }
+/// # fn main() {  }
+/// ```
+///
+/// The `Json`, `MsgPack`, and `Template` responders from `rocket_contrib` can
+/// be used as variants in the same manner:
+///
+/// ```rust,ignore
+/// Negotiate::new()
+///     .variant(MediaType::JSON, Json(&user))
+///     .variant(MediaType::MsgPack, MsgPack(&user))
+///     .variant(MediaType::HTML, Template::render("user", &user))
+/// ```
+pub struct Negotiate<'r> {
+    variants: Vec<(MediaType, Variant<'r>)>,
+}
+
+impl<'r> Negotiate<'r> {
+    /// Creates a new `Negotiate` responder with no variants.
+    ///
+    /// A `Negotiate` without any variants always responds with a `406 Not
+    /// Acceptable`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Negotiate;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let negotiate = Negotiate::new();
+    /// ```
+    #[inline(always)]
+    pub fn new() -> Negotiate<'r> {
+        Negotiate { variants: vec![] }
+    }
+
+    /// Adds a variant to `self` that responds with `responder`, which produces
+    /// a representation of type `media_type`. Variants added earlier take
+    /// precedence over those added later when the client accepts both equally.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::MediaType;
+    /// use rocket::response::{content, Negotiate};
+    ///
+    /// # #[allow(unused_variables)]
+    /// let negotiate = Negotiate::new()
+    ///     .variant(MediaType::HTML, content::Html("<p>Hello!</p>"))
+    ///     .variant(MediaType::Plain, "Hello!");
+    /// ```
+    pub fn variant<R>(mut self, media_type: MediaType, responder: R) -> Negotiate<'r>
+        where R: Responder<'r> + 'r
+    {
+        let variant = move |req: &Request<'_>| responder.respond_to(req);
+        self.variants.push((media_type, Box::new(variant)));
+        self
+    }
+}
+
+impl fmt::Debug for Negotiate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let media_types = self.variants.iter().map(|(media_type, _)| media_type);
+        f.debug_struct("Negotiate")
+            .field("variants", &media_types.collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Responds with the variant most acceptable to the client and adjoins a
+/// `Vary: Accept` header. Responds with an empty `406 Not Acceptable`, also
+/// with a `Vary: Accept` header, if no variant is acceptable.
+impl<'r> Responder<'r> for Negotiate<'r> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let media_types: Vec<_> = self.variants.iter().map(|(m, _)| m.clone()).collect();
+        let chosen = match req.accept() {
+            Some(accept) => accept.preferred_of(&media_types),
+            None => media_types.first(),
+        };
+
+        let chosen = match chosen {
+            Some(chosen) => chosen,
+            None => {
+                warn_!("No acceptable representation for '{}'.", req.uri());
+                return Response::build()
+                    .status(Status::NotAcceptable)
+                    .raw_header("Vary", "Accept")
+                    .ok();
+            }
+        };
+
+        let i = self.variants.iter()
+            .position(|(media_type, _)| media_type.exact_eq(chosen))
+            .expect("chosen variant exists");
+
+        let (media_type, variant) = self.variants.into_iter().nth(i).expect("variant");
+        let mut response = variant(req)?;
+        if response.content_type().is_none() && media_type.specificity() == 2 {
+            response.set_header(ContentType(media_type));
+        }
+
+        response.adjoin_raw_header("Vary", "Accept");
+        Ok(response)
+    }
+}
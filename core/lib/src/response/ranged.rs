@@ -0,0 +1,405 @@
+use std::cmp::min;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Cursor};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder, Body};
+use crate::http::{Method, Status, ContentType, EntityTag};
+
+/// The maximum number of ranges honored in a single `Range` header. Requests
+/// with more ranges receive the full body.
+const MAX_RANGES: usize = 32;
+
+/// Serves a seekable body, honoring `Range` requests.
+///
+/// A `Ranged` responder advertises `Accept-Ranges: bytes` and, when the request
+/// is a `GET` or `HEAD` with a `Range: bytes=..` header, responds with only the
+/// requested parts of the body:
+///
+///   * A single satisfiable range is sent as a `206 Partial Content` with a
+///     `Content-Range` header.
+///   * Several satisfiable ranges are sent as a `206 Partial Content` with a
+///     `multipart/byteranges` body, each part carrying its own `Content-Type`
+///     and `Content-Range`.
+///   * If none of the ranges can be satisfied, the response is a `416 Range
+///     Not Satisfiable` with a `Content-Range: bytes */{length}` header.
+///
+/// A `Range` header that is malformed, uses a unit other than `bytes`, or
+/// requests an excessive number of ranges is ignored, and the full body is
+/// sent with a `200 OK`.
+///
+/// If the request carries an `If-Range` header, the ranges are honored only
+/// if its validator matches the response's: an entity tag must be a strong
+/// match for the response's `ETag`, and a date must be exactly the response's
+/// `Last-Modified`. Otherwise, the full body is sent. Validators are set on
+/// the response by the responder that wraps `Ranged`, as [`NamedFile`] does.
+///
+/// [`NamedFile`]: crate::response::NamedFile
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use std::io::Cursor;
+///
+/// use rocket::http::ContentType;
+/// use rocket::response::Ranged;
+///
+/// #[get("/video")]
+/// fn video() -> Ranged<Cursor<&'static [u8]>> {
+///     # let bytes: &'static [u8] = &[];
+///     Ranged::new(Cursor::new(bytes)).content_type(ContentType::new("video", "mp4"))
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug)]
+pub struct Ranged<R> {
+    content_type: Option<ContentType>,
+    reader: R,
+}
+
+impl<R: Read + Seek> Ranged<R> {
+    /// Creates a new `Ranged` responder serving the contents of `reader` from
+    /// its start to its end.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::response::Ranged;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let response = Ranged::new(Cursor::new("Hello, world!"));
+    /// ```
+    #[inline(always)]
+    pub fn new(reader: R) -> Ranged<R> {
+        Ranged { content_type: None, reader }
+    }
+
+    /// Sets the `Content-Type` of the body to `content_type`. In
+    /// `multipart/byteranges` responses, this is the `Content-Type` of each
+    /// part.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::http::ContentType;
+    /// use rocket::response::Ranged;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let response = Ranged::new(Cursor::new("Hello, world!"))
+    ///     .content_type(ContentType::Plain);
+    /// ```
+    #[inline(always)]
+    pub fn content_type(mut self, content_type: ContentType) -> Ranged<R> {
+        self.content_type = Some(content_type);
+        self
+    }
+}
+
+/// Responds with the parts of the body requested by the `Range` header, or the
+/// whole body, as described in the [`Ranged`] documentation.
+impl<'r, R: Read + Seek + 'r> Responder<'r> for Ranged<R> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let mut response = Response::new();
+        if let Some(content_type) = self.content_type {
+            response.set_header(content_type);
+        }
+
+        respond(req, response, self.reader)
+    }
+}
+
+/// Sets the body of `response`, which has status `200` and carries the headers
+/// of the full representation, to the parts of `body` requested by `req`. If
+/// the length of `body` can't be determined, it is streamed in full.
+pub(crate) fn respond<'r, R>(
+    req: &Request<'_>,
+    mut response: Response<'r>,
+    mut body: R
+) -> response::Result<'r>
+    where R: Read + Seek + 'r
+{
+    let len = match body.seek(SeekFrom::End(0)).and_then(|len| {
+        body.seek(SeekFrom::Start(0)).map(|_| len)
+    }) {
+        Ok(len) => len,
+        Err(e) => {
+            warn_!("Failed to determine the length of a ranged body: {}.", e);
+            response.set_streamed_body(body);
+            return Ok(response);
+        }
+    };
+
+    response.set_raw_header("Accept-Ranges", "bytes");
+    let ranges = match requested_ranges(req, &response) {
+        Some(ranges) => ranges,
+        None => {
+            response.set_raw_body(Body::Sized(body, len));
+            return Ok(response);
+        }
+    };
+
+    let mut ranges: Vec<_> = ranges.iter().filter_map(|r| r.resolve(len)).collect();
+    match ranges.len() {
+        0 => {
+            response.remove_header("Content-Type");
+            response.set_status(Status::RangeNotSatisfiable);
+            response.set_raw_header("Content-Range", format!("bytes */{}", len));
+        }
+        1 => {
+            let (start, end) = ranges.remove(0);
+            body.seek(SeekFrom::Start(start)).map_err(|e| {
+                error_!("Failed to seek to the start of a range: {}.", e);
+                Status::InternalServerError
+            })?;
+
+            response.set_status(Status::PartialContent);
+            response.set_raw_header("Content-Range", format!("bytes {}-{}/{}", start, end, len));
+            response.set_raw_body(Body::Sized(body, end - start + 1));
+        }
+        _ => {
+            let boundary = boundary();
+            let part_type = response.content_type()
+                .unwrap_or(ContentType::Binary);
+
+            let parts = Multipart::new(body, ranges, len, &boundary, &part_type);
+            let size = parts.size();
+            let content_type = ContentType::with_params("multipart", "byteranges",
+                ("boundary", boundary));
+
+            response.set_status(Status::PartialContent);
+            response.set_header(content_type);
+            response.set_raw_body(Body::Sized(parts, size));
+        }
+    }
+
+    Ok(response)
+}
+
+/// A range of bytes as written in a `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ByteRange {
+    /// `first-last` or, when `last` is `None`, `first-`.
+    From(u64, Option<u64>),
+    /// `-suffix`: the last `suffix` bytes.
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Returns the inclusive `(start, end)` positions of the range in a body
+    /// of length `len`, or `None` if the range can't be satisfied.
+    fn resolve(&self, len: u64) -> Option<(u64, u64)> {
+        match *self {
+            ByteRange::From(first, _) if first >= len => None,
+            ByteRange::From(first, last) => {
+                Some((first, min(last.unwrap_or(len - 1), len - 1)))
+            }
+            ByteRange::Suffix(0) => None,
+            ByteRange::Suffix(_) if len == 0 => None,
+            ByteRange::Suffix(suffix) => Some((len.saturating_sub(suffix), len - 1)),
+        }
+    }
+}
+
+/// Parses the value of a `Range` header. Returns `None` if the header is
+/// malformed, uses a unit other than `bytes`, or has too many ranges.
+fn parse_ranges(value: &str) -> Option<Vec<ByteRange>> {
+    let eq = value.find('=')?;
+    if !value[..eq].trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut ranges = vec![];
+    for spec in value[(eq + 1)..].split(',').map(|s| s.trim()) {
+        if spec.is_empty() {
+            continue;
+        }
+
+        let dash = spec.find('-')?;
+        let (first, last) = (spec[..dash].trim(), spec[(dash + 1)..].trim());
+        let range = match (first.is_empty(), last.is_empty()) {
+            (true, true) => return None,
+            (true, false) => ByteRange::Suffix(last.parse().ok()?),
+            (false, true) => ByteRange::From(first.parse().ok()?, None),
+            (false, false) => {
+                let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+                if last < first {
+                    return None;
+                }
+
+                ByteRange::From(first, Some(last))
+            }
+        };
+
+        ranges.push(range);
+    }
+
+    match ranges.len() {
+        0 => None,
+        n if n > MAX_RANGES => None,
+        _ => Some(ranges)
+    }
+}
+
+/// Returns the ranges `req` asks for if they should be honored for `response`.
+fn requested_ranges(req: &Request<'_>, response: &Response<'_>) -> Option<Vec<ByteRange>> {
+    if req.method() != Method::Get && req.method() != Method::Head {
+        return None;
+    }
+
+    let ranges = parse_ranges(req.headers().get_one("Range")?)?;
+    if let Some(validator) = req.headers().get_one("If-Range").map(|v| v.trim()) {
+        let matches = match validator.parse::<EntityTag>() {
+            Ok(tag) => response.headers().get_one("ETag")
+                .and_then(|etag| etag.parse::<EntityTag>().ok())
+                .map_or(false, |etag| etag.strong_eq(&tag)),
+            Err(_) => response.headers().get_one("Last-Modified")
+                .map_or(false, |date| date.trim() == validator),
+        };
+
+        if !matches {
+            return None;
+        }
+    }
+
+    Some(ranges)
+}
+
+/// Returns a fresh boundary for a `multipart/byteranges` body.
+fn boundary() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("rocket-byteranges-{:x}{:x}", nanos, count)
+}
+
+/// A `multipart/byteranges` body over the ranges of a seekable reader.
+struct Multipart<R> {
+    reader: R,
+    /// The remaining parts: the part's header followed by its range.
+    parts: std::vec::IntoIter<(Vec<u8>, (u64, u64))>,
+    /// The header and the unread length of the range of the current part.
+    current: Option<(Cursor<Vec<u8>>, u64)>,
+    closing: Cursor<Vec<u8>>,
+}
+
+impl<R: Read + Seek> Multipart<R> {
+    fn new(
+        reader: R,
+        ranges: Vec<(u64, u64)>,
+        len: u64,
+        boundary: &str,
+        content_type: &ContentType
+    ) -> Multipart<R> {
+        let parts: Vec<_> = ranges.into_iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let header = format!("{}--{}\r\nContent-Type: {}\r\n\
+                    Content-Range: bytes {}-{}/{}\r\n\r\n",
+                    if i == 0 { "" } else { "\r\n" },
+                    boundary, content_type, start, end, len);
+
+                (header.into_bytes(), (start, end))
+            })
+            .collect();
+
+        let closing = format!("\r\n--{}--\r\n", boundary).into_bytes();
+        Multipart {
+            reader,
+            parts: parts.into_iter(),
+            current: None,
+            closing: Cursor::new(closing)
+        }
+    }
+
+    /// The total length of the body.
+    fn size(&self) -> u64 {
+        let parts: u64 = self.parts.as_slice().iter()
+            .map(|(header, (start, end))| header.len() as u64 + (end - start + 1))
+            .sum();
+
+        parts + self.closing.get_ref().len() as u64
+    }
+}
+
+impl<R: Read + Seek> Read for Multipart<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some((ref mut header, ref mut remaining)) = self.current {
+                let n = header.read(buf)?;
+                if n > 0 {
+                    return Ok(n);
+                }
+
+                if *remaining > 0 {
+                    let max = min(buf.len() as u64, *remaining) as usize;
+                    let n = self.reader.read(&mut buf[..max])?;
+                    if n == 0 {
+                        let msg = "ranged body ended before the end of a range";
+                        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, msg));
+                    }
+
+                    *remaining -= n as u64;
+                    return Ok(n);
+                }
+            }
+
+            match self.parts.next() {
+                Some((header, (start, end))) => {
+                    self.reader.seek(SeekFrom::Start(start))?;
+                    self.current = Some((Cursor::new(header), end - start + 1));
+                }
+                None => return self.closing.read(buf),
+            }
+        }
+    }
+}
+
+impl<R> fmt::Debug for Multipart<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Multipart").field("parts", &self.parts.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ranges, ByteRange::*};
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(parse_ranges("bytes=0-499"), Some(vec![From(0, Some(499))]));
+        assert_eq!(parse_ranges("Bytes = 500-"), Some(vec![From(500, None)]));
+        assert_eq!(parse_ranges("bytes=-500"), Some(vec![Suffix(500)]));
+        assert_eq!(parse_ranges("bytes=0-0, -1"), Some(vec![From(0, Some(0)), Suffix(1)]));
+        assert_eq!(parse_ranges("bytes=0-1,,4-5"), Some(vec![From(0, Some(1)), From(4, Some(5))]));
+
+        assert_eq!(parse_ranges("bytes=5-4"), None);
+        assert_eq!(parse_ranges("bytes=-"), None);
+        assert_eq!(parse_ranges("bytes="), None);
+        assert_eq!(parse_ranges("bytes=a-b"), None);
+        assert_eq!(parse_ranges("bytes 0-1"), None);
+        assert_eq!(parse_ranges("items=0-1"), None);
+        assert_eq!(parse_ranges(&format!("bytes={}", vec!["0-0"; 33].join(","))), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(From(0, Some(499)).resolve(100), Some((0, 99)));
+        assert_eq!(From(10, None).resolve(100), Some((10, 99)));
+        assert_eq!(From(100, None).resolve(100), None);
+        assert_eq!(Suffix(10).resolve(100), Some((90, 99)));
+        assert_eq!(Suffix(1000).resolve(100), Some((0, 99)));
+        assert_eq!(Suffix(0).resolve(100), None);
+        assert_eq!(Suffix(10).resolve(0), None);
+        assert_eq!(From(0, None).resolve(0), None);
+    }
+}
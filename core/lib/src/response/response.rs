@@ -2,11 +2,17 @@ use std::{io, fmt, str};
 use std::borrow::Cow;
 
 use crate::response::Responder;
+use crate::data::NetStream;
 use crate::http::{Header, HeaderMap, Status, ContentType, Cookie};
 
 /// The default size, in bytes, of a chunk for streamed responses.
 pub const DEFAULT_CHUNK_SIZE: u64 = 4096;
 
+/// Takes over a connection once a `101 Switching Protocols` response to it has
+/// been written. The connection must be shut down when the function returns so
+/// that Hyper stops reading requests from it.
+pub(crate) type OnUpgrade<'r> = Box<dyn FnOnce(NetStream) + 'r>;
+
 #[derive(PartialEq, Clone, Hash)]
 /// The body of a response: can be sized or streamed/chunked.
 pub enum Body<T> {
@@ -409,6 +415,16 @@ impl<'r> ResponseBuilder<'r> {
         self
     }
 
+    /// Sets the body of the `Response` to be the streamed `body`, flushing
+    /// each chunk as soon as it's read.
+    #[inline(always)]
+    pub(crate) fn flushed_body<B>(&mut self, body: B) -> &mut ResponseBuilder<'r>
+        where B: io::Read + 'r
+    {
+        self.response.set_flushed_body(body);
+        self
+    }
+
     /// Sets the body of `self` to be `body`. This method should typically not
     /// be used, opting instead for one of `sized_body`, `streamed_body`, or
     /// `chunked_body`.
@@ -561,6 +577,8 @@ pub struct Response<'r> {
     status: Option<Status>,
     headers: HeaderMap<'r>,
     body: Option<Body<Box<dyn io::Read + 'r>>>,
+    flush: bool,
+    upgrade: Option<OnUpgrade<'r>>,
 }
 
 impl<'r> Response<'r> {
@@ -587,6 +605,8 @@ impl<'r> Response<'r> {
             status: None,
             headers: HeaderMap::new(),
             body: None,
+            flush: false,
+            upgrade: None,
         }
     }
 
@@ -970,6 +990,19 @@ impl<'r> Response<'r> {
         self.body.take()
     }
 
+    /// Sets the function that takes over the connection after `self`, a `101
+    /// Switching Protocols` response, is written.
+    #[inline(always)]
+    pub(crate) fn set_upgrade(&mut self, upgrade: OnUpgrade<'r>) {
+        self.upgrade = Some(upgrade);
+    }
+
+    /// Removes and returns the function that takes over the connection.
+    #[inline(always)]
+    pub(crate) fn take_upgrade(&mut self) -> Option<OnUpgrade<'r>> {
+        self.upgrade.take()
+    }
+
     // Makes the `Read`er in the body empty but leaves the size of the body if
     // it exists. Only meant to be used to handle HEAD requests automatically.
     #[inline(always)]
@@ -1050,6 +1083,23 @@ impl<'r> Response<'r> {
     pub fn set_chunked_body<B>(&mut self, body: B, chunk_size: u64)
             where B: io::Read + 'r {
         self.body = Some(Body::Chunked(Box::new(body), chunk_size));
+        self.flush = false;
+    }
+
+    /// Sets the body of `self` to be the streamed `body`, flushing each chunk
+    /// to the client as soon as `body` produces it instead of waiting for a
+    /// full chunk. Meant for bodies that trickle out data, like event streams.
+    #[inline(always)]
+    pub(crate) fn set_flushed_body<B>(&mut self, body: B) where B: io::Read + 'r {
+        self.set_streamed_body(body);
+        self.flush = true;
+    }
+
+    /// Returns `true` if each chunk of the body should be flushed as soon as
+    /// it's read.
+    #[inline(always)]
+    pub(crate) fn flushes_body(&self) -> bool {
+        self.flush
     }
 
     /// Sets the body of `self` to be `body`. This method should typically not
@@ -1075,6 +1125,8 @@ impl<'r> Response<'r> {
             Body::Sized(b, n) => Body::Sized(Box::new(b.take(n)), n),
             Body::Chunked(b, n) => Body::Chunked(Box::new(b), n),
         });
+
+        self.flush = false;
     }
 
     /// Replaces this response's status and body with that of `other`, if they
@@ -1119,6 +1171,11 @@ impl<'r> Response<'r> {
 
         if let Some(body) = other.body {
             self.body = Some(body);
+            self.flush = other.flush;
+        }
+
+        if let Some(upgrade) = other.upgrade {
+            self.upgrade = Some(upgrade);
         }
 
         for (name, values) in other.headers.into_iter_raw() {
@@ -1168,6 +1225,11 @@ impl<'r> Response<'r> {
 
         if self.body.is_none() {
             self.body = other.body;
+            self.flush = other.flush;
+        }
+
+        if self.upgrade.is_none() {
+            self.upgrade = other.upgrade;
         }
 
         for (name, mut values) in other.headers.into_iter_raw() {
@@ -7,13 +7,12 @@
 //! responders; when they do, the responder finalizes the response by writing
 //! out additional headers and, importantly, the body of the response.
 
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
 use std::borrow::Cow;
 
 use crate::request::Request;
-use crate::response::{Responder, Response};
-use crate::http::Status;
+use crate::response::{Responder, Response, tagged};
+use crate::http::{Status, EntityTag};
 
 /// Sets the status of the response to 201 (Created).
 ///
@@ -33,7 +32,7 @@ use crate::http::Status;
 ///     .tagged_body("{ 'resource': 'Hello, world!' }");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
-pub struct Created<R>(Cow<'static, str>, Option<R>, Option<u64>);
+pub struct Created<R>(Cow<'static, str>, Option<R>, Option<EntityTag>);
 
 impl<'r, R> Created<R> {
     /// Constructs a `Created` response with a `location` and no body.
@@ -101,7 +100,8 @@ impl<'r, R> Created<R> {
     }
 
     /// Adds `responder` as the body of `self`. Computes a hash of the
-    /// `responder` to be used as the value of the `ETag` header.
+    /// `responder` to be used as the value of the `ETag` header. The tag is the
+    /// same as that of [`Tagged::hashed()`](crate::response::Tagged::hashed()).
     ///
     /// # Example
     ///
@@ -132,11 +132,8 @@ impl<'r, R> Created<R> {
     pub fn tagged_body(mut self, responder: R) -> Self
         where R: Responder<'r> + Hash
     {
-        let mut hasher = &mut DefaultHasher::default();
-        responder.hash(&mut hasher);
-        let hash = hasher.finish();
+        self.2 = Some(tagged::hash_tag(&responder));
         self.1 = Some(responder);
-        self.2 = Some(hash);
         self
     }
 }
@@ -161,8 +158,8 @@ impl<'r, R: Responder<'r>> Responder<'r> for Created<R> {
            response.merge(responder.respond_to(req)?);
        }
 
-       if let Some(hash) = self.2 {
-           response.raw_header("ETag", format!(r#""{}""#, hash));
+       if let Some(tag) = self.2 {
+           response.header(tag);
        }
 
        response.status(Status::Created)
@@ -0,0 +1,106 @@
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::{Method, Status, EntityTag, IfNoneMatch};
+
+/// Sets the `ETag` header of a response to an [`EntityTag`].
+///
+/// The wrapped responder `R` generates the response; the `ETag` header is then
+/// set to the entity tag, overriding any `ETag` set by `R`. If the request is a
+/// `GET` or `HEAD` whose `If-None-Match` header matches the entity tag under
+/// the weak comparison function, `R` isn't used and the response is instead a
+/// `304 Not Modified` carrying only the `ETag`.
+///
+/// A tag can be chosen explicitly, for instance a version number stored with
+/// the resource, or computed from a hashable responder with
+/// [`Tagged::hashed()`], which produces the same tag as
+/// [`Created::tagged_body()`](crate::response::status::Created::tagged_body()).
+///
+/// # Example
+///
+/// Serving a resource's current version and rejecting stale updates with a
+/// `412 Precondition Failed`:
+///
+/// ```rust
+/// # #![feature(proc_macro_hygiene)]
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::{EntityTag, IfMatch, Status};
+/// use rocket::response::Tagged;
+///
+/// # fn current_version() -> u64 { 1 }
+/// #[get("/doc")]
+/// fn get() -> Tagged<&'static str> {
+///     Tagged(EntityTag::strong(current_version().to_string()), "the document")
+/// }
+///
+/// #[put("/doc", data = "<doc>")]
+/// fn put(if_match: &IfMatch, doc: String) -> Result<Tagged<String>, Status> {
+///     let current = EntityTag::strong(current_version().to_string());
+///     if !if_match.matches(Some(&current)) {
+///         return Err(Status::PreconditionFailed);
+///     }
+///
+///     /* store `doc` as the next version */
+///     Ok(Tagged(EntityTag::strong((current_version() + 1).to_string()), doc))
+/// }
+/// # fn main() {  }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tagged<R>(pub EntityTag, pub R);
+
+impl<R: Hash> Tagged<R> {
+    /// Wraps `responder`, using a strong entity tag derived from its hash.
+    ///
+    /// The hash is computed with the standard library's default hasher, whose
+    /// output isn't specified and may change between compiler versions. A
+    /// tag is therefore only stable for a given build of an application: after
+    /// a redeploy, or across instances built with different compilers, the
+    /// same value may have a different tag, causing clients to refetch it. Use
+    /// an explicit tag, such as a version number, if that matters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Tagged;
+    ///
+    /// let tagged = Tagged::hashed("{ 'resource': 'Hello, world!' }");
+    /// assert!(!tagged.0.is_weak());
+    /// assert!(!tagged.0.tag().is_empty());
+    ///
+    /// // Equal values have equal tags within a single build.
+    /// assert_eq!(Tagged::hashed("{ 'resource': 'Hello, world!' }").0, tagged.0);
+    /// ```
+    pub fn hashed(responder: R) -> Tagged<R> {
+        let tag = hash_tag(&responder);
+        Tagged(tag, responder)
+    }
+}
+
+/// Returns a strong entity tag for the hash of `value`.
+pub(crate) fn hash_tag<T: Hash + ?Sized>(value: &T) -> EntityTag {
+    let mut hasher = DefaultHasher::default();
+    value.hash(&mut hasher);
+    EntityTag::strong(hasher.finish().to_string())
+}
+
+/// Sets the `ETag` header, answering a matching `If-None-Match` for a `GET` or
+/// `HEAD` request with a `304 Not Modified`.
+impl<'r, R: Responder<'r>> Responder<'r> for Tagged<R> {
+    fn respond_to(self, req: &Request<'_>) -> response::Result<'r> {
+        let Tagged(tag, responder) = self;
+        if req.method() == Method::Get || req.method() == Method::Head {
+            let header = req.headers().get("If-None-Match").collect::<Vec<_>>().join(", ");
+            if let Ok(if_none_match) = header.parse::<IfNoneMatch>() {
+                if if_none_match.matches(Some(&tag)) {
+                    return Response::build().status(Status::NotModified).header(tag).ok();
+                }
+            }
+        }
+
+        let mut response = responder.respond_to(req)?;
+        response.set_header(tag);
+        Ok(response)
+    }
+}
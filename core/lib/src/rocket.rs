@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::str::from_utf8;
 use std::cmp::min;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::time::Duration;
 use std::mem;
 
@@ -14,15 +14,17 @@ use crate::{logger, handler};
 use crate::ext::ReadExt;
 use crate::config::{self, Config, LoggedValue};
 use crate::request::{Request, FormItems};
-use crate::data::Data;
+use crate::data::{Data, NetStream};
 use crate::response::{Body, Response};
+use crate::response::event_stream::OpenStreams;
+use crate::websocket::has_token;
 use crate::router::{Router, Route};
 use crate::catcher::{self, Catcher};
 use crate::outcome::Outcome;
 use crate::error::{LaunchError, LaunchErrorKind};
 use crate::fairing::{Fairing, Fairings};
 
-use crate::http::{Method, Status, Header};
+use crate::http::{Method, Status, StatusClass, Header, CookiePrefix};
 use crate::http::hyper::{self, header};
 use crate::http::uri::Origin;
 
@@ -50,7 +52,7 @@ impl hyper::Handler for Rocket {
         res: hyper::FreshResponse<'h>,
     ) {
         // Get all of the information from Hyper.
-        let (h_addr, h_method, h_headers, h_uri, _, h_body) = hyp_req.deconstruct();
+        let (h_addr, h_method, h_headers, h_uri, _, mut h_body) = hyp_req.deconstruct();
 
         // Convert the Hyper request into a Rocket request.
         let req_res = Request::from_hyp(self, h_method, h_headers, h_uri, h_addr);
@@ -68,8 +70,15 @@ impl hyper::Handler for Rocket {
             }
         };
 
+        // A request to upgrade the connection may be answered by handing the
+        // connection over to the application; keep a handle to it for that.
+        let connection = match is_upgrade_request(&req) {
+            true => NetStream::from_hyp(*h_body.get_mut().get_mut()),
+            false => None,
+        };
+
         // Retrieve the data from the hyper body.
-        let data = match Data::from_hyp(h_body) {
+        let data = match Data::from_hyp(h_body, self.config.timeouts) {
             Ok(data) => data,
             Err(reason) => {
                 error_!("Bad data in request: {}", reason);
@@ -79,11 +88,28 @@ impl hyper::Handler for Rocket {
         };
 
         // Dispatch the request to get a response, then write that response out.
-        let response = self.dispatch(&mut req, data);
-        self.issue_response(response, res)
+        let mut response = self.dispatch(&mut req, data);
+        let upgrade = response.take_upgrade()
+            .filter(|_| response.status() == Status::SwitchingProtocols);
+
+        match (upgrade, connection) {
+            (Some(upgrade), Some(connection)) => match self.write_response(response, res) {
+                Ok(_) => {
+                    info_!("{}", Paint::green("Switching protocols."));
+                    upgrade(connection);
+                }
+                Err(e) => error_!("Failed to write response: {:?}.", e),
+            },
+            _ => self.issue_response(response, res),
+        }
     }
 }
 
+/// Returns `true` if `req` asks to upgrade the connection to another protocol.
+fn is_upgrade_request(req: &Request<'_>) -> bool {
+    req.headers().contains("Upgrade") && has_token(req.headers().get("Connection"), "upgrade")
+}
+
 // This macro is a terrible hack to get around Hyper's Server<L> type. What we
 // want is to use almost exactly the same launch code when we're serving over
 // HTTPS as over HTTP. But Hyper forces two different types, so we can't use the
@@ -137,9 +163,14 @@ impl Rocket {
             hyp_res.headers_mut().append_raw(name, value);
         }
 
+        let flush = response.flushes_body();
         match response.body() {
             None => {
-                hyp_res.headers_mut().set(header::ContentLength(0));
+                // A `1xx` response never has a body nor a `Content-Length`.
+                if response.status().class() != StatusClass::Informational {
+                    hyp_res.headers_mut().set(header::ContentLength(0));
+                }
+
                 hyp_res.start()?.end()
             }
             Some(Body::Sized(body, size)) => {
@@ -158,10 +189,30 @@ impl Rocket {
                 // The buffer stores the current chunk being written out.
                 let mut buffer = vec![0; chunk_size as usize];
                 let mut stream = hyp_res.start()?;
+                if !flush {
+                    loop {
+                        match body.read_max(&mut buffer)? {
+                            0 => break,
+                            n => stream.write_all(&buffer[..n])?,
+                        }
+                    }
+
+                    return stream.end();
+                }
+
+                // A body that produces data slowly, like an event stream, asks
+                // for each chunk to be flushed as soon as it's read so that it
+                // reaches the client without waiting for a full buffer.
+                stream.flush()?;
                 loop {
-                    match body.read_max(&mut buffer)? {
-                        0 => break,
-                        n => stream.write_all(&buffer[..n])?,
+                    match body.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            stream.write_all(&buffer[..n])?;
+                            stream.flush()?;
+                        }
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) => return Err(e),
                     }
                 }
 
@@ -204,6 +255,16 @@ impl Rocket {
     ) -> Response<'r> {
         info!("{}:", request);
 
+        // Decode the body, rejecting encodings that can't be decoded.
+        #[cfg(any(feature = "gzip_decompression", feature = "brotli_decompression"))]
+        let data = match crate::data::decode::decode(request, data) {
+            Ok(data) => data,
+            Err(coding) => {
+                error_!("Unsupported request Content-Encoding: {}.", coding);
+                return self.handle_error(Status::UnsupportedMediaType, request);
+            }
+        };
+
         // Do a bit of preprocessing before routing.
         self.preprocess_request(request, &data);
 
@@ -213,8 +274,36 @@ impl Rocket {
         // Remember if the request is a `HEAD` request for later body stripping.
         let was_head_request = request.method() == Method::Head;
 
-        // Route the request and run the user's handlers.
-        let mut response = self.route_and_process(request, data);
+        // Route the request and run the user's handlers. If reading the body
+        // violated a timeout or limit, whatever the handler made of the failed
+        // read is replaced by the corresponding error, and the connection is
+        // closed since the rest of the body can't be trusted to arrive.
+        let abort = data.abort();
+        let mut response = match abort.status() {
+            Some(status) => self.handle_error(status, request),
+            None => self.route_and_process(request, data),
+        };
+
+        if let Some(status) = abort.status() {
+            if response.status() != status {
+                response = self.handle_error(status, request);
+            }
+
+            response.set_raw_header("Connection", "close");
+        }
+
+        // Apply the default cache policy for the path to successful responses
+        // that don't set their own. Responses setting cookies are skipped so
+        // that a public policy never lets a shared cache store them.
+        let status = response.status();
+        if (status.class() == StatusClass::Success || status == Status::NotModified)
+            && !response.headers().contains("Cache-Control")
+            && !response.headers().contains("Set-Cookie")
+        {
+            if let Some(cache_control) = self.config.cache.get(request.uri().path()) {
+                response.set_header(cache_control.clone());
+            }
+        }
 
         // Add a default 'Server' header if it isn't already there.
         // TODO: If removing Hyper, write out `Date` header too.
@@ -262,6 +351,14 @@ impl Rocket {
         // Set the cookies. Note that error responses will only include cookies
         // set by the error handler. See `handle_error` for more.
         for cookie in request.cookies().delta() {
+            let mut cookie = cookie.clone();
+            if let Some(prefix) = CookiePrefix::of(cookie.name()) {
+                for violation in prefix.enforce(&mut cookie) {
+                    warn_!("Cookie '{}' violates the '{}' prefix rules: {}.",
+                           cookie.name(), prefix, violation);
+                }
+            }
+
             response.adjoin_header(cookie);
         }
 
@@ -292,6 +389,18 @@ impl Rocket {
             info_!("Matched: {}", route);
             request.set_route(route);
 
+            // Reject a declared body that exceeds the limit before reading it,
+            // and cap the body at the limit in case it wasn't declared.
+            let limit = route.limit.or_else(|| request.limits().get("data"));
+            if let (Some(limit), Some(length)) = (limit, request.content_length()) {
+                if length > limit {
+                    error_!("Request body length ({}) exceeds limit ({}).", length, limit);
+                    return Outcome::Failure(Status::PayloadTooLarge);
+                }
+            }
+
+            data.limit().set(limit);
+
             // Dispatch the request to the handler.
             let outcome = route.handler.handle(request, data);
 
@@ -408,8 +517,23 @@ impl Rocket {
         launch_info_!("port: {}", Paint::default(&config.port).bold());
         launch_info_!("log: {}", Paint::default(config.log_level).bold());
         launch_info_!("workers: {}", Paint::default(config.workers).bold());
+        launch_info_!("event streams: {}", Paint::default(config.max_event_streams()).bold());
         launch_info_!("secret key: {}", Paint::default(&config.secret_key).bold());
+
+        #[cfg(feature = "private-cookies")]
+        {
+            let retired = config.retired_key_fingerprints();
+            if !retired.is_empty() {
+                launch_info_!("retired secret keys: {}",
+                              Paint::default(retired.join(", ")).bold());
+            }
+        }
+
         launch_info_!("limits: {}", Paint::default(&config.limits).bold());
+        launch_info_!("timeouts: {}", Paint::default(&config.timeouts).bold());
+        launch_info_!("validators: {}", Paint::default(&config.validators).bold());
+        launch_info_!("cache: {}", Paint::default(&config.cache).bold());
+        launch_info_!("cookies: {}", Paint::default(&config.cookies).bold());
 
         match config.keep_alive {
             Some(v) => launch_info_!("keep-alive: {}", Paint::default(format!("{}s", v)).bold()),
@@ -436,12 +560,15 @@ impl Rocket {
                           Paint::default(LoggedValue(value)).bold());
         }
 
+        let state = Container::new();
+        state.set(OpenStreams::default());
+
         Rocket {
             config,
             router: Router::new(),
             default_catchers: catcher::defaults::get(),
             catchers: catcher::defaults::get(),
-            state: Container::new(),
+            state,
             fairings: Fairings::new(),
         }
     }
@@ -713,6 +840,13 @@ impl Rocket {
             let timeout = self.config.keep_alive.map(|s| Duration::from_secs(s as u64));
             server.keep_alive(timeout);
 
+            // Set the timeout for reading request headers. Hyper replaces it
+            // with the keep-alive timeout after a connection's first request.
+            // Body reads are bounded separately, per request, in
+            // `Data::from_hyp`.
+            let timeout = self.config.timeouts.header.map(|s| Duration::from_secs(s as u64));
+            server.set_read_timeout(timeout);
+
             // Freeze managed state for synchronization-free accesses later.
             self.state.freeze();
 
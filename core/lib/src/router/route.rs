@@ -29,6 +29,10 @@ pub struct Route {
     pub rank: isize,
     /// The media type this route matches against, if any.
     pub format: Option<MediaType>,
+    /// The maximum size, in bytes, of a request body this route accepts, if
+    /// any. When `None`, the `data` limit applies, if it is set. See
+    /// [`Limits`](crate::config::Limits#request-body-limits).
+    pub limit: Option<u64>,
     /// Cached metadata that aids in routing later.
     pub(crate) metadata: Metadata
 }
@@ -182,6 +186,7 @@ impl Route {
         let mut route = Route {
             name: None,
             format: None,
+            limit: None,
             base: Origin::dummy(),
             handler: Box::new(handler),
             metadata: Metadata::default(),
@@ -310,6 +315,7 @@ impl fmt::Debug for Route {
             .field("uri", &self.uri)
             .field("rank", &self.rank)
             .field("format", &self.format)
+            .field("limit", &self.limit)
             .field("metadata", &self.metadata)
             .finish()
     }
@@ -321,6 +327,7 @@ impl From<&StaticRouteInfo> for Route {
         // This should never panic since `info.path` is statically checked.
         let mut route = Route::new(info.method, info.path, info.handler);
         route.format = info.format.clone();
+        route.limit = info.limit;
         route.name = Some(info.name);
         if let Some(rank) = info.rank {
             route.rank = rank;
@@ -0,0 +1,434 @@
+use std::io::{self, Read, Write, BufReader};
+use std::net::Shutdown;
+use std::time::{Duration, Instant};
+
+use crate::data::NetStream;
+use crate::http::hyper::net::NetworkStream;
+
+/// How long to wait for the client to acknowledge a close frame.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The largest payload of a control frame.
+const MAX_CONTROL_PAYLOAD: usize = 125;
+
+// Frame opcodes, RFC 6455 section 5.2.
+const CONTINUATION: u8 = 0x0;
+const TEXT: u8 = 0x1;
+const BINARY: u8 = 0x2;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xA;
+
+// Close status codes, RFC 6455 section 7.4.1.
+const NORMAL: u16 = 1000;
+const PROTOCOL_ERROR: u16 = 1002;
+const INVALID_DATA: u16 = 1007;
+const TOO_BIG: u16 = 1009;
+
+/// A message received or sent over a WebSocket [`Connection`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Message {
+    /// A UTF-8 text message.
+    Text(String),
+    /// A binary message.
+    Binary(Vec<u8>),
+}
+
+impl From<String> for Message {
+    #[inline(always)]
+    fn from(text: String) -> Message {
+        Message::Text(text)
+    }
+}
+
+impl From<&str> for Message {
+    #[inline(always)]
+    fn from(text: &str) -> Message {
+        Message::Text(text.to_string())
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    #[inline(always)]
+    fn from(bytes: Vec<u8>) -> Message {
+        Message::Binary(bytes)
+    }
+}
+
+/// A single frame read from the client.
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// Why reading a frame failed.
+enum Failure {
+    Io(io::Error),
+    Protocol(u16, &'static str),
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Failure {
+        Failure::Io(error)
+    }
+}
+
+/// An upgraded WebSocket connection.
+///
+/// A `Connection` is handed to the function passed to
+/// [`WebSocket::on_upgrade()`](crate::websocket::WebSocket::on_upgrade()). See
+/// the [module level docs](crate::websocket) for details.
+pub struct Connection {
+    stream: BufReader<NetStream>,
+    max_message_size: u64,
+    closed: bool,
+}
+
+impl Connection {
+    pub(crate) fn new(stream: NetStream, max_message_size: u64) -> Connection {
+        // The connection is idle between messages; don't time it out.
+        if let Err(e) = stream.set_read_timeout(None) {
+            warn_!("Failed to clear the WebSocket read timeout: {:?}", e);
+        }
+
+        Connection { stream: BufReader::new(stream), max_message_size, closed: false }
+    }
+
+    /// Blocks until the next message arrives and returns it, or returns `None`
+    /// once the connection is closed.
+    ///
+    /// Pings are answered and pongs are discarded while waiting. If the client
+    /// violates the protocol or sends a message larger than the limit, the
+    /// connection is closed with the corresponding status code and an error is
+    /// returned.
+    pub fn recv(&mut self) -> io::Result<Option<Message>> {
+        let mut partial: Option<(u8, Vec<u8>)> = None;
+        while !self.closed {
+            let received = partial.as_ref().map_or(0, |(_, data)| data.len() as u64);
+            let frame = match self.read_frame(self.max_message_size - received) {
+                Ok(frame) => frame,
+                Err(Failure::Io(e)) => {
+                    self.closed = true;
+                    return Err(e);
+                }
+                Err(Failure::Protocol(code, reason)) => return self.fail(code, reason),
+            };
+
+            match (frame.opcode, partial.take()) {
+                (PING, partial_message) => {
+                    self.write_frame(PONG, &frame.payload)?;
+                    partial = partial_message;
+                }
+                (PONG, partial_message) => partial = partial_message,
+                (CLOSE, _) => {
+                    // Echo the status code, if any, back to the client.
+                    let code_len = match frame.payload.len() {
+                        0 => 0,
+                        1 => return self.fail(PROTOCOL_ERROR, "malformed close frame"),
+                        _ => 2,
+                    };
+
+                    if std::str::from_utf8(&frame.payload[code_len..]).is_err() {
+                        return self.fail(INVALID_DATA, "close reason isn't UTF-8");
+                    }
+
+                    self.closed = true;
+                    let _ = self.write_frame(CLOSE, &frame.payload[..code_len]);
+                    return Ok(None);
+                }
+                (TEXT, None) | (BINARY, None) if frame.fin => {
+                    return self.message(frame.opcode, frame.payload).map(Some);
+                }
+                (TEXT, None) | (BINARY, None) => partial = Some((frame.opcode, frame.payload)),
+                (CONTINUATION, Some((opcode, mut data))) => {
+                    data.extend_from_slice(&frame.payload);
+                    match frame.fin {
+                        true => return self.message(opcode, data).map(Some),
+                        false => partial = Some((opcode, data)),
+                    }
+                }
+                (TEXT, Some(_)) | (BINARY, Some(_)) => {
+                    return self.fail(PROTOCOL_ERROR, "expected a continuation frame");
+                }
+                (CONTINUATION, None) => {
+                    return self.fail(PROTOCOL_ERROR, "unexpected continuation frame");
+                }
+                _ => return self.fail(PROTOCOL_ERROR, "unknown opcode"),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Sends `message` to the client.
+    pub fn send<M: Into<Message>>(&mut self, message: M) -> io::Result<()> {
+        match message.into() {
+            Message::Text(text) => self.write_frame(TEXT, text.as_bytes()),
+            Message::Binary(bytes) => self.write_frame(BINARY, &bytes),
+        }
+    }
+
+    /// Sends a ping carrying `payload`, of at most 125 bytes, to the client.
+    /// The client's pong is discarded by [`Connection::recv()`].
+    pub fn ping(&mut self, payload: &[u8]) -> io::Result<()> {
+        if payload.len() > MAX_CONTROL_PAYLOAD {
+            let msg = "ping payload exceeds 125 bytes";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        }
+
+        self.write_frame(PING, payload)
+    }
+
+    /// Closes the connection with the status `code` and `reason`, truncated to
+    /// 123 bytes, then waits briefly for the client to acknowledge the close.
+    /// Messages arriving in the meantime are discarded.
+    pub fn close(&mut self, code: u16, reason: &str) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+
+        self.closed = true;
+        self.write_close(code, reason)?;
+        self.stream.get_ref().set_read_timeout(Some(CLOSE_TIMEOUT))?;
+        loop {
+            match self.read_frame(self.max_message_size) {
+                Ok(frame) if frame.opcode == CLOSE => return Ok(()),
+                Ok(_) => continue,
+                Err(Failure::Io(e)) => return Err(e),
+                Err(Failure::Protocol(..)) => return Ok(()),
+            }
+        }
+    }
+
+    /// Returns `true` if the connection has been closed.
+    #[inline(always)]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Closes the connection, if it isn't already, and shuts the stream down.
+    ///
+    /// Whatever the client still sends is drained, for a bounded time, before
+    /// the stream is shut down so that unread data doesn't cause the
+    /// connection to be reset before the client reads the close frame.
+    pub(crate) fn shutdown(mut self) {
+        if let Err(e) = self.close(NORMAL, "") {
+            debug_!("Failed to close the WebSocket connection: {:?}", e);
+        }
+
+        let _ = self.stream.get_mut().close(Shutdown::Write);
+        let deadline = Instant::now() + CLOSE_TIMEOUT;
+        let mut buffer = [0u8; 512];
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let _ = self.stream.get_ref().set_read_timeout(Some(remaining));
+            match self.stream.read(&mut buffer) {
+                Ok(n) if n > 0 => continue,
+                _ => break,
+            }
+        }
+
+        let _ = self.stream.get_mut().close(Shutdown::Both);
+    }
+
+    /// Closes the connection with `code` after a violation and returns an
+    /// error describing it.
+    fn fail<T>(&mut self, code: u16, reason: &'static str) -> io::Result<T> {
+        if !self.closed {
+            self.closed = true;
+            let _ = self.write_close(code, reason);
+        }
+
+        Err(io::Error::new(io::ErrorKind::InvalidData, reason))
+    }
+
+    fn write_close(&mut self, code: u16, reason: &str) -> io::Result<()> {
+        let mut end = std::cmp::min(reason.len(), MAX_CONTROL_PAYLOAD - 2);
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let mut payload = code.to_be_bytes().to_vec();
+        payload.extend_from_slice(reason[..end].as_bytes());
+        self.write_frame(CLOSE, &payload)
+    }
+
+    /// Converts a complete message's data into a `Message`.
+    fn message(&mut self, opcode: u8, data: Vec<u8>) -> io::Result<Message> {
+        match opcode {
+            TEXT => match String::from_utf8(data) {
+                Ok(text) => Ok(Message::Text(text)),
+                Err(_) => self.fail(INVALID_DATA, "text message isn't UTF-8"),
+            },
+            _ => Ok(Message::Binary(data)),
+        }
+    }
+
+    /// Reads a frame, rejecting a data frame with a payload larger than
+    /// `limit` bytes.
+    fn read_frame(&mut self, limit: u64) -> Result<Frame, Failure> {
+        let mut head = [0u8; 2];
+        self.stream.read_exact(&mut head)?;
+
+        let (fin, opcode, masked) = (head[0] & 0x80 != 0, head[0] & 0x0F, head[1] & 0x80 != 0);
+        if head[0] & 0x70 != 0 {
+            return Err(Failure::Protocol(PROTOCOL_ERROR, "reserved bits are set"));
+        }
+
+        let len = match head[1] & 0x7F {
+            126 => {
+                let mut len = [0u8; 2];
+                self.stream.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as u64
+            }
+            127 => {
+                let mut len = [0u8; 8];
+                self.stream.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            }
+            len => len as u64,
+        };
+
+        if !masked {
+            return Err(Failure::Protocol(PROTOCOL_ERROR, "client frames must be masked"));
+        } else if opcode >= CLOSE && (!fin || len > MAX_CONTROL_PAYLOAD as u64) {
+            return Err(Failure::Protocol(PROTOCOL_ERROR, "malformed control frame"));
+        } else if opcode < CLOSE && len > limit {
+            return Err(Failure::Protocol(TOO_BIG, "message exceeds the size limit"));
+        }
+
+        let mut mask = [0u8; 4];
+        self.stream.read_exact(&mut mask)?;
+
+        let mut payload = vec![0u8; len as usize];
+        self.stream.read_exact(&mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        Ok(Frame { fin, opcode, payload })
+    }
+
+    /// Writes an unfragmented, unmasked frame and flushes it.
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 10);
+        frame.push(0x80 | opcode);
+        match payload.len() {
+            len if len < 126 => frame.push(len as u8),
+            len if len <= u16::max_value() as usize => {
+                frame.push(126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+
+        frame.extend_from_slice(payload);
+        let stream = self.stream.get_mut();
+        stream.write_all(&frame)?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    use super::{Connection, Message};
+    use crate::data::NetStream;
+    use crate::http::hyper::net::HttpStream;
+
+    // Serves an echo connection with a 16 byte limit on a local socket and
+    // returns the client's end.
+    fn echo_server() -> TcpStream {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let stream = NetStream::Http(HttpStream(listener.accept().unwrap().0));
+            let mut connection = Connection::new(stream, 16);
+            while let Ok(Some(message)) = connection.recv() {
+                connection.send(message).unwrap();
+            }
+
+            connection.shutdown();
+        });
+
+        TcpStream::connect(addr).unwrap()
+    }
+
+    // Writes a masked client frame.
+    fn send(client: &mut TcpStream, fin: bool, opcode: u8, payload: &[u8]) {
+        let mask = [0x37, 0xfa, 0x21, 0x3d];
+        let mut frame = vec![(fin as u8) << 7 | opcode, 0x80 | payload.len() as u8];
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        client.write_all(&frame).unwrap();
+    }
+
+    // Reads a short, unmasked server frame.
+    fn recv(client: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut head = [0u8; 2];
+        client.read_exact(&mut head).unwrap();
+        assert_eq!(head[0] & 0x80, 0x80);
+        assert_eq!(head[1] & 0x80, 0);
+        let mut payload = vec![0u8; head[1] as usize];
+        client.read_exact(&mut payload).unwrap();
+        (head[0] & 0x0F, payload)
+    }
+
+    #[test]
+    fn echoes_messages() {
+        let mut client = echo_server();
+        send(&mut client, true, 0x1, b"hello");
+        assert_eq!(recv(&mut client), (0x1, b"hello".to_vec()));
+
+        send(&mut client, true, 0x2, &[0, 159, 146, 150]);
+        assert_eq!(recv(&mut client), (0x2, vec![0, 159, 146, 150]));
+
+        // A fragmented message with a ping in between its fragments.
+        send(&mut client, false, 0x1, b"hel");
+        send(&mut client, true, 0x9, b"beat");
+        send(&mut client, true, 0x0, b"lo!");
+        assert_eq!(recv(&mut client), (0xA, b"beat".to_vec()));
+        assert_eq!(recv(&mut client), (0x1, b"hello!".to_vec()));
+
+        send(&mut client, true, 0x8, &1000u16.to_be_bytes());
+        assert_eq!(recv(&mut client), (0x8, 1000u16.to_be_bytes().to_vec()));
+        assert_eq!(client.read(&mut [0; 1]).unwrap(), 0);
+    }
+
+    #[test]
+    fn enforces_message_limit() {
+        let mut client = echo_server();
+        send(&mut client, false, 0x2, &[1; 10]);
+        send(&mut client, true, 0x0, &[2; 10]);
+        let (opcode, payload) = recv(&mut client);
+        assert_eq!(opcode, 0x8);
+        assert_eq!(&payload[..2], &1009u16.to_be_bytes());
+    }
+
+    #[test]
+    fn rejects_invalid_frames() {
+        let mut client = echo_server();
+        send(&mut client, true, 0x1, &[0xff, 0xfe]);
+        let (opcode, payload) = recv(&mut client);
+        assert_eq!(opcode, 0x8);
+        assert_eq!(&payload[..2], &1007u16.to_be_bytes());
+
+        let mut client = echo_server();
+        client.write_all(&[0x81, 0x00]).unwrap();
+        let (opcode, payload) = recv(&mut client);
+        assert_eq!(opcode, 0x8);
+        assert_eq!(&payload[..2], &1002u16.to_be_bytes());
+    }
+
+    #[test]
+    fn message_conversions() {
+        assert_eq!(Message::from("hi"), Message::Text("hi".into()));
+        assert_eq!(Message::from(vec![1, 2]), Message::Binary(vec![1, 2]));
+    }
+}
@@ -0,0 +1,146 @@
+use std::io;
+
+use crate::outcome::Outcome::*;
+use crate::request::{self, Request, FromRequest};
+use crate::response::{self, Response, Responder};
+use crate::http::{Method, Status};
+use crate::websocket::Connection;
+
+/// The GUID that the server appends to the client's key to compute the key it
+/// accepts the handshake with.
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The default maximum size of a message: 1MiB.
+const DEFAULT_LIMIT: u64 = 1 << 20;
+
+/// Request guard for a WebSocket handshake.
+///
+/// The guard succeeds for a `GET` request asking to upgrade to the WebSocket
+/// protocol. It forwards requests that don't ask for an upgrade and fails with
+/// a `400 Bad Request` when the handshake is malformed or requests a protocol
+/// version other than 13. See the [module level docs](crate::websocket) for
+/// details.
+#[derive(Debug, Clone)]
+pub struct WebSocket {
+    accept: String,
+    max_message_size: u64,
+}
+
+impl WebSocket {
+    /// Accepts the handshake: the returned responder answers it with a `101
+    /// Switching Protocols`, then calls `f` with the upgraded connection.
+    ///
+    /// If `f` returns an error, the error is logged. The connection is closed
+    /// once `f` returns, if it hasn't been closed already.
+    pub fn on_upgrade<'r, F>(self, f: F) -> Upgrade<'r>
+        where F: FnOnce(&mut Connection) -> io::Result<()> + 'r
+    {
+        Upgrade { handshake: self, handler: Box::new(f) }
+    }
+
+    /// Returns the maximum size, in bytes, of a message received over the
+    /// connection, set by the `websocket` limit.
+    #[inline(always)]
+    pub fn max_message_size(&self) -> u64 {
+        self.max_message_size
+    }
+}
+
+/// Returns `true` if the comma-separated `values` of a header contain `token`.
+pub(crate) fn has_token<'a, I: Iterator<Item = &'a str>>(mut values: I, token: &str) -> bool {
+    values.any(|value| value.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+}
+
+/// Returns the value of `Sec-WebSocket-Accept` for the client's `key`.
+fn accept_key(key: &str) -> String {
+    let mut sha1 = sha1::Sha1::new();
+    sha1.update(key.as_bytes());
+    sha1.update(GUID.as_bytes());
+
+    // `binascii` requires a bit more space than actual output for padding
+    let mut encoded = [0u8; 32];
+    let encoded = binascii::b64encode(&sha1.digest().bytes(), &mut encoded)
+        .expect("base64 of a SHA-1 digest fits in 32 bytes");
+
+    String::from_utf8_lossy(encoded).into_owned()
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for WebSocket {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let headers = request.headers();
+        if request.method() != Method::Get
+            || !has_token(headers.get("Upgrade"), "websocket")
+            || !has_token(headers.get("Connection"), "upgrade")
+        {
+            return Forward(());
+        }
+
+        if headers.get_one("Sec-WebSocket-Version") != Some("13") {
+            return Failure((Status::BadRequest, "unsupported WebSocket version"));
+        }
+
+        // The key must be the base64 encoding of 16 bytes.
+        let mut decoded = [0u8; 20];
+        let key = match headers.get_one("Sec-WebSocket-Key").map(|key| key.trim()) {
+            Some(key) if key.len() == 24 => key,
+            _ => return Failure((Status::BadRequest, "missing or malformed WebSocket key")),
+        };
+
+        match binascii::b64decode(key.as_bytes(), &mut decoded) {
+            Ok(bytes) if bytes.len() == 16 => {},
+            _ => return Failure((Status::BadRequest, "missing or malformed WebSocket key")),
+        }
+
+        Success(WebSocket {
+            accept: accept_key(key),
+            max_message_size: request.limits().get("websocket").unwrap_or(DEFAULT_LIMIT),
+        })
+    }
+}
+
+/// An accepted WebSocket handshake and the function serving the connection.
+///
+/// An `Upgrade` is returned by [`WebSocket::on_upgrade()`]. As a responder, it
+/// answers the handshake with a `101 Switching Protocols`; once the response
+/// is written, Rocket hands the connection to the function.
+pub struct Upgrade<'r> {
+    handshake: WebSocket,
+    handler: Box<dyn FnOnce(&mut Connection) -> io::Result<()> + 'r>,
+}
+
+impl<'r> Responder<'r> for Upgrade<'r> {
+    fn respond_to(self, _: &Request<'_>) -> response::Result<'r> {
+        let Upgrade { handshake, handler } = self;
+        let mut response = Response::build()
+            .status(Status::SwitchingProtocols)
+            .raw_header("Upgrade", "websocket")
+            .raw_header("Connection", "Upgrade")
+            .raw_header("Sec-WebSocket-Accept", handshake.accept)
+            .finalize();
+
+        let limit = handshake.max_message_size;
+        response.set_upgrade(Box::new(move |stream| {
+            let mut connection = Connection::new(stream, limit);
+            if let Err(e) = handler(&mut connection) {
+                warn_!("WebSocket connection failed: {}.", e);
+            }
+
+            connection.shutdown();
+        }));
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::accept_key;
+
+    #[test]
+    fn test_accept_key() {
+        // The example from RFC 6455, section 1.3.
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+}
@@ -0,0 +1,59 @@
+//! WebSocket connections.
+//!
+//! A route accepts a [WebSocket] handshake by requesting the [`WebSocket`]
+//! request guard and returning the [`Upgrade`] produced by
+//! [`WebSocket::on_upgrade()`]. Rocket answers the handshake with a `101
+//! Switching Protocols` response, then hands the connection to the upgrade's
+//! function as a [`Connection`] over which [`Message`]s are exchanged:
+//!
+//! ```rust
+//! # #![feature(proc_macro_hygiene)]
+//! # #[macro_use] extern crate rocket;
+//! use rocket::websocket::{WebSocket, Upgrade};
+//!
+//! #[get("/echo")]
+//! fn echo(ws: WebSocket) -> Upgrade<'static> {
+//!     ws.on_upgrade(|connection| {
+//!         while let Some(message) = connection.recv()? {
+//!             connection.send(message)?;
+//!         }
+//!
+//!         Ok(())
+//!     })
+//! }
+//! # fn main() {  }
+//! ```
+//!
+//! [WebSocket]: https://tools.ietf.org/html/rfc6455
+//!
+//! # Guards
+//!
+//! A handshake is an ordinary `GET` request until it's answered, so a route's
+//! other request guards, such as those checking cookies or credentials, run
+//! before the connection is upgraded and can refuse it with an error status.
+//! The `WebSocket` guard forwards requests that aren't handshakes, allowing
+//! another route to serve plain HTTP at the same path.
+//!
+//! # Protocol
+//!
+//! [`Connection::recv()`] answers pings with pongs and reassembles fragmented
+//! messages; a close frame from the client is answered in kind and ends the
+//! stream of messages. A client that violates the protocol, sends a text
+//! message that isn't valid UTF-8, or sends a message larger than the
+//! `websocket` [limit](crate::config::Limits), 1MiB by default, has its
+//! connection closed with the corresponding status code. When the upgrade's
+//! function returns, the connection is closed if it isn't already.
+//!
+//! # Concurrency
+//!
+//! Like every request, a connection is served by one of Rocket's workers,
+//! which stays occupied until the connection closes. Make sure that `workers`
+//! comfortably exceeds the number of connections expected at once.
+
+mod handshake;
+mod connection;
+
+pub use self::handshake::{WebSocket, Upgrade};
+pub use self::connection::{Connection, Message};
+
+pub(crate) use self::handshake::has_token;
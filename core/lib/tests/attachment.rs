@@ -0,0 +1,60 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::response::{Attachment, NamedFile};
+
+#[get("/report")]
+fn report() -> Attachment<&'static str> {
+    Attachment::new("résumé 2020.json", "{}")
+}
+
+#[get("/file")]
+fn file() -> Option<Attachment<NamedFile>> {
+    let file = NamedFile::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).ok()?;
+    Some(file.attachment("manifest.txt"))
+}
+
+#[get("/unknown")]
+fn unknown() -> Attachment<&'static str> {
+    Attachment::new("data.unknown-ext", "data")
+}
+
+mod attachment_tests {
+    use rocket::local::Client;
+    use rocket::http::{ContentType, Status};
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![super::report, super::file, super::unknown]))
+            .unwrap()
+    }
+
+    #[test]
+    fn encodes_name_and_infers_type() {
+        let client = client();
+        let mut response = client.get("/report").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"r_sum_ 2020.json\"; \
+                filename*=UTF-8''r%C3%A9sum%C3%A9%202020.json"));
+
+        assert_eq!(response.body_string(), Some("{}".into()));
+    }
+
+    #[test]
+    fn named_file_attachment() {
+        let client = client();
+        let response = client.get("/file").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"manifest.txt\""));
+    }
+
+    #[test]
+    fn unknown_extension_keeps_type() {
+        let client = client();
+        let response = client.get("/unknown").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+    }
+}
@@ -0,0 +1,88 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{CacheControl, Cookie, Cookies, Status};
+use rocket::response::Cached;
+
+#[get("/app.js")]
+fn asset() -> &'static str {
+    "console.log('hi')"
+}
+
+#[get("/users")]
+fn users() -> &'static str {
+    "[]"
+}
+
+#[get("/fresh")]
+fn fresh() -> Cached<&'static str> {
+    Cached(CacheControl::new().public().max_age(5).stale_while_revalidate(30), "fresh")
+}
+
+#[get("/login")]
+fn login(mut cookies: Cookies<'_>) -> &'static str {
+    cookies.add(Cookie::new("session", "abc"));
+    "welcome"
+}
+
+#[get("/missing")]
+fn missing() -> Status {
+    Status::NotFound
+}
+
+mod cache_control_tests {
+    use super::*;
+    use rocket::config::{Config, Environment, CacheDefaults};
+    use rocket::local::Client;
+
+    fn client() -> Client {
+        let cache = CacheDefaults::new()
+            .policy("/static", CacheControl::new().public().max_age(31536000).immutable())
+            .policy("/api", CacheControl::new().private().no_store());
+
+        let config = Config::build(Environment::Development).cache(cache).unwrap();
+        let rocket = rocket::custom(config)
+            .mount("/static", routes![asset])
+            .mount("/api", routes![users, fresh, login, missing])
+            .mount("/", routes![users]);
+
+        Client::new(rocket).unwrap()
+    }
+
+    fn cache_control(client: &Client, path: &str) -> Option<String> {
+        let response = client.get(path.to_string()).dispatch();
+        response.headers().get_one("Cache-Control").map(String::from)
+    }
+
+    #[test]
+    fn default_policies() {
+        let client = client();
+        assert_eq!(cache_control(&client, "/static/app.js").as_deref(),
+            Some("public, max-age=31536000, immutable"));
+
+        assert_eq!(cache_control(&client, "/api/users").as_deref(), Some("private, no-store"));
+        assert_eq!(cache_control(&client, "/users"), None);
+    }
+
+    #[test]
+    fn cached_overrides_default() {
+        let client = client();
+        assert_eq!(cache_control(&client, "/api/fresh").as_deref(),
+            Some("public, max-age=5, stale-while-revalidate=30"));
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let client = client();
+        assert_eq!(cache_control(&client, "/api/missing"), None);
+        assert_eq!(cache_control(&client, "/static/nope.js"), None);
+    }
+    #[test]
+    fn responses_with_cookies_are_not_cached() {
+        let client = client();
+        let response = client.get("/api/login").dispatch();
+        assert!(response.headers().get_one("Set-Cookie").is_some());
+        assert_eq!(response.headers().get_one("Cache-Control"), None);
+    }
+}
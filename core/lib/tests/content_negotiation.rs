@@ -0,0 +1,91 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::http::MediaType;
+use rocket::response::{content, Negotiate, Stream};
+
+#[get("/")]
+fn index() -> Negotiate<'static> {
+    Negotiate::new()
+        .variant(MediaType::JSON, content::Json("{ \"hi\": \"json\" }"))
+        .variant(MediaType::HTML, content::Html("<p>hi html</p>"))
+        .variant(MediaType::Plain, "hi plain")
+}
+
+#[get("/untyped")]
+fn untyped() -> Negotiate<'static> {
+    Negotiate::new().variant(MediaType::CSV, Stream::from(Cursor::new("a,b,c")))
+}
+
+mod content_negotiation_tests {
+    use super::*;
+
+    use rocket::local::Client;
+    use rocket::http::{Status, Header, ContentType};
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![index, untyped])).unwrap()
+    }
+
+    fn negotiate(client: &Client, accept: Option<&'static str>) -> (Status, Option<String>) {
+        let mut request = client.get("/");
+        if let Some(accept) = accept {
+            request.add_header(Header::new("Accept", accept));
+        }
+
+        let mut response = request.dispatch();
+        let vary: Vec<_> = response.headers().get("Vary").collect();
+        assert_eq!(vary, vec!["Accept"]);
+
+        (response.status(), response.body_string())
+    }
+
+    #[test]
+    fn chooses_preferred_variant() {
+        let client = client();
+        let (status, body) = negotiate(&client, None);
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "{ \"hi\": \"json\" }");
+
+        let (_, body) = negotiate(&client, Some("*/*"));
+        assert_eq!(body.unwrap(), "{ \"hi\": \"json\" }");
+
+        let (_, body) = negotiate(&client, Some("text/html"));
+        assert_eq!(body.unwrap(), "<p>hi html</p>");
+
+        let (_, body) = negotiate(&client, Some("text/*"));
+        assert_eq!(body.unwrap(), "<p>hi html</p>");
+
+        let (_, body) = negotiate(&client, Some("text/html; q=0.5, text/plain"));
+        assert_eq!(body.unwrap(), "hi plain");
+
+        let (_, body) = negotiate(&client, Some("application/json; q=0, */*"));
+        assert_eq!(body.unwrap(), "<p>hi html</p>");
+    }
+
+    #[test]
+    fn not_acceptable() {
+        let client = client();
+        let (status, _) = negotiate(&client, Some("image/png"));
+        assert_eq!(status, Status::NotAcceptable);
+
+        let (status, _) = negotiate(&client, Some("*/*; q=0"));
+        assert_eq!(status, Status::NotAcceptable);
+    }
+
+    #[test]
+    fn sets_content_type_of_chosen_variant() {
+        let client = client();
+        let response = client.get("/")
+            .header(Header::new("Accept", "text/plain"))
+            .dispatch();
+
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+
+        let response = client.get("/untyped").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::CSV));
+    }
+}
@@ -0,0 +1,135 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{Cookie, Cookies, SameSite};
+
+#[get("/plain")]
+fn plain(mut cookies: Cookies) {
+    cookies.add(Cookie::new("plain", "value"));
+    cookies.add(Cookie::build("explicit", "value")
+        .same_site(SameSite::Strict)
+        .secure(false)
+        .path("/explicit")
+        .finish());
+}
+
+#[cfg(feature = "private-cookies")]
+#[get("/private")]
+fn private(mut cookies: Cookies) {
+    cookies.add_private(Cookie::new("private", "value"));
+}
+
+#[get("/prefixed")]
+fn prefixed(mut cookies: Cookies) {
+    cookies.add(Cookie::new("__Secure-a", "value"));
+    cookies.add(Cookie::new("__Host-b", "value"));
+    cookies.add(Cookie::build("__Host-c", "value")
+        .secure(false)
+        .path("/sub")
+        .domain("rocket.rs")
+        .finish());
+}
+
+mod cookie_defaults_tests {
+    use super::*;
+
+    use rocket::local::Client;
+    use rocket::config::{Config, Environment};
+    use rocket::http::CookieDefaults;
+
+    fn client(defaults: CookieDefaults) -> Client {
+        let config = Config::build(Environment::Development)
+            .cookies(defaults)
+            .unwrap();
+
+        let rocket = rocket::custom(config).mount("/", routes![plain, prefixed]);
+
+        #[cfg(feature = "private-cookies")]
+        let rocket = rocket.mount("/", routes![private]);
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn cookie(client: &Client, uri: &'static str, name: &str) -> Cookie<'static> {
+        client.get(uri).dispatch()
+            .cookies().into_iter()
+            .find(|c| c.name() == name)
+            .map(|c| c.into_owned())
+            .expect("cookie was set")
+    }
+
+    #[test]
+    fn defaults_are_applied_to_unset_attributes() {
+        let client = client(CookieDefaults::new()
+            .same_site(SameSite::Lax)
+            .secure(true)
+            .http_only(true)
+            .path("/")
+            .domain("rocket.rs")
+            .max_age(time::Duration::hours(1)));
+
+        let plain = cookie(&client, "/plain", "plain");
+        assert_eq!(plain.same_site(), Some(SameSite::Lax));
+        assert_eq!(plain.secure(), Some(true));
+        assert_eq!(plain.http_only(), Some(true));
+        assert_eq!(plain.path(), Some("/"));
+        assert_eq!(plain.domain(), Some("rocket.rs"));
+        assert_eq!(plain.max_age(), Some(time::Duration::hours(1)));
+
+        let explicit = cookie(&client, "/plain", "explicit");
+        assert_eq!(explicit.same_site(), Some(SameSite::Strict));
+        assert_ne!(explicit.secure(), Some(true));
+        assert_eq!(explicit.path(), Some("/explicit"));
+        assert_eq!(explicit.domain(), Some("rocket.rs"));
+    }
+
+    #[test]
+    fn no_defaults_leaves_cookies_untouched() {
+        let client = client(CookieDefaults::new());
+        let plain = cookie(&client, "/plain", "plain");
+        assert_eq!(plain.same_site(), None);
+        assert_eq!(plain.secure(), None);
+        assert_eq!(plain.path(), None);
+        assert_eq!(plain.max_age(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "private-cookies")]
+    fn configured_defaults_take_precedence_for_private_cookies() {
+        let client = client(CookieDefaults::new()
+            .same_site(SameSite::Lax)
+            .secure(true)
+            .max_age(time::Duration::hours(1)));
+
+        let private = cookie(&client, "/private", "private");
+        assert_eq!(private.same_site(), Some(SameSite::Lax));
+        assert_eq!(private.secure(), Some(true));
+        assert_eq!(private.http_only(), Some(true));
+        assert_eq!(private.path(), Some("/"));
+        assert_eq!(private.max_age(), Some(time::Duration::hours(1)));
+        assert_eq!(private.expires(), None);
+    }
+
+    #[test]
+    fn prefix_rules_are_enforced() {
+        let client = client(CookieDefaults::new()
+            .secure(false)
+            .path("/app")
+            .domain("rocket.rs"));
+
+        let secure = cookie(&client, "/prefixed", "__Secure-a");
+        assert_eq!(secure.secure(), Some(true));
+        assert_eq!(secure.path(), Some("/app"));
+
+        let host = cookie(&client, "/prefixed", "__Host-b");
+        assert_eq!(host.secure(), Some(true));
+        assert_eq!(host.path(), Some("/"));
+        assert_eq!(host.domain(), None);
+
+        let host = cookie(&client, "/prefixed", "__Host-c");
+        assert_eq!(host.secure(), Some(true));
+        assert_eq!(host.path(), Some("/"));
+        assert_eq!(host.domain(), None);
+    }
+}
@@ -0,0 +1,89 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use rocket::request::LastEventId;
+use rocket::response::{Event, EventStream};
+
+#[get("/events")]
+fn events(last: Option<LastEventId<'_>>) -> EventStream<'static> {
+    let start: u32 = last.and_then(|id| id.as_str().parse().ok()).unwrap_or(0);
+    let events = (start + 1..=start + 2)
+        .map(|n| Event::data(format!("n = {}", n)).id(n.to_string()));
+    EventStream::new(events)
+}
+
+#[get("/channel")]
+fn channel() -> EventStream<'static> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        tx.send(Event::data("first\nsecond").event("update")).unwrap();
+        thread::sleep(Duration::from_millis(250));
+        tx.send(Event::empty().retry(Duration::from_secs(3))).unwrap();
+    });
+
+    EventStream::from(rx).heartbeat(Duration::from_millis(100))
+}
+
+mod event_stream_tests {
+    use rocket::config::{Config, Environment};
+    use rocket::local::Client;
+    use rocket::http::{ContentType, Header, Status};
+
+    fn client(max_streams: u16) -> Client {
+        let config = Config::build(Environment::Development)
+            .event_streams(max_streams)
+            .unwrap();
+
+        let rocket = rocket::custom(config).mount("/", routes![super::events, super::channel]);
+        Client::new(rocket).unwrap()
+    }
+
+    #[test]
+    fn streams_framed_events() {
+        let client = client(4);
+        let mut response = client.get("/events").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::EventStream));
+        assert_eq!(response.headers().get_one("Cache-Control"), Some("no-cache"));
+        assert_eq!(response.body_string().unwrap(), "id: 1\ndata: n = 1\n\nid: 2\ndata: n = 2\n\n");
+    }
+
+    #[test]
+    fn resumes_from_last_event_id() {
+        let client = client(4);
+        let mut response = client.get("/events")
+            .header(Header::new("Last-Event-ID", "7"))
+            .dispatch();
+
+        assert_eq!(response.body_string().unwrap(), "id: 8\ndata: n = 8\n\nid: 9\ndata: n = 9\n\n");
+    }
+
+    #[test]
+    fn sends_heartbeats_while_waiting() {
+        let client = client(4);
+        let mut response = client.get("/channel").dispatch();
+        let body = response.body_string().unwrap();
+        assert!(body.starts_with("event: update\ndata: first\ndata: second\n\n:\n\n"));
+        assert!(body.ends_with(":\n\nretry: 3000\n\n"));
+    }
+
+    #[test]
+    fn bounds_concurrent_streams() {
+        let client = client(1);
+        let open = client.get("/events").dispatch();
+        assert_eq!(open.status(), Status::Ok);
+
+        let refused = client.get("/events").dispatch();
+        assert_eq!(refused.status(), Status::ServiceUnavailable);
+
+        drop(open);
+        let mut reopened = client.get("/events").dispatch();
+        assert_eq!(reopened.status(), Status::Ok);
+        assert!(reopened.body_string().unwrap().starts_with("id: 1\n"));
+    }
+}
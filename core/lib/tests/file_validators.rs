@@ -0,0 +1,111 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::response::NamedFile;
+
+#[get("/file")]
+fn file() -> Option<NamedFile> {
+    NamedFile::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).ok()
+}
+
+mod file_validators_tests {
+    use rocket::config::{Config, Environment, Validators};
+    use rocket::local::Client;
+    use rocket::http::{Header, Status};
+
+    fn client(validators: Validators) -> Client {
+        let config = Config::build(Environment::Development).validators(validators).unwrap();
+        Client::new(rocket::custom(config).mount("/", routes![super::file])).unwrap()
+    }
+
+    fn validators(client: &Client) -> (Option<String>, Option<String>) {
+        let response = client.get("/file").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let etag = response.headers().get_one("ETag").map(String::from);
+        let modified = response.headers().get_one("Last-Modified").map(String::from);
+        (etag, modified)
+    }
+
+    #[test]
+    fn if_none_match() {
+        let client = client(Validators::default());
+        let etag = validators(&client).0.expect("ETag");
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+
+        for value in &[etag.clone(), format!("W/{}", etag), format!("\"nope\", {}", etag), "*".into()] {
+            let mut response = client.get("/file")
+                .header(Header::new("If-None-Match", value.clone()))
+                .dispatch();
+
+            assert_eq!(response.status(), Status::NotModified);
+            assert_eq!(response.headers().get_one("ETag"), Some(&*etag));
+            assert!(response.body_bytes().map_or(true, |body| body.is_empty()));
+        }
+
+        let response = client.get("/file").header(Header::new("If-None-Match", "\"nope\"")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn if_modified_since() {
+        let client = client(Validators::default());
+        let modified = validators(&client).1.expect("Last-Modified");
+        assert!(modified.ends_with(" GMT"));
+
+        let response = client.get("/file")
+            .header(Header::new("If-Modified-Since", modified.clone()))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotModified);
+
+        let response = client.get("/file")
+            .header(Header::new("If-Modified-Since", "Thu, 01 Jan 1970 00:00:00 GMT"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+
+        // `If-None-Match` takes precedence over `If-Modified-Since`.
+        let response = client.get("/file")
+            .header(Header::new("If-Modified-Since", modified))
+            .header(Header::new("If-None-Match", "\"nope\""))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn disabled_validators() {
+        let client = client(Validators { etag: false, last_modified: true });
+        let (etag, modified) = validators(&client);
+        assert!(etag.is_none() && modified.is_some());
+
+        // The file exists, so `*` matches it even without an entity tag.
+        let response = client.get("/file").header(Header::new("If-None-Match", "*")).dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+
+        let client = client(Validators { etag: false, last_modified: false });
+        assert_eq!(validators(&client), (None, None));
+    }
+
+    #[test]
+    fn if_range() {
+        let client = client(Validators::default());
+        let (etag, modified) = validators(&client);
+        for validator in &[etag.unwrap(), modified.unwrap()] {
+            let response = client.get("/file")
+                .header(Header::new("Range", "bytes=0-8"))
+                .header(Header::new("If-Range", validator.clone()))
+                .dispatch();
+
+            assert_eq!(response.status(), Status::PartialContent);
+        }
+
+        let response = client.get("/file")
+            .header(Header::new("Range", "bytes=0-8"))
+            .header(Header::new("If-Range", "\"stale\""))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
@@ -0,0 +1,64 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::request::{Form, FormError, FormDataError};
+
+#[derive(FromForm)]
+struct Signup {
+    #[form(validate = len(1..=16))]
+    name: String,
+    #[form(validate = email)]
+    email: String,
+    #[form(validate = range(13..))]
+    age: u8,
+}
+
+#[post("/", data = "<signup>")]
+fn signup(signup: Result<Form<Signup>, FormError<'_>>) -> String {
+    match signup {
+        Ok(form) => format!("welcome, {}", form.name),
+        Err(FormDataError::Parse(errors, _)) => {
+            let mut fields = vec![];
+            for field in &["name", "email", "age"] {
+                fields.push(format!("{}={:?}/{:?}", field, errors.value(field), errors.get(field)));
+            }
+
+            fields.join(" ")
+        }
+        Err(_) => "malformed".into()
+    }
+}
+
+mod form_validation_tests {
+    use super::*;
+    use rocket::local::Client;
+    use rocket::http::{Status, ContentType};
+
+    fn post(body: &str) -> (Status, String) {
+        let client = Client::new(rocket::ignite().mount("/", routes![signup])).unwrap();
+        let mut response = client.post("/").header(ContentType::Form).body(body).dispatch();
+        (response.status(), response.body_string().unwrap())
+    }
+
+    #[test]
+    fn valid_form() {
+        let (status, body) = post("name=Bob&email=bob%40example.com&age=30");
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body, "welcome, Bob");
+    }
+
+    #[test]
+    fn every_error_with_submitted_values() {
+        let (status, body) = post("name=&email=bob+smith&age=x");
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body, "name=Some(\"\")/Some(\"length must be at least 1 and at most 16\") \
+            email=Some(\"bob smith\")/Some(\"value must be an email address\") \
+            age=Some(\"x\")/Some(\"invalid value\")");
+
+        let (_, body) = post("email=bob%40example.com&age=12");
+        assert_eq!(body, "name=None/Some(\"missing field\") \
+            email=Some(\"bob@example.com\")/None \
+            age=Some(\"12\")/Some(\"value must be at least 13\")");
+    }
+}
@@ -0,0 +1,231 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::io::Read;
+
+use rocket::request::{Form, FormItems, FromForm, TempFile};
+
+#[derive(FromForm)]
+struct Upload {
+    description: String,
+    file: TempFile,
+    extra: Option<TempFile>,
+}
+
+#[post("/", data = "<form>")]
+fn upload(form: Form<Upload>) -> String {
+    let upload = form.into_inner();
+    let mut contents = String::new();
+    upload.file.open().unwrap().read_to_string(&mut contents).unwrap();
+    format!("{}: {} ({:?}, {}, {:?}) {}", upload.description, contents,
+        upload.file.file_name(), upload.file.len(),
+        upload.file.content_type().map(|ct| ct.to_string()),
+        upload.extra.is_some())
+}
+
+#[derive(FromForm)]
+struct Persist {
+    file: TempFile,
+}
+
+#[post("/persist", data = "<form>")]
+fn persist(form: Form<Persist>) -> String {
+    let mut file = form.into_inner().file;
+    let temp_path = file.path().to_path_buf();
+    let path = std::env::temp_dir().join(format!("rocket-persist-test-{}", std::process::id()));
+    file.persist_to(&path).unwrap();
+    drop(file);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    format!("{} {}", temp_path.exists(), contents)
+}
+
+#[derive(FromForm)]
+struct Gallery {
+    images: Vec<TempFile>,
+}
+
+#[post("/gallery", data = "<form>")]
+fn gallery(form: Form<Gallery>) -> String {
+    let names: Vec<_> = form.images.iter().filter_map(|image| image.file_name()).collect();
+    names.join(",")
+}
+
+/// Collects every file in the form, whatever its field, by hand.
+struct AnyFiles(Vec<TempFile>);
+
+impl<'f> FromForm<'f> for AnyFiles {
+    type Error = ();
+
+    fn from_form(items: &mut FormItems<'f>, _: bool) -> Result<AnyFiles, ()> {
+        Ok(AnyFiles(items.filter_map(|item| item.file.and_then(|file| file.take())).collect()))
+    }
+}
+
+#[post("/any", data = "<form>")]
+fn any(form: Form<AnyFiles>) -> String {
+    form.0.iter().map(|file| file.len().to_string()).collect::<Vec<_>>().join(",")
+}
+
+mod multipart_form_tests {
+    use super::*;
+    use rocket::config::{Environment, Config, Limits};
+    use rocket::local::Client;
+    use rocket::http::{Status, ContentType};
+
+    const BOUNDARY: &str = "X-BOUNDARY";
+
+    fn rocket(limits: Limits) -> rocket::Rocket {
+        let config = Config::build(Environment::Development).limits(limits).unwrap();
+        rocket::custom(config).mount("/", routes![upload, persist, gallery, any])
+    }
+
+    fn multipart() -> ContentType {
+        ContentType::with_params("multipart", "form-data", ("boundary", BOUNDARY))
+    }
+
+    fn body(parts: &[(&str, Option<&str>, &str)]) -> String {
+        let mut body = String::new();
+        for &(name, file_name, value) in parts {
+            body.push_str(&format!("--{}\r\n", BOUNDARY));
+            match file_name {
+                Some(file_name) => {
+                    body.push_str(&format!("Content-Disposition: form-data; name=\"{}\"; \
+                        filename=\"{}\"\r\nContent-Type: text/plain\r\n\r\n", name, file_name));
+                }
+                None => {
+                    body.push_str(&format!("Content-Disposition: form-data; \
+                        name=\"{}\"\r\n\r\n", name));
+                }
+            }
+
+            body.push_str(value);
+            body.push_str("\r\n");
+        }
+
+        body.push_str(&format!("--{}--\r\n", BOUNDARY));
+        body
+    }
+
+    fn post(client: &Client, uri: &'static str, body: String) -> (Status, Option<String>) {
+        let mut response = client.post(uri).header(multipart()).body(body).dispatch();
+        (response.status(), response.body_string())
+    }
+
+    #[test]
+    fn text_and_files() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let (status, body) = post(&client, "/", body(&[
+            ("description", None, "A file & more"),
+            ("file", Some("../dir/hello.txt"), "Hello,\r\n--world!"),
+            ("extra", Some(""), ""),
+        ]));
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "A file & more: Hello,\r\n--world! \
+            (Some(\"hello.txt\"), 16, Some(\"text/plain\"), false)");
+
+        let (status, body) = post(&client, "/", body(&[
+            ("extra", Some("extra.bin"), "\0\u{1}"),
+            ("file", Some("a.txt"), ""),
+            ("description", None, "empty"),
+        ]));
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "empty:  (Some(\"a.txt\"), 0, Some(\"text/plain\"), true)");
+    }
+
+    #[test]
+    fn persisted_files() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let (status, body) = post(&client, "/persist", body(&[("file", Some("f"), "kept")]));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "false kept");
+    }
+
+    #[test]
+    fn files_reach_collections_and_manual_impls() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let (status, body) = post(&client, "/gallery", body(&[
+            ("images", Some("a.png"), "a"),
+            ("images", Some("b.png"), "bb"),
+        ]));
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "a.png,b.png");
+
+        let (status, body) = post(&client, "/any", body(&[
+            ("first", Some("a"), "a"),
+            ("text", None, "ignored"),
+            ("second", Some("b"), "bbb"),
+        ]));
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body.unwrap(), "1,3");
+    }
+
+    #[test]
+    fn files_must_be_uploaded() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let (status, _) = post(&client, "/", body(&[
+            ("description", None, "text"),
+            ("file", None, "not a file"),
+        ]));
+
+        assert_eq!(status, Status::UnprocessableEntity);
+
+        let response = client.post("/persist")
+            .header(ContentType::Form)
+            .body("file=%2Fetc%2Fpasswd")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        let limits = Limits::default().limit("file", 4);
+        let client = Client::new(rocket(limits)).unwrap();
+        let (status, _) = post(&client, "/persist", body(&[("file", Some("f"), "four")]));
+        assert_eq!(status, Status::Ok);
+
+        let (status, _) = post(&client, "/persist", body(&[("file", Some("f"), "five!")]));
+        assert_eq!(status, Status::PayloadTooLarge);
+
+        let limits = Limits::default().limit("data-form", 64);
+        let client = Client::new(rocket(limits)).unwrap();
+        let (status, _) = post(&client, "/persist", body(&[("file", Some("f"), &"x".repeat(64))]));
+        assert_eq!(status, Status::PayloadTooLarge);
+
+        let limits = Limits::default().limit("forms", 4);
+        let client = Client::new(rocket(limits)).unwrap();
+        let (status, _) = post(&client, "/", body(&[
+            ("description", None, "too long"),
+            ("file", Some("f"), "short"),
+        ]));
+
+        assert_eq!(status, Status::PayloadTooLarge);
+    }
+
+    #[test]
+    fn malformed_forms() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let (status, _) = post(&client, "/persist", format!("--{}\r\nfile", BOUNDARY));
+        assert_eq!(status, Status::BadRequest);
+
+        let missing_name = format!("--{b}\r\nContent-Disposition: form-data\r\n\r\nx\r\n--{b}--",
+            b = BOUNDARY);
+
+        let (status, _) = post(&client, "/persist", missing_name);
+        assert_eq!(status, Status::BadRequest);
+
+        let response = client.post("/persist")
+            .header(ContentType::new("multipart", "form-data"))
+            .body(body(&[("file", Some("f"), "x")]))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
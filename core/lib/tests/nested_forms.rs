@@ -0,0 +1,89 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::collections::HashMap;
+
+use rocket::request::Form;
+
+#[derive(FromForm, UriDisplayQuery)]
+struct Filter {
+    tags: Vec<String>,
+    range: Range,
+    weights: HashMap<String, usize>,
+}
+
+#[derive(FromForm, UriDisplayQuery)]
+struct Range {
+    min: usize,
+    max: usize,
+}
+
+fn describe(filter: &Filter) -> String {
+    let mut weights = filter.weights.iter().collect::<Vec<_>>();
+    weights.sort();
+    format!("{:?} {}..{} {:?}", filter.tags, filter.range.min, filter.range.max, weights)
+}
+
+#[get("/?<filter..>")]
+fn query(filter: Form<Filter>) -> String {
+    describe(&filter)
+}
+
+#[post("/", data = "<filter>")]
+fn body(filter: Form<Filter>) -> String {
+    describe(&filter)
+}
+
+mod nested_forms_tests {
+    use super::*;
+    use rocket::local::Client;
+    use rocket::http::{Status, ContentType};
+
+    const EXPECTED: &str = r#"["a", "b"] 1..5 [("x", 2)]"#;
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![query, body])).unwrap()
+    }
+
+    #[test]
+    fn nested_query() {
+        let client = client();
+        let mut response = client.get("/?tags[]=a&tags[]=b&range.min=1&range[max]=5&weights.x=2")
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some(EXPECTED.into()));
+
+        let response = client.get("/?tags[]=a&range.min=1").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn nested_body() {
+        let client = client();
+        let mut response = client.post("/")
+            .header(ContentType::Form)
+            .body("tags%5B0%5D=a&tags%5B1%5D=b&range%5Bmin%5D=1&range.max=5&weights%5Bx%5D=2")
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some(EXPECTED.into()));
+    }
+
+    #[test]
+    fn uri_round_trip() {
+        let mut weights = HashMap::new();
+        weights.insert("x".to_string(), 2);
+        let filter = Filter {
+            tags: vec!["a".into(), "b".into()],
+            range: Range { min: 1, max: 5 },
+            weights,
+        };
+
+        let uri = uri!(query: filter);
+        assert_eq!(uri.to_string(), "/?tags.0=a&tags.1=b&range.min=1&range.max=5&weights.x=2");
+
+        let client = client();
+        let mut response = client.get(uri.to_string()).dispatch();
+        assert_eq!(response.body_string(), Some(EXPECTED.into()));
+    }
+}
@@ -0,0 +1,133 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::State;
+use rocket::http::{EntityTag, IfMatch, IfNoneMatch, Status};
+use rocket::response::{Tagged, status::Created};
+
+struct Version(AtomicUsize);
+
+impl Version {
+    fn tag(&self) -> EntityTag {
+        EntityTag::strong(self.0.load(Ordering::SeqCst).to_string())
+    }
+}
+
+#[get("/doc")]
+fn get(version: State<'_, Version>) -> Tagged<&'static str> {
+    Tagged(version.tag(), "document")
+}
+
+#[put("/doc")]
+fn put(version: State<'_, Version>, if_match: Result<&IfMatch, String>) -> Result<Tagged<()>, Status> {
+    let if_match = if_match.map_err(|_| Status::BadRequest)?;
+    if !if_match.matches(Some(&version.tag())) {
+        return Err(Status::PreconditionFailed);
+    }
+
+    version.0.fetch_add(1, Ordering::SeqCst);
+    Ok(Tagged(version.tag(), ()))
+}
+
+#[put("/doc", rank = 2)]
+fn put_unconditional() -> Status {
+    Status::PreconditionRequired
+}
+
+#[post("/doc")]
+fn create(if_none_match: Option<&IfNoneMatch>) -> Result<Created<&'static str>, Status> {
+    // There is never a current representation at `/new`.
+    match if_none_match.map_or(true, |header| !header.matches(None)) {
+        true => Ok(Created::new("/new").tagged_body("created")),
+        false => Err(Status::PreconditionFailed),
+    }
+}
+
+#[get("/hashed")]
+fn hashed() -> Tagged<&'static str> {
+    Tagged::hashed("created")
+}
+
+mod preconditions_tests {
+    use super::*;
+    use rocket::local::Client;
+    use rocket::http::Header;
+
+    fn client() -> Client {
+        let rocket = rocket::ignite()
+            .manage(Version(AtomicUsize::new(1)))
+            .mount("/", routes![get, put, put_unconditional, create, hashed]);
+
+        Client::new(rocket).unwrap()
+    }
+
+    #[test]
+    fn tagged_sets_etag() {
+        let client = client();
+        let mut response = client.get("/doc").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("ETag"), Some("\"1\""));
+        assert_eq!(response.body_string(), Some("document".into()));
+    }
+
+    #[test]
+    fn tagged_not_modified() {
+        let client = client();
+        for value in &["\"1\"", "W/\"1\"", "\"0\", \"1\"", "*"] {
+            let response = client.get("/doc").header(Header::new("If-None-Match", *value)).dispatch();
+            assert_eq!(response.status(), Status::NotModified);
+            assert_eq!(response.headers().get_one("ETag"), Some("\"1\""));
+        }
+
+        let response = client.get("/doc").header(Header::new("If-None-Match", "\"2\"")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn if_match() {
+        let client = client();
+        let response = client.put("/doc").header(Header::new("If-Match", "\"1\"")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("ETag"), Some("\"2\""));
+
+        // The update above made the client's tag stale.
+        let response = client.put("/doc").header(Header::new("If-Match", "\"1\"")).dispatch();
+        assert_eq!(response.status(), Status::PreconditionFailed);
+
+        // `If-Match` uses the strong comparison.
+        let response = client.put("/doc").header(Header::new("If-Match", "W/\"2\"")).dispatch();
+        assert_eq!(response.status(), Status::PreconditionFailed);
+
+        let response = client.put("/doc").header(Header::new("If-Match", "*")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn missing_or_malformed_if_match() {
+        let client = client();
+        let response = client.put("/doc").dispatch();
+        assert_eq!(response.status(), Status::PreconditionRequired);
+
+        let response = client.put("/doc").header(Header::new("If-Match", "1")).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn if_none_match_any() {
+        let client = client();
+        let response = client.post("/doc").header(Header::new("If-None-Match", "*")).dispatch();
+        assert_eq!(response.status(), Status::Created);
+    }
+
+    #[test]
+    fn hashed_matches_created() {
+        let client = client();
+        let created = client.post("/doc").dispatch();
+        let hashed = client.get("/hashed").dispatch();
+        assert!(created.headers().get_one("ETag").is_some());
+        assert_eq!(created.headers().get_one("ETag"), hashed.headers().get_one("ETag"));
+    }
+}
@@ -0,0 +1,115 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::http::ContentType;
+use rocket::response::{NamedFile, Ranged};
+
+const BODY: &str = "0123456789abcdefghij";
+
+#[get("/")]
+fn ranged() -> Ranged<Cursor<&'static str>> {
+    Ranged::new(Cursor::new(BODY)).content_type(ContentType::Plain)
+}
+
+#[get("/file")]
+fn file() -> Option<NamedFile> {
+    NamedFile::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).ok()
+}
+
+mod range_requests_tests {
+    use super::BODY;
+
+    use rocket::local::Client;
+    use rocket::http::{Header, Status};
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![super::ranged, super::file])).unwrap()
+    }
+
+    #[test]
+    fn no_range() {
+        let client = client();
+        let mut response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+        assert_eq!(response.body_string(), Some(BODY.into()));
+    }
+
+    #[test]
+    fn single_range() {
+        let client = client();
+        for &(range, content_range, body) in &[
+            ("bytes=0-4", "bytes 0-4/20", "01234"),
+            ("bytes=15-", "bytes 15-19/20", "fghij"),
+            ("bytes=-3", "bytes 17-19/20", "hij"),
+            ("bytes=18-100", "bytes 18-19/20", "ij"),
+        ] {
+            let mut response = client.get("/").header(Header::new("Range", range)).dispatch();
+            assert_eq!(response.status(), Status::PartialContent);
+            assert_eq!(response.headers().get_one("Content-Range"), Some(content_range));
+            assert_eq!(response.headers().get_one("Content-Type"), Some("text/plain; charset=utf-8"));
+            assert_eq!(response.body_string(), Some(body.into()));
+        }
+    }
+
+    #[test]
+    fn multiple_ranges() {
+        let client = client();
+        let mut response = client.get("/").header(Header::new("Range", "bytes=0-1, 10-12")).dispatch();
+        assert_eq!(response.status(), Status::PartialContent);
+
+        let content_type = response.content_type().unwrap();
+        assert_eq!((content_type.top(), content_type.sub()), ("multipart", "byteranges"));
+        let boundary = content_type.params().find(|&(k, _)| k == "boundary").unwrap().1.to_string();
+
+        let expected = format!("--{b}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+            Content-Range: bytes 0-1/20\r\n\r\n01\r\n--{b}\r\n\
+            Content-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 10-12/20\r\n\r\n\
+            abc\r\n--{b}--\r\n", b = boundary);
+
+        assert_eq!(response.body_string(), Some(expected));
+    }
+
+    #[test]
+    fn unsatisfiable_range() {
+        let client = client();
+        let response = client.get("/").header(Header::new("Range", "bytes=20-")).dispatch();
+        assert_eq!(response.status(), Status::RangeNotSatisfiable);
+        assert_eq!(response.headers().get_one("Content-Range"), Some("bytes */20"));
+    }
+
+    #[test]
+    fn ignored_ranges() {
+        let client = client();
+        for range in &["bytes=5-1", "items=0-1", "bytes=x-"] {
+            let mut response = client.get("/").header(Header::new("Range", *range)).dispatch();
+            assert_eq!(response.status(), Status::Ok);
+            assert_eq!(response.body_string(), Some(BODY.into()));
+        }
+    }
+
+    #[test]
+    fn if_range_without_validators() {
+        // The response has no validators, so `If-Range` never matches.
+        let client = client();
+        let mut response = client.get("/")
+            .header(Header::new("Range", "bytes=0-1"))
+            .header(Header::new("If-Range", "\"abc\""))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some(BODY.into()));
+    }
+
+    #[test]
+    fn named_file_range() {
+        let client = client();
+        let mut response = client.get("/file").header(Header::new("Range", "bytes=0-8")).dispatch();
+        assert_eq!(response.status(), Status::PartialContent);
+        assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+        assert_eq!(response.body_string(), Some("[package]".into()));
+    }
+}
@@ -0,0 +1,103 @@
+#![feature(proc_macro_hygiene)]
+
+#[cfg(feature = "gzip_decompression")]
+#[macro_use] extern crate rocket;
+
+#[cfg(feature = "gzip_decompression")]
+mod request_decompression_tests {
+    use std::io::{Read, Write};
+
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+
+    use rocket::{Rocket, Data};
+    use rocket::config::{Environment, Config, Limits};
+    use rocket::local::Client;
+    use rocket::http::{Header, Status};
+
+    #[post("/", data = "<data>")]
+    fn echo(data: Data) -> Result<String, Status> {
+        let mut string = String::new();
+        data.open().read_to_string(&mut string).map_err(|_| Status::BadRequest)?;
+        Ok(string)
+    }
+
+    fn rocket(limits: Limits) -> Rocket {
+        let config = Config::build(Environment::Development).limits(limits).unwrap();
+        rocket::custom(config).mount("/", routes![echo])
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn deflate(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "brotli_decompression")]
+    fn brotli(bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![];
+        {
+            // The stream is finished when the encoder is dropped.
+            let mut encoder = brotli::CompressorWriter::new(&mut encoded, 4096, 5, 22);
+            encoder.write_all(bytes).unwrap();
+        }
+
+        encoded
+    }
+
+    fn post(client: &Client, encoding: &'static str, body: Vec<u8>) -> (Status, Option<String>) {
+        let mut response = client.post("/")
+            .header(Header::new("Content-Encoding", encoding))
+            .body(body)
+            .dispatch();
+
+        (response.status(), response.body_string())
+    }
+
+    #[test]
+    fn decodes_body() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let body = "hello, world!";
+        assert_eq!(post(&client, "gzip", gzip(body.as_bytes())), (Status::Ok, Some(body.into())));
+        assert_eq!(post(&client, "GZIP", gzip(body.as_bytes())).1, Some(body.into()));
+        assert_eq!(post(&client, "deflate", deflate(body.as_bytes())).1, Some(body.into()));
+        assert_eq!(post(&client, "identity", body.into()).1, Some(body.into()));
+
+        // Codings are listed in the order they were applied.
+        let twice = deflate(&gzip(body.as_bytes()));
+        assert_eq!(post(&client, "gzip, deflate", twice).1, Some(body.into()));
+    }
+
+    #[test]
+    #[cfg(feature = "brotli_decompression")]
+    fn decodes_brotli_body() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        let body = "hello, brotli!";
+        assert_eq!(post(&client, "br", brotli(body.as_bytes())), (Status::Ok, Some(body.into())));
+
+        let twice = brotli(&gzip(body.as_bytes()));
+        assert_eq!(post(&client, "gzip, br", twice).1, Some(body.into()));
+    }
+
+    #[test]
+    fn unknown_encoding() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        assert_eq!(post(&client, "compress", b"abc".to_vec()).0, Status::UnsupportedMediaType);
+    }
+
+    #[test]
+    fn decompressed_limit() {
+        let client = Client::new(rocket(Limits::default().limit("decompressed", 1024))).unwrap();
+        let fits = "a".repeat(1024);
+        assert_eq!(post(&client, "gzip", gzip(fits.as_bytes())).1, Some(fits));
+
+        let (status, _) = post(&client, "gzip", gzip("a".repeat(1025).as_bytes()));
+        assert_eq!(status, Status::PayloadTooLarge);
+    }
+}
@@ -0,0 +1,94 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use std::io::{self, Read};
+
+use rocket::Data;
+use rocket::response::Debug;
+
+type Body = Result<String, Debug<io::Error>>;
+
+#[post("/default", data = "<data>")]
+fn default(data: Data) -> Body {
+    let mut string = String::new();
+    data.open().read_to_string(&mut string)?;
+    Ok(string)
+}
+
+#[post("/small", data = "<data>", limit = "8 B")]
+fn small(data: Data) -> Body {
+    default(data)
+}
+
+#[post("/large", data = "<data>", limit = "1 KiB")]
+fn large(data: Data) -> Body {
+    default(data)
+}
+
+#[post("/small", data = "<data>", rank = 2)]
+fn fallback(data: Data) -> Body {
+    default(data)
+}
+
+mod route_body_limits_tests {
+    use rocket::Rocket;
+    use rocket::config::{Environment, Config, Limits};
+    use rocket::local::Client;
+    use rocket::http::{Header, Status};
+
+    fn rocket(limits: Limits) -> Rocket {
+        let config = Config::build(Environment::Development).limits(limits).unwrap();
+        rocket::custom(config).mount("/", routes![super::default, super::small, super::large])
+    }
+
+    fn post(client: &Client, uri: &'static str, body: &str) -> (Status, Option<String>) {
+        let length = Header::new("Content-Length", body.len().to_string());
+        let mut response = client.post(uri).header(length).body(body).dispatch();
+        (response.status(), response.body_string())
+    }
+
+    #[test]
+    fn route_limit() {
+        let client = Client::new(rocket(Limits::default())).unwrap();
+        assert_eq!(post(&client, "/small", "12345678"), (Status::Ok, Some("12345678".into())));
+        assert_eq!(post(&client, "/small", "123456789").0, Status::PayloadTooLarge);
+        assert_eq!(post(&client, "/large", "123456789").0, Status::Ok);
+        assert_eq!(post(&client, "/default", &"a".repeat(4096)).0, Status::Ok);
+    }
+
+    #[test]
+    fn data_limit() {
+        let client = Client::new(rocket(Limits::default().limit("data", 4))).unwrap();
+        assert_eq!(post(&client, "/default", "1234").0, Status::Ok);
+        assert_eq!(post(&client, "/default", "12345").0, Status::PayloadTooLarge);
+
+        // A route's own limit overrides the `data` limit.
+        assert_eq!(post(&client, "/small", "12345678").0, Status::Ok);
+        assert_eq!(post(&client, "/large", &"a".repeat(1024)).0, Status::Ok);
+        assert_eq!(post(&client, "/large", &"a".repeat(1025)).0, Status::PayloadTooLarge);
+    }
+
+    #[test]
+    fn rejects_before_guards() {
+        // The failure isn't forwarded to the next matching route.
+        let client = Client::new(rocket(Limits::default()).mount("/", routes![super::fallback]))
+            .unwrap();
+
+        assert_eq!(post(&client, "/small", "123456789").0, Status::PayloadTooLarge);
+    }
+
+    #[test]
+    fn without_content_length() {
+        // The body is capped at the limit as it's read instead.
+        let client = Client::new(rocket(Limits::default().limit("data", 4))).unwrap();
+        let mut response = client.post("/small").body("12345678").dispatch();
+        assert_eq!(response.body_string(), Some("12345678".into()));
+
+        let response = client.post("/small").body("123456789").dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+
+        let response = client.post("/default").body("12345").dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+    }
+}
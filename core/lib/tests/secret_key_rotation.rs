@@ -0,0 +1,98 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "private-cookies")]
+extern crate rocket;
+
+#[cfg(feature = "private-cookies")]
+mod secret_key_rotation_tests {
+    use rocket::http::{Cookie, Cookies};
+
+    #[get("/private")]
+    fn get_private(mut cookies: Cookies) -> Option<String> {
+        cookies.get_private("name").map(|cookie| cookie.value().into())
+    }
+
+    #[get("/private/set")]
+    fn set_private(mut cookies: Cookies) {
+        cookies.add_private(Cookie::new("name", "value"));
+    }
+
+    #[get("/signed")]
+    fn get_signed(mut cookies: Cookies) -> Option<String> {
+        cookies.get_signed("name").map(|cookie| cookie.value().into())
+    }
+
+    #[get("/signed/set")]
+    fn set_signed(mut cookies: Cookies) {
+        cookies.add_signed(Cookie::new("name", "value"));
+    }
+
+    mod tests {
+        use super::*;
+        use rocket::local::Client;
+        use rocket::config::{Config, Environment};
+        use rocket::http::Status;
+
+        const OLD: &str = "TpUiXK2d/v5DFxJnWL12suJKPExKR8h9zd/o+E7SU+0=";
+        const NEW: &str = "jTyprDberFUiUFsJ3vcb1XKsYHWNBRvWAnXTlbTgGFU=";
+
+        fn client(primary: &str, retired: &[&str]) -> Client {
+            let config = Config::build(Environment::Development)
+                .secret_keys(primary, retired.iter().cloned())
+                .unwrap();
+
+            let routes = routes![get_private, set_private, get_signed, set_signed];
+            Client::untracked(rocket::custom(config).mount("/", routes)).unwrap()
+        }
+
+        fn issued_cookie(client: &Client, uri: &str) -> Cookie<'static> {
+            client.get(uri.to_string()).dispatch()
+                .cookies().into_iter()
+                .find(|c| c.name() == "name")
+                .map(|c| c.into_owned())
+                .expect("cookie was issued")
+        }
+
+        fn check_rotation(kind: &str) {
+            let old_cookie = issued_cookie(&client(OLD, &[]), &format!("/{}/set", kind));
+
+            // Without the old key, the cookie is rejected.
+            let new_only = client(NEW, &[]);
+            let response = new_only.get(format!("/{}", kind))
+                .cookie(old_cookie.clone())
+                .dispatch();
+            assert_eq!(response.status(), Status::NotFound);
+
+            // With the old key retired, the cookie is accepted and reissued.
+            let rotated = client(NEW, &[OLD]);
+            let mut response = rotated.get(format!("/{}", kind))
+                .cookie(old_cookie.clone())
+                .dispatch();
+            assert_eq!(response.body_string(), Some("value".into()));
+
+            let reissued = response.cookies().into_iter()
+                .find(|c| c.name() == "name")
+                .expect("cookie was reissued");
+            assert_ne!(reissued.value(), old_cookie.value());
+
+            // The reissued cookie is readable with only the new key and isn't
+            // reissued again.
+            let mut response = new_only.get(format!("/{}", kind))
+                .cookie(Cookie::new("name", reissued.value().to_string()))
+                .dispatch();
+            assert_eq!(response.body_string(), Some("value".into()));
+            assert_eq!(response.headers().get_one("Set-Cookie"), None);
+        }
+
+        #[test]
+        fn private_cookies_are_reissued_with_primary_key() {
+            check_rotation("private");
+        }
+
+        #[test]
+        fn signed_cookies_are_reissued_with_primary_key() {
+            check_rotation("signed");
+        }
+    }
+}
@@ -0,0 +1,71 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use]
+#[cfg(feature = "private-cookies")]
+extern crate rocket;
+
+#[cfg(feature = "private-cookies")]
+mod signed_cookies_test {
+    use rocket::http::{Cookie, Cookies};
+
+    #[get("/")]
+    fn return_signed_cookie(mut cookies: Cookies) -> Option<String> {
+        cookies.get_signed("cookie_name").map(|cookie| cookie.value().into())
+    }
+
+    #[get("/set")]
+    fn set_signed_cookie(mut cookies: Cookies) {
+        cookies.add_signed(Cookie::new("cookie_name", "cookie_value"));
+    }
+
+    mod tests {
+        use super::*;
+        use rocket::local::Client;
+        use rocket::http::Status;
+
+        fn client() -> Client {
+            let routes = routes![return_signed_cookie, set_signed_cookie];
+            Client::new(rocket::ignite().mount("/", routes)).unwrap()
+        }
+
+        #[test]
+        fn signed_cookie_is_returned() {
+            let client = client();
+            let req = client.get("/").signed_cookie(Cookie::new("cookie_name", "cookie_value"));
+            let mut response = req.dispatch();
+
+            assert_eq!(response.body_string(), Some("cookie_value".into()));
+            assert_eq!(response.headers().get_one("Set-Cookie"), None);
+        }
+
+        #[test]
+        fn unsigned_or_private_cookie_is_not_returned() {
+            let client = client();
+            let req = client.get("/").cookie(Cookie::new("cookie_name", "cookie_value"));
+            assert_eq!(req.dispatch().status(), Status::NotFound);
+
+            let req = client.get("/").private_cookie(Cookie::new("cookie_name", "cookie_value"));
+            assert_eq!(req.dispatch().status(), Status::NotFound);
+        }
+
+        #[test]
+        fn signed_cookie_value_is_readable_but_not_forgeable() {
+            let client = client();
+            let response = client.get("/set").dispatch();
+            let cookie = response.cookies().into_iter()
+                .find(|c| c.name() == "cookie_name")
+                .expect("signed cookie set");
+
+            assert!(cookie.value().ends_with("cookie_value"));
+            assert_ne!(cookie.value(), "cookie_value");
+            assert_eq!(cookie.http_only(), None);
+
+            let req = client.get("/").cookie(cookie.clone());
+            assert_eq!(req.dispatch().body_string(), Some("cookie_value".into()));
+
+            let forged = cookie.value().replace("cookie_value", "forged_value");
+            let req = client.get("/").cookie(Cookie::new("cookie_name", forged));
+            assert_eq!(req.dispatch().status(), Status::NotFound);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#![feature(proc_macro_hygiene)]
+
+#[macro_use] extern crate rocket;
+
+use rocket::request::{self, Request, FromRequest};
+use rocket::outcome::IntoOutcome;
+use rocket::http::Status;
+use rocket::websocket::{WebSocket, Upgrade};
+
+struct User;
+
+impl<'a, 'r> FromRequest<'a, 'r> for User {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<User, ()> {
+        request.cookies().get("user").map(|_| User).into_outcome((Status::Unauthorized, ()))
+    }
+}
+
+#[get("/echo")]
+fn echo(_user: User, ws: WebSocket) -> Upgrade<'static> {
+    ws.on_upgrade(|connection| {
+        while let Some(message) = connection.recv()? {
+            connection.send(message)?;
+        }
+
+        Ok(())
+    })
+}
+
+#[get("/echo", rank = 2)]
+fn plain() -> &'static str {
+    "not a WebSocket"
+}
+
+mod websocket_tests {
+    use rocket::local::{Client, LocalRequest};
+    use rocket::http::{Cookie, Header, Status};
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![super::echo, super::plain])).unwrap()
+    }
+
+    fn handshake<'c>(client: &'c Client, version: &'static str) -> LocalRequest<'c> {
+        client.get("/echo")
+            .header(Header::new("Upgrade", "websocket"))
+            .header(Header::new("Connection", "keep-alive, Upgrade"))
+            .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .header(Header::new("Sec-WebSocket-Version", version))
+    }
+
+    #[test]
+    fn accepts_handshake() {
+        let client = client();
+        let mut response = handshake(&client, "13").cookie(Cookie::new("user", "bob")).dispatch();
+        assert_eq!(response.status(), Status::SwitchingProtocols);
+        assert_eq!(response.headers().get_one("Upgrade"), Some("websocket"));
+        assert_eq!(response.headers().get_one("Connection"), Some("Upgrade"));
+        assert_eq!(response.headers().get_one("Sec-WebSocket-Accept"),
+            Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+        assert!(response.body().is_none());
+    }
+
+    #[test]
+    fn guards_run_before_upgrade() {
+        let client = client();
+        let response = handshake(&client, "13").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert!(response.headers().get_one("Sec-WebSocket-Accept").is_none());
+    }
+
+    #[test]
+    fn rejects_bad_handshakes() {
+        let client = client();
+        let response = handshake(&client, "8").cookie(Cookie::new("user", "bob")).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.get("/echo")
+            .cookie(Cookie::new("user", "bob"))
+            .header(Header::new("Upgrade", "websocket"))
+            .header(Header::new("Connection", "Upgrade"))
+            .header(Header::new("Sec-WebSocket-Key", "short"))
+            .header(Header::new("Sec-WebSocket-Version", "13"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn forwards_plain_requests() {
+        let client = client();
+        let mut response = client.get("/echo").cookie(Cookie::new("user", "bob")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("not a WebSocket".into()));
+    }
+}
@@ -65,6 +65,7 @@ if [ "$1" = "--contrib" ]; then
   FEATURES=(
     json
     msgpack
+    serde_form
     tera_templates
     handlebars_templates
     serve
@@ -81,6 +82,10 @@ if [ "$1" = "--contrib" ]; then
     memcache_pool
     brotli_compression
     gzip_compression
+    sessions
+    sqlite_sessions
+    csrf
+    etag
   )
 
   pushd "${CONTRIB_LIB_ROOT}" > /dev/null 2>&1
@@ -98,6 +103,9 @@ elif [ "$1" = "--core" ]; then
   FEATURES=(
     private-cookies # this is already tested since it's the default feature
     tls
+    decompression
+    gzip_decompression
+    brotli_decompression
   )
 
   pushd "${CORE_LIB_ROOT}" > /dev/null 2>&1